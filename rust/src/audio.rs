@@ -0,0 +1,263 @@
+//! PipeWire audio sinks.
+//!
+//! Sinks come from `pw-dump`, which prints the whole PipeWire graph as
+//! JSON: every node with its properties and `Props` params (volumes,
+//! mute), plus the `default` metadata object naming the default sink.
+//! Unlike the text of `wpctl status`, that format doesn't change with
+//! WirePlumber releases or break on names containing `.` or `*`.
+//!
+//! Changing the default still goes through `wpctl set-default`, so
+//! WirePlumber remembers the choice.
+
+use std::fmt;
+use std::io;
+use std::process::Command;
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Node type of sinks (and every other node) in pw-dump.
+const NODE_TYPE: &str = "PipeWire:Interface:Node";
+const METADATA_TYPE: &str = "PipeWire:Interface:Metadata";
+
+/// Metadata keys naming the default sink, in order of preference: the
+/// one in use, then the one the user picked.
+const DEFAULT_SINK_KEYS: [&str; 2] = ["default.audio.sink", "default.configured.audio.sink"];
+
+#[derive(Debug)]
+pub(crate) enum AudioError {
+    Spawn(io::Error),
+    Failed(String),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::Spawn(e) => write!(f, "Failed to run pw-dump: {}", e),
+            AudioError::Failed(stderr) => write!(f, "pw-dump failed: {}", stderr),
+            AudioError::Parse(e) => write!(f, "Invalid JSON from pw-dump: {}", e),
+        }
+    }
+}
+
+impl From<AudioError> for PyErr {
+    fn from(e: AudioError) -> Self {
+        match e {
+            AudioError::Spawn(_) => PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()),
+            _ => PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// pw-dump JSON
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Object {
+    id: u32,
+    #[serde(rename = "type")]
+    kind: String,
+    info: Option<NodeInfo>,
+    props: Properties,
+    metadata: Vec<MetadataEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NodeInfo {
+    props: Properties,
+    params: Params,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Properties {
+    #[serde(rename = "node.name")]
+    node_name: String,
+    #[serde(rename = "node.description")]
+    node_description: Option<String>,
+    #[serde(rename = "node.nick")]
+    node_nick: Option<String>,
+    #[serde(rename = "media.class")]
+    media_class: String,
+    #[serde(rename = "metadata.name")]
+    metadata_name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Params {
+    #[serde(rename = "Props")]
+    props: Vec<PropsParam>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PropsParam {
+    channel_volumes: Option<Vec<f64>>,
+    channel_map: Vec<String>,
+    mute: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MetadataEntry {
+    subject: u32,
+    key: String,
+    value: Value,
+}
+
+/// Node name in a metadata value: `{"name": ...}`, or that object as a
+/// JSON string in older pw-dump versions.
+fn metadata_node_name(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(object) => object.get("name")?.as_str().map(str::to_string),
+        Value::String(json) => metadata_node_name(&serde_json::from_str(json).ok()?),
+        _ => None,
+    }
+}
+
+fn is_sink(media_class: &str) -> bool {
+    media_class.starts_with("Audio/Sink") || media_class == "Audio/Duplex"
+}
+
+/// PipeWire stores volumes as linear amplitude; like wpctl and pavucontrol,
+/// report them on the cubic scale users set them on (1.0 is 100%).
+fn cubic(linear: f64) -> f64 {
+    linear.max(0.0).cbrt()
+}
+
+// ============================================================================
+// Sinks
+// ============================================================================
+
+/// An audio output node (`media.class` "Audio/Sink" or "Audio/Duplex").
+#[derive(Debug, Clone, Serialize)]
+#[pyclass(get_all, frozen)]
+pub struct AudioSink {
+    /// Node id, as `set_default_sink` and wpctl take it.
+    pub id: u32,
+    /// `node.name`, e.g. "alsa_output.pci-0000_00_1f.3.analog-stereo".
+    pub name: String,
+    /// `node.description` (or `node.nick`, or the name), e.g. "Built-in
+    /// Audio Analog Stereo".
+    pub description: String,
+    pub media_class: String,
+    /// Average of the channel volumes; None when the node has none.
+    pub volume: Option<f64>,
+    pub channel_volumes: Vec<f64>,
+    /// Channel positions matching `channel_volumes`, e.g. ["FL", "FR"].
+    pub channels: Vec<String>,
+    pub muted: bool,
+    pub is_default: bool,
+}
+
+#[pymethods]
+impl AudioSink {
+    fn __repr__(&self) -> String {
+        format!(
+            "AudioSink(id={}, name={:?}, is_default={})",
+            self.id, self.name, self.is_default
+        )
+    }
+}
+
+/// Sinks in a pw-dump document, in graph order.
+fn parse_sinks(json: &str) -> Result<Vec<AudioSink>, serde_json::Error> {
+    let objects: Vec<Object> = serde_json::from_str(json)?;
+
+    let defaults = objects
+        .iter()
+        .filter(|o| o.kind == METADATA_TYPE && o.props.metadata_name == "default")
+        .flat_map(|o| &o.metadata)
+        .filter(|entry| entry.subject == 0);
+    let mut default_names: Vec<(usize, String)> = defaults
+        .filter_map(|entry| {
+            let rank = DEFAULT_SINK_KEYS.iter().position(|k| *k == entry.key)?;
+            Some((rank, metadata_node_name(&entry.value)?))
+        })
+        .collect();
+    default_names.sort();
+    let default_name = default_names.into_iter().next().map(|(_, name)| name);
+
+    let sinks = objects
+        .into_iter()
+        .filter(|o| o.kind == NODE_TYPE)
+        .filter_map(|o| {
+            let info = o.info?;
+            if !is_sink(&info.props.media_class) {
+                return None;
+            }
+            let props = info.props;
+            // Device nodes carry one Props entry with the channel volumes
+            let params = info
+                .params
+                .props
+                .into_iter()
+                .find(|p| p.channel_volumes.is_some())
+                .unwrap_or_default();
+            let channel_volumes: Vec<f64> = params
+                .channel_volumes
+                .unwrap_or_default()
+                .into_iter()
+                .map(cubic)
+                .collect();
+            let volume = (!channel_volumes.is_empty())
+                .then(|| channel_volumes.iter().sum::<f64>() / channel_volumes.len() as f64);
+            let description = props
+                .node_description
+                .or(props.node_nick)
+                .filter(|d| !d.is_empty())
+                .unwrap_or_else(|| props.node_name.clone());
+            Some(AudioSink {
+                id: o.id,
+                is_default: default_name.as_deref() == Some(props.node_name.as_str()),
+                name: props.node_name,
+                description,
+                media_class: props.media_class,
+                volume,
+                channel_volumes,
+                channels: params.channel_map,
+                muted: params.mute,
+            })
+        })
+        .collect();
+    Ok(sinks)
+}
+
+/// Audio sinks from a single `pw-dump` run.
+pub(crate) fn audio_sinks() -> Result<Vec<AudioSink>, AudioError> {
+    let output = Command::new("pw-dump")
+        .output()
+        .map_err(AudioError::Spawn)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AudioError::Failed(stderr.trim().to_string()));
+    }
+    parse_sinks(&String::from_utf8_lossy(&output.stdout)).map_err(AudioError::Parse)
+}
+
+// ============================================================================
+// Python API
+// ============================================================================
+
+/// Get audio sinks from PipeWire via pw-dump.
+#[pyfunction]
+pub fn get_audio_sinks(py: Python<'_>) -> PyResult<Vec<AudioSink>> {
+    Ok(py.allow_threads(audio_sinks)?)
+}
+
+/// Set the default audio sink by ID.
+#[pyfunction]
+pub fn set_default_sink(sink_id: u32) -> PyResult<bool> {
+    let output = Command::new("wpctl")
+        .args(["set-default", &sink_id.to_string()])
+        .output()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()))?;
+
+    Ok(output.status.success())
+}
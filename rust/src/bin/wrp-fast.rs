@@ -0,0 +1,5 @@
+//! Native `wrp` hot paths that start without Python; see `wrp_native::fast`.
+
+fn main() -> std::process::ExitCode {
+    wrp_native::fast::main()
+}
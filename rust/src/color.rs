@@ -0,0 +1,515 @@
+//! Color math shared by the Python side, templates and the Hue bridge.
+//!
+//! Everything works on 8-bit sRGB (`#rrggbb`). Conversions go through
+//! linear sRGB with a D65 white point: HSV/HSL are plain sRGB transforms,
+//! XYZ/xy/Lab are CIE, OKLCH is Björn Ottosson's Oklab in polar form, and
+//! HCT is Material's hue/chroma/tone (via material-colors). Results that
+//! fall outside sRGB are clamped per channel, except `from_xy` which
+//! scales the color down so its hue survives.
+//!
+//! Exposed to Python as the `wrp_native.color` submodule.
+
+use material_colors::blend;
+use material_colors::color::Argb;
+use material_colors::hct::Hct;
+use pyo3::prelude::*;
+
+/// D65 reference white, XYZ with Y = 1.
+const D65: [f64; 3] = [0.95047, 1.0, 1.08883];
+
+/// Linear sRGB to XYZ (D65).
+const RGB_TO_XYZ: [[f64; 3]; 3] = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+
+/// XYZ (D65) to linear sRGB.
+const XYZ_TO_RGB: [[f64; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+fn mul(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn linearize(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn delinearize(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// An 8-bit sRGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Parse "#rgb" or "#rrggbb" (the `#` is optional).
+    pub(crate) fn parse(hex: &str) -> Result<Self, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let invalid = || format!("Invalid hex color {:?} (expected #rrggbb or #rgb)", hex);
+        if !digits.is_ascii() {
+            return Err(invalid());
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(Self {
+                r: channel(&digits[0..2])?,
+                g: channel(&digits[2..4])?,
+                b: channel(&digits[4..6])?,
+            }),
+            3 => {
+                let short = |i: usize| channel(&digits[i..i + 1]).map(|c| c * 17);
+                Ok(Self {
+                    r: short(0)?,
+                    g: short(1)?,
+                    b: short(2)?,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    pub(crate) fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Channels from 0.0 to 1.0, rounded and clamped.
+    pub(crate) fn from_unit([r, g, b]: [f64; 3]) -> Self {
+        let channel = |v: f64| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        Self {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+
+    pub(crate) fn unit(self) -> [f64; 3] {
+        [self.r, self.g, self.b].map(|c| f64::from(c) / 255.0)
+    }
+
+    pub(crate) fn linear(self) -> [f64; 3] {
+        self.unit().map(linearize)
+    }
+
+    pub(crate) fn from_linear(rgb: [f64; 3]) -> Self {
+        Self::from_unit(rgb.map(|c| delinearize(c.clamp(0.0, 1.0))))
+    }
+
+    /// Hue in degrees, saturation and value from 0.0 to 1.0.
+    pub(crate) fn to_hsv(self) -> (f64, f64, f64) {
+        let [r, g, b] = self.unit();
+        let max = r.max(g).max(b);
+        let d = max - r.min(g).min(b);
+        let s = if max == 0.0 { 0.0 } else { d / max };
+        (hue_of(r, g, b, max, d), s, max)
+    }
+
+    pub(crate) fn from_hsv(h: f64, s: f64, v: f64) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let c = v * s;
+        Self::from_chroma(h, c, v - c)
+    }
+
+    /// Hue in degrees, saturation and lightness from 0.0 to 1.0.
+    pub(crate) fn to_hsl(self) -> (f64, f64, f64) {
+        let [r, g, b] = self.unit();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d == 0.0 {
+            0.0
+        } else {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (hue_of(r, g, b, max, d), s, l)
+    }
+
+    pub(crate) fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_chroma(h, c, l - c / 2.0)
+    }
+
+    /// Shared tail of HSV/HSL: chroma `c` at hue `h`, plus `m` on every channel.
+    fn from_chroma(h: f64, c: f64, m: f64) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        Self::from_unit([r + m, g + m, b + m])
+    }
+
+    /// CIE XYZ (D65) with Y from 0.0 to 1.0.
+    pub(crate) fn to_xyz(self) -> [f64; 3] {
+        mul(&RGB_TO_XYZ, self.linear())
+    }
+
+    pub(crate) fn from_xyz(xyz: [f64; 3]) -> Self {
+        Self::from_linear(mul(&XYZ_TO_RGB, xyz))
+    }
+
+    /// CIE xy chromaticity. Black has none and reports the white point.
+    pub(crate) fn to_xy(self) -> (f64, f64) {
+        let [x, y, z] = self.to_xyz();
+        let sum = x + y + z;
+        if sum == 0.0 {
+            let [wx, wy, wz] = D65;
+            return (wx / (wx + wy + wz), wy / (wx + wy + wz));
+        }
+        (x / sum, y / sum)
+    }
+
+    /// The color at chromaticity `(x, y)` with luminance `brightness`.
+    ///
+    /// Out-of-gamut results are scaled down until the brightest channel
+    /// fits, which keeps their hue where per-channel clamping wouldn't.
+    pub(crate) fn from_xy(x: f64, y: f64, brightness: f64) -> Self {
+        if y <= 0.0 {
+            return Self { r: 0, g: 0, b: 0 };
+        }
+        let big_y = brightness.clamp(0.0, 1.0);
+        let xyz = [big_y / y * x, big_y, big_y / y * (1.0 - x - y)];
+        let rgb = mul(&XYZ_TO_RGB, xyz).map(|c| c.max(0.0));
+        let max = rgb[0].max(rgb[1]).max(rgb[2]);
+        let rgb = if max > 1.0 { rgb.map(|c| c / max) } else { rgb };
+        Self::from_linear(rgb)
+    }
+
+    /// CIE L*a*b* (D65), L from 0 to 100.
+    pub(crate) fn to_lab(self) -> (f64, f64, f64) {
+        let f = |t: f64| {
+            if t > 216.0 / 24389.0 {
+                t.cbrt()
+            } else {
+                (24389.0 / 27.0 * t + 16.0) / 116.0
+            }
+        };
+        let xyz = self.to_xyz();
+        let [fx, fy, fz] = [0, 1, 2].map(|i| f(xyz[i] / D65[i]));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    pub(crate) fn from_lab(l: f64, a: f64, b: f64) -> Self {
+        let f_inv = |t: f64| {
+            if t.powi(3) > 216.0 / 24389.0 {
+                t.powi(3)
+            } else {
+                (116.0 * t - 16.0) * 27.0 / 24389.0
+            }
+        };
+        let fy = (l + 16.0) / 116.0;
+        let f = [fy + a / 500.0, fy, fy - b / 200.0];
+        Self::from_xyz([0, 1, 2].map(|i| f_inv(f[i]) * D65[i]))
+    }
+
+    /// OKLab as [lightness, a, b]; Euclidean distances in it track
+    /// perceived differences.
+    pub(crate) fn to_oklab(self) -> [f64; 3] {
+        let [r, g, b] = self.linear();
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        [
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        ]
+    }
+
+    /// OKLCH: lightness from 0.0 to 1.0, chroma (about 0.0 to 0.4) and hue
+    /// in degrees.
+    pub(crate) fn to_oklch(self) -> (f64, f64, f64) {
+        let [lightness, a, b] = self.to_oklab();
+        let chroma = a.hypot(b);
+        // Grays have no meaningful hue; keep them at 0 instead of noise
+        let hue = if chroma < 1e-6 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        };
+        (lightness, chroma, hue)
+    }
+
+    pub(crate) fn from_oklch(lightness: f64, chroma: f64, hue: f64) -> Self {
+        let (a, b) = (
+            chroma * hue.to_radians().cos(),
+            chroma * hue.to_radians().sin(),
+        );
+        let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        Self::from_linear([
+            4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        ])
+    }
+
+    fn argb(self) -> Argb {
+        Argb::new(255, self.r, self.g, self.b)
+    }
+
+    fn hct(self) -> Hct {
+        Hct::new(self.argb())
+    }
+
+    fn from_argb(argb: Argb) -> Self {
+        Self {
+            r: argb.red,
+            g: argb.green,
+            b: argb.blue,
+        }
+    }
+
+    /// Material HCT: hue in degrees, chroma, and tone from 0 to 100.
+    pub(crate) fn to_hct(self) -> (f64, f64, f64) {
+        let hct = self.hct();
+        (hct.get_hue(), hct.get_chroma(), hct.get_tone())
+    }
+
+    /// The closest sRGB color; chroma is reduced if it doesn't fit.
+    pub(crate) fn from_hct(hue: f64, chroma: f64, tone: f64) -> Self {
+        Self::from_argb(Argb::from(Hct::from(hue, chroma, tone)))
+    }
+
+    /// The same hue and chroma at another HCT tone (0 to 100).
+    pub(crate) fn with_tone(self, tone: f64) -> Self {
+        let mut hct = self.hct();
+        hct.set_tone(tone.clamp(0.0, 100.0));
+        Self::from_argb(Argb::from(hct))
+    }
+
+    /// Raise the HCT tone by `amount` (negative darkens).
+    pub(crate) fn lighten(self, amount: f64) -> Self {
+        self.with_tone(self.hct().get_tone() + amount)
+    }
+
+    /// Rotate the hue up to 15 degrees toward `source`'s, keeping tone
+    /// (Material's harmonization, as used for custom colors).
+    pub(crate) fn harmonize(self, source: Rgb) -> Self {
+        Self::from_argb(blend::harmonize(self.argb(), source.argb()))
+    }
+
+    /// Approximate color of a black body at `kelvin` (Tanner Helland's fit,
+    /// good from 1000 K to 40000 K).
+    pub(crate) fn from_kelvin(kelvin: f64) -> Self {
+        let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+        let (r, g, b) = if t <= 66.0 {
+            let b = if t <= 19.0 {
+                0.0
+            } else {
+                138.5177312231 * (t - 10.0).ln() - 305.0447927307
+            };
+            (255.0, 99.4708025861 * t.ln() - 161.1195681661, b)
+        } else {
+            (
+                329.698727446 * (t - 60.0).powf(-0.1332047592),
+                288.1221695283 * (t - 60.0).powf(-0.0755148492),
+                255.0,
+            )
+        };
+        Self::from_unit([r, g, b].map(|c| c / 255.0))
+    }
+
+    /// WCAG 2 relative luminance, 0.0 for black to 1.0 for white.
+    pub(crate) fn luminance(self) -> f64 {
+        let [r, g, b] = self.linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG 2 contrast ratio, from 1.0 (same luminance) to 21.0.
+    pub(crate) fn contrast(self, other: Self) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+/// Hue in degrees of an RGB color with maximum channel `max` and chroma `d`.
+fn hue_of(r: f64, g: f64, b: f64, max: f64, d: f64) -> f64 {
+    if d == 0.0 {
+        return 0.0;
+    }
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    h * 60.0
+}
+
+// ============================================================================
+// Python API (wrp_native.color)
+// ============================================================================
+
+fn parse(color: &str) -> PyResult<Rgb> {
+    Rgb::parse(color).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Parse "#rrggbb" or "#rgb" into (r, g, b) from 0 to 255.
+#[pyfunction]
+pub fn parse_hex(color: &str) -> PyResult<(u8, u8, u8)> {
+    let rgb = parse(color)?;
+    Ok((rgb.r, rgb.g, rgb.b))
+}
+
+/// Format (r, g, b) from 0 to 255 as "#rrggbb".
+#[pyfunction]
+pub fn to_hex(r: u8, g: u8, b: u8) -> String {
+    Rgb { r, g, b }.to_hex()
+}
+
+#[pyfunction]
+pub fn to_hsv(color: &str) -> PyResult<(f64, f64, f64)> {
+    Ok(parse(color)?.to_hsv())
+}
+
+#[pyfunction]
+pub fn from_hsv(h: f64, s: f64, v: f64) -> String {
+    Rgb::from_hsv(h, s, v).to_hex()
+}
+
+#[pyfunction]
+pub fn to_hsl(color: &str) -> PyResult<(f64, f64, f64)> {
+    Ok(parse(color)?.to_hsl())
+}
+
+#[pyfunction]
+pub fn from_hsl(h: f64, s: f64, l: f64) -> String {
+    Rgb::from_hsl(h, s, l).to_hex()
+}
+
+#[pyfunction]
+pub fn to_xyz(color: &str) -> PyResult<(f64, f64, f64)> {
+    let [x, y, z] = parse(color)?.to_xyz();
+    Ok((x, y, z))
+}
+
+#[pyfunction]
+pub fn from_xyz(x: f64, y: f64, z: f64) -> String {
+    Rgb::from_xyz([x, y, z]).to_hex()
+}
+
+#[pyfunction]
+pub fn to_xy(color: &str) -> PyResult<(f64, f64)> {
+    Ok(parse(color)?.to_xy())
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, brightness=1.0))]
+pub fn from_xy(x: f64, y: f64, brightness: f64) -> String {
+    Rgb::from_xy(x, y, brightness).to_hex()
+}
+
+#[pyfunction]
+pub fn to_lab(color: &str) -> PyResult<(f64, f64, f64)> {
+    Ok(parse(color)?.to_lab())
+}
+
+#[pyfunction]
+pub fn from_lab(l: f64, a: f64, b: f64) -> String {
+    Rgb::from_lab(l, a, b).to_hex()
+}
+
+#[pyfunction]
+pub fn to_oklch(color: &str) -> PyResult<(f64, f64, f64)> {
+    Ok(parse(color)?.to_oklch())
+}
+
+#[pyfunction]
+pub fn from_oklch(l: f64, c: f64, h: f64) -> String {
+    Rgb::from_oklch(l, c, h).to_hex()
+}
+
+#[pyfunction]
+pub fn to_hct(color: &str) -> PyResult<(f64, f64, f64)> {
+    Ok(parse(color)?.to_hct())
+}
+
+#[pyfunction]
+pub fn from_hct(hue: f64, chroma: f64, tone: f64) -> String {
+    Rgb::from_hct(hue, chroma, tone).to_hex()
+}
+
+#[pyfunction]
+pub fn from_kelvin(kelvin: f64) -> String {
+    Rgb::from_kelvin(kelvin).to_hex()
+}
+
+#[pyfunction]
+pub fn relative_luminance(color: &str) -> PyResult<f64> {
+    Ok(parse(color)?.luminance())
+}
+
+#[pyfunction]
+pub fn contrast_ratio(foreground: &str, background: &str) -> PyResult<f64> {
+    Ok(parse(foreground)?.contrast(parse(background)?))
+}
+
+#[pyfunction]
+pub fn with_tone(color: &str, tone: f64) -> PyResult<String> {
+    Ok(parse(color)?.with_tone(tone).to_hex())
+}
+
+#[pyfunction]
+pub fn lighten(color: &str, amount: f64) -> PyResult<String> {
+    Ok(parse(color)?.lighten(amount).to_hex())
+}
+
+#[pyfunction]
+pub fn darken(color: &str, amount: f64) -> PyResult<String> {
+    Ok(parse(color)?.lighten(-amount).to_hex())
+}
+
+/// Build the `color` submodule.
+pub(crate) fn module(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+    let m = PyModule::new(py, "color")?;
+    m.add_function(wrap_pyfunction!(parse_hex, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_hex, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_hsv, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_hsv, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_hsl, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_hsl, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_xyz, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_xyz, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_xy, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_xy, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_lab, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_lab, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_oklch, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_oklch, &m)?)?;
+    m.add_function(wrap_pyfunction!(to_hct, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_hct, &m)?)?;
+    m.add_function(wrap_pyfunction!(from_kelvin, &m)?)?;
+    m.add_function(wrap_pyfunction!(relative_luminance, &m)?)?;
+    m.add_function(wrap_pyfunction!(contrast_ratio, &m)?)?;
+    m.add_function(wrap_pyfunction!(with_tone, &m)?)?;
+    m.add_function(wrap_pyfunction!(lighten, &m)?)?;
+    m.add_function(wrap_pyfunction!(darken, &m)?)?;
+    Ok(m)
+}
@@ -0,0 +1,932 @@
+//! Matugen color caching.
+//!
+//! `~/.cache/matuwrap/colors.json` holds the palettes of recently used
+//! wallpapers (bounded LRU), keyed by a hash of the image content so the
+//! same image hits whatever its path or mtime. Each wallpaper has one
+//! palette per scheme options (type, contrast, source index), and each
+//! palette keeps both the dark and light scheme from a single matugen run,
+//! so switching modes never regenerates anything. Without matugen,
+//! palettes are generated natively (see `material`).
+//!
+//! To avoid reading the image on every lookup, the hash last seen at each
+//! path is indexed with the file's stamp (nanosecond mtime, size, device
+//! and inode); any change to the stamp, including re-pointing a symlink,
+//! re-hashes the file. `verify` re-hashes even when the stamp matches.
+//!
+//! Writes go through a temp file and `rename`, under an advisory `flock`
+//! on `colors.json.lock`. A miss keeps the lock while generating, so when
+//! several shells start at once only one runs matugen and the rest pick up
+//! its result. A cache that fails to parse is moved to `colors.json.corrupt`
+//! and reported with `ColorCacheCorruptWarning`.
+
+use pyo3::create_exception;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::material::{self, Mode, SchemeOptions};
+
+create_exception!(
+    wrp_native,
+    ColorCacheCorruptWarning,
+    pyo3::exceptions::PyUserWarning,
+    "Warned when the color cache file can't be parsed and is discarded."
+);
+
+/// Color role -> "#rrggbb".
+pub(crate) type Colors = HashMap<String, String>;
+
+/// Dark and light schemes generated from the same source color.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[pyclass(frozen)]
+pub struct Palette {
+    pub(crate) dark: Colors,
+    pub(crate) light: Colors,
+}
+
+impl Palette {
+    pub(crate) fn colors(&self, mode: Mode) -> &Colors {
+        match mode {
+            Mode::Dark => &self.dark,
+            Mode::Light => &self.light,
+        }
+    }
+
+    pub(crate) fn into_colors(self, mode: Mode) -> Colors {
+        match mode {
+            Mode::Dark => self.dark,
+            Mode::Light => self.light,
+        }
+    }
+
+    fn scheme_for(&self, mode: Mode) -> ColorScheme {
+        ColorScheme {
+            mode,
+            colors: self.colors(mode).clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl Palette {
+    #[getter]
+    fn dark(&self) -> ColorScheme {
+        self.scheme_for(Mode::Dark)
+    }
+
+    #[getter]
+    fn light(&self) -> ColorScheme {
+        self.scheme_for(Mode::Light)
+    }
+
+    /// The scheme for `mode`, or for the saved mode if None.
+    #[pyo3(signature = (mode=None))]
+    fn scheme(&self, mode: Option<&str>) -> PyResult<ColorScheme> {
+        Ok(self.scheme_for(material::mode_from_arg(mode)?))
+    }
+
+    /// One role's color in `mode` (default: the saved mode).
+    #[pyo3(signature = (role, mode=None))]
+    fn get(&self, role: &str, mode: Option<&str>) -> PyResult<Option<String>> {
+        let mode = material::mode_from_arg(mode)?;
+        Ok(self.colors(mode).get(role).cloned())
+    }
+
+    /// All role names, sorted.
+    fn roles(&self) -> Vec<String> {
+        let mut roles: Vec<String> = self.dark.keys().chain(self.light.keys()).cloned().collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Palette(dark.primary={:?}, light.primary={:?})",
+            self.dark.get("primary").map_or("?", String::as_str),
+            self.light.get("primary").map_or("?", String::as_str)
+        )
+    }
+}
+
+/// One scheme of a palette. Roles are attributes (`scheme.primary`) and
+/// items (`scheme["on_surface"]`).
+#[derive(Debug, Clone)]
+#[pyclass(frozen)]
+pub struct ColorScheme {
+    mode: Mode,
+    colors: Colors,
+}
+
+#[pymethods]
+impl ColorScheme {
+    /// "dark" or "light".
+    #[getter]
+    fn mode(&self) -> &'static str {
+        self.mode.name()
+    }
+
+    fn __getattr__(&self, role: &str) -> PyResult<String> {
+        self.colors.get(role).cloned().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyAttributeError, _>(format!("No color role {:?}", role))
+        })
+    }
+
+    fn __getitem__(&self, role: &str) -> PyResult<String> {
+        self.colors
+            .get(role)
+            .cloned()
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(role.to_string()))
+    }
+
+    fn __contains__(&self, role: &str) -> bool {
+        self.colors.contains_key(role)
+    }
+
+    fn __len__(&self) -> usize {
+        self.colors.len()
+    }
+
+    #[pyo3(signature = (role, default=None))]
+    fn get(&self, role: &str, default: Option<String>) -> Option<String> {
+        self.colors.get(role).cloned().or(default)
+    }
+
+    /// Role names, sorted.
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.colors.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    fn to_dict(&self) -> Colors {
+        self.colors.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ColorScheme(mode={:?}, roles={})",
+            self.mode.name(),
+            self.colors.len()
+        )
+    }
+}
+
+fn get_cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("matuwrap").join("colors.json"))
+}
+
+fn get_lock_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("matuwrap").join("colors.json.lock"))
+}
+
+fn get_mode_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("matuwrap").join("color_mode"))
+}
+
+fn get_scheme_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("matuwrap").join("scheme.json"))
+}
+
+fn content_hash(path: &str) -> io::Result<String> {
+    Ok(format!("{:016x}", xxh3_64(&fs::read(path)?)))
+}
+
+/// Numbers the temporary files of `write_atomic`, so threads writing the
+/// same file don't share one.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replace a file atomically: readers see the old or the new contents,
+/// never a partial write. Creates missing parent directories.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let tmp_path = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Wallpapers kept in the cache before the least recently used is dropped.
+const MAX_ENTRIES: usize = 32;
+
+#[derive(Default, Serialize, Deserialize)]
+struct ColorCache {
+    /// Most recently used first.
+    entries: Vec<CacheEntry>,
+    /// Last seen content hash per wallpaper path, so unchanged files
+    /// aren't re-read on every lookup.
+    paths: HashMap<String, PathStamp>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// xxh3 of the image bytes.
+    hash: String,
+    /// Path the image was last used from.
+    wallpaper_path: String,
+    /// Palettes keyed by `SchemeOptions::cache_key()`.
+    palettes: HashMap<String, Palette>,
+}
+
+/// Identifies a file's current version without reading it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FileStamp {
+    /// Modification time in nanoseconds since the epoch.
+    mtime_ns: i64,
+    size: u64,
+    dev: u64,
+    inode: u64,
+}
+
+impl FileStamp {
+    /// Stamp of the file `path` points to (symlinks are followed).
+    pub(crate) fn of(path: &str) -> io::Result<Self> {
+        let meta = fs::metadata(path)?;
+        Ok(Self {
+            mtime_ns: meta
+                .mtime()
+                .saturating_mul(1_000_000_000)
+                .saturating_add(meta.mtime_nsec()),
+            size: meta.size(),
+            dev: meta.dev(),
+            inode: meta.ino(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PathStamp {
+    #[serde(flatten)]
+    stamp: FileStamp,
+    hash: String,
+}
+
+/// Whether a palette is cached for a wallpaper and scheme options.
+#[pyclass(eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// Cached for this image content.
+    Hit,
+    /// Cached for what this path held before, but the file has changed.
+    Stale,
+    /// Nothing cached for this image.
+    Miss,
+    /// The cache file exists but can't be parsed.
+    Corrupt,
+}
+
+/// A wallpaper's content hash, as resolved against the path index.
+struct Resolved {
+    hash: String,
+    stamp: FileStamp,
+    /// Hash indexed for this path before, if the content changed since.
+    previous: Option<String>,
+    /// The index didn't have this path at this stamp.
+    reindexed: bool,
+}
+
+impl ColorCache {
+    /// Ok(None) if there is no cache yet; Err with the reason if it's corrupt.
+    fn load() -> Result<Option<Self>, String> {
+        let Some(cache_path) = get_cache_path() else {
+            return Ok(None);
+        };
+        let data = match fs::read_to_string(&cache_path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Like `load`, but moves a corrupt cache aside and starts empty.
+    /// The second value is why the cache was discarded.
+    fn load_or_reset() -> (Self, Option<String>) {
+        match Self::load() {
+            Ok(cache) => (cache.unwrap_or_default(), None),
+            Err(e) => {
+                if let Some(cache_path) = get_cache_path() {
+                    let _ = fs::rename(&cache_path, cache_path.with_extension("json.corrupt"));
+                }
+                (Self::default(), Some(e))
+            }
+        }
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let cache_path = get_cache_path()
+            .ok_or_else(|| io::Error::other("Could not determine cache directory"))?;
+        write_atomic(&cache_path, &serde_json::to_vec(self)?)
+    }
+
+    /// Content hash of a wallpaper, reusing the indexed one while the
+    /// file's stamp is unchanged, unless `verify` is set.
+    fn resolve(&self, wallpaper_path: &str, verify: bool) -> io::Result<Resolved> {
+        let stamp = FileStamp::of(wallpaper_path)?;
+        let indexed = self.paths.get(wallpaper_path);
+        let current = indexed.filter(|p| p.stamp == stamp);
+        let hash = match current {
+            Some(p) if !verify => p.hash.clone(),
+            _ => content_hash(wallpaper_path)?,
+        };
+        Ok(Resolved {
+            previous: indexed.map(|p| p.hash.clone()).filter(|h| *h != hash),
+            reindexed: current.is_none_or(|p| p.hash != hash),
+            hash,
+            stamp,
+        })
+    }
+
+    /// Record `resolved` in the path index.
+    fn index(&mut self, wallpaper_path: &str, resolved: &Resolved) {
+        self.paths.insert(
+            wallpaper_path.to_string(),
+            PathStamp {
+                stamp: resolved.stamp.clone(),
+                hash: resolved.hash.clone(),
+            },
+        );
+    }
+
+    /// Whether `resolved` is indexed and already the most recently used
+    /// entry, i.e. a hit wouldn't change the cache.
+    fn is_current(&self, wallpaper_path: &str, resolved: &Resolved) -> bool {
+        !resolved.reindexed
+            && self
+                .entries
+                .first()
+                .is_some_and(|e| e.hash == resolved.hash && e.wallpaper_path == wallpaper_path)
+    }
+
+    fn palette(&self, hash: &str, key: &str) -> Option<&Palette> {
+        self.entries
+            .iter()
+            .find(|e| e.hash == hash)
+            .and_then(|e| e.palettes.get(key))
+    }
+
+    fn status(&self, resolved: &Resolved, key: &str) -> CacheStatus {
+        if self.palette(&resolved.hash, key).is_some() {
+            CacheStatus::Hit
+        } else if resolved
+            .previous
+            .as_ref()
+            .is_some_and(|h| self.palette(h, key).is_some())
+        {
+            CacheStatus::Stale
+        } else {
+            CacheStatus::Miss
+        }
+    }
+
+    /// Mark the entry for `hash` as most recently used from `wallpaper_path`,
+    /// creating it if needed.
+    fn touch(&mut self, hash: &str, wallpaper_path: &str) {
+        let entry = match self.entries.iter().position(|e| e.hash == hash) {
+            Some(index) => self.entries.remove(index),
+            None => CacheEntry {
+                hash: hash.to_string(),
+                wallpaper_path: String::new(),
+                palettes: HashMap::new(),
+            },
+        };
+        self.entries.insert(
+            0,
+            CacheEntry {
+                wallpaper_path: wallpaper_path.to_string(),
+                ..entry
+            },
+        );
+    }
+
+    /// Keep the `max_entries` most recently used wallpapers. Returns how
+    /// many were dropped.
+    fn prune(&mut self, max_entries: usize) -> usize {
+        let removed = self.entries.len().saturating_sub(max_entries);
+        self.entries.truncate(max_entries);
+        let entries = &self.entries;
+        self.paths
+            .retain(|_, stamp| entries.iter().any(|e| e.hash == stamp.hash));
+        removed
+    }
+}
+
+/// Run matugen once; its JSON carries both the dark and light scheme.
+fn run_matugen(wallpaper_path: &str, options: &SchemeOptions) -> Option<Palette> {
+    // Resolve symlinks so matugen gets a real file path
+    let resolved = std::fs::canonicalize(wallpaper_path).ok()?;
+    let resolved_str = resolved.to_str()?;
+
+    let output = Command::new("matugen")
+        .args([
+            "image",
+            resolved_str,
+            "-t",
+            options.scheme_type.name(),
+            "-m",
+            "dark",
+            "--contrast",
+            &options.contrast.to_string(),
+            "-j",
+            "hex",
+            "--source-color-index",
+            &options.source_index.to_string(),
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Parse JSON (matugen outputs on stdout)
+    let json: serde_json::Value = serde_json::from_str(&stdout).ok()?;
+    let colors_obj = json.get("colors")?;
+    let mut dark = HashMap::new();
+    let mut light = HashMap::new();
+
+    let variant = |val: &serde_json::Value, name: &str| {
+        val.get(name)
+            .and_then(|v| v.get("color"))
+            .and_then(|c| c.as_str())
+            .map(|s| s.to_string())
+    };
+
+    if let Some(obj) = colors_obj.as_object() {
+        for (key, val) in obj {
+            // matugen 4.0.0 structure: {color_name: {dark: {color: "#hex"}, light: {...}}}
+            let default = variant(val, "default");
+            let dark_color = variant(val, "dark").or_else(|| default.clone());
+            let light_color = variant(val, "light").or(default);
+
+            if let Some(hex) = dark_color.clone().or_else(|| light_color.clone()) {
+                dark.insert(key.clone(), hex);
+            }
+            if let Some(hex) = light_color.or(dark_color) {
+                light.insert(key.clone(), hex);
+            }
+        }
+    }
+
+    Some(Palette { dark, light })
+}
+
+/// Exclusive advisory lock on the color cache, released on drop.
+struct CacheLock {
+    _file: fs::File,
+}
+
+impl CacheLock {
+    /// Block until no other process holds the lock.
+    fn acquire() -> io::Result<Self> {
+        let lock_path = get_lock_path()
+            .ok_or_else(|| io::Error::other("Could not determine cache directory"))?;
+        if let Some(dir) = lock_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        loop {
+            // SAFETY: flock(2) on a descriptor owned by `file`.
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(Self { _file: file });
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+}
+
+/// Outcome of `cached_palette`.
+pub(crate) struct Cached {
+    /// None if both matugen and native extraction failed.
+    pub palette: Option<Palette>,
+    /// Why an unreadable cache file was discarded, if one was.
+    pub corrupt: Option<String>,
+}
+
+/// Cached palette for a wallpaper, generating and caching it on a miss.
+pub(crate) fn cached_palette(
+    wallpaper_path: &str,
+    options: &SchemeOptions,
+    verify: bool,
+) -> Cached {
+    let key = options.cache_key();
+    let miss = |corrupt| Cached {
+        palette: None,
+        corrupt,
+    };
+
+    // Try cache first; a hit that changes nothing needs no lock
+    let cache = ColorCache::load().ok().flatten().unwrap_or_default();
+    let Ok(resolved) = cache.resolve(wallpaper_path, verify) else {
+        return miss(None);
+    };
+    if let Some(palette) = cache.palette(&resolved.hash, &key)
+        && cache.is_current(wallpaper_path, &resolved)
+    {
+        return Cached {
+            palette: Some(palette.clone()),
+            corrupt: None,
+        };
+    }
+
+    // Anything else rewrites the cache. Hold the lock until done so
+    // concurrent callers wait for this result instead of regenerating it.
+    // If locking fails, carry on unlocked: writes are still atomic.
+    let _lock = CacheLock::acquire().ok();
+    let (mut cache, corrupt) = ColorCache::load_or_reset();
+    cache.index(wallpaper_path, &resolved);
+    cache.touch(&resolved.hash, wallpaper_path);
+
+    // Another process may have generated it while we waited
+    let palette = match cache.entries[0].palettes.get(&key) {
+        Some(palette) => palette.clone(),
+        None => {
+            // Run matugen, or extract natively if it's missing or fails
+            let Some(palette) = run_matugen(wallpaper_path, options)
+                .or_else(|| material::extract(Path::new(wallpaper_path), options).ok())
+            else {
+                return miss(corrupt);
+            };
+            cache.entries[0].palettes.insert(key, palette.clone());
+            cache.prune(MAX_ENTRIES);
+            palette
+        }
+    };
+
+    // Save to cache
+    let _ = cache.write();
+    Cached {
+        palette: Some(palette),
+        corrupt,
+    }
+}
+
+/// Surface a discarded cache as a Python warning.
+pub(crate) fn warn_corrupt(py: Python<'_>, cached: &Cached) -> PyResult<()> {
+    if let Some(reason) = &cached.corrupt {
+        let message =
+            CString::new(format!("Discarded corrupt color cache: {}", reason)).unwrap_or_default();
+        PyErr::warn(py, &py.get_type::<ColorCacheCorruptWarning>(), &message, 1)?;
+    }
+    Ok(())
+}
+
+/// Saved light/dark preference; dark unless set otherwise.
+pub(crate) fn preferred_mode() -> Mode {
+    get_mode_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| Mode::from_name(s.trim()))
+        .unwrap_or(Mode::Dark)
+}
+
+fn save_mode(mode: Mode) -> PyResult<()> {
+    let path = get_mode_path().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Could not determine config directory")
+    })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, mode.name())?;
+    Ok(())
+}
+
+/// Scheme options as saved in `scheme.json`; missing fields keep the
+/// defaults.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedScheme {
+    scheme_type: String,
+    contrast: f64,
+    source_index: usize,
+}
+
+impl Default for SavedScheme {
+    fn default() -> Self {
+        SchemeOptions::default().into()
+    }
+}
+
+impl From<SchemeOptions> for SavedScheme {
+    fn from(options: SchemeOptions) -> Self {
+        Self {
+            scheme_type: options.scheme_type.name().to_string(),
+            contrast: options.contrast,
+            source_index: options.source_index,
+        }
+    }
+}
+
+/// Saved scheme options; tonal spot at standard contrast unless set
+/// otherwise. A file that doesn't parse or holds invalid options is ignored.
+pub(crate) fn preferred_options() -> SchemeOptions {
+    get_scheme_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str::<SavedScheme>(&s).ok())
+        .and_then(|saved| {
+            SchemeOptions::new(&saved.scheme_type, saved.contrast, saved.source_index).ok()
+        })
+        .unwrap_or_default()
+}
+
+fn save_options(options: SchemeOptions) -> PyResult<()> {
+    let path = get_scheme_path().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Could not determine config directory")
+    })?;
+    let json = serde_json::to_string_pretty(&SavedScheme::from(options))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    write_atomic(&path, json.as_bytes())?;
+    Ok(())
+}
+
+/// Get matugen colors with caching.
+/// Returns a dict of color_name -> hex_value for one mode.
+/// Falls back to native extraction when matugen is unavailable.
+/// Returns None if both fail (caller should use defaults).
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, scheme_type=None, mode=None, contrast=None, source_index=None, verify=false))]
+pub fn get_cached_colors(
+    py: Python<'_>,
+    wallpaper_path: &str,
+    scheme_type: Option<&str>,
+    mode: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    verify: bool,
+) -> PyResult<Option<PyObject>> {
+    let mode = material::mode_from_arg(mode)?;
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+
+    let cached = py.allow_threads(|| cached_palette(wallpaper_path, &options, verify));
+    warn_corrupt(py, &cached)?;
+    let Some(palette) = cached.palette else {
+        return Ok(None);
+    };
+
+    // Return as Python dict
+    let dict = PyDict::new(py);
+    for (k, v) in palette.into_colors(mode) {
+        dict.set_item(k, v)?;
+    }
+    Ok(Some(dict.into()))
+}
+
+/// Get both the dark and light scheme for a wallpaper, with caching.
+/// Returns None if both matugen and native extraction fail.
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, scheme_type=None, contrast=None, source_index=None, verify=false))]
+pub fn get_cached_palette(
+    py: Python<'_>,
+    wallpaper_path: &str,
+    scheme_type: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    verify: bool,
+) -> PyResult<Option<Palette>> {
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+    let cached = py.allow_threads(|| cached_palette(wallpaper_path, &options, verify));
+    warn_corrupt(py, &cached)?;
+    Ok(cached.palette)
+}
+
+/// Result of `cache_lookup`.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct CacheLookup {
+    pub status: CacheStatus,
+    /// Content hash of the wallpaper (None if the cache is corrupt).
+    pub hash: Option<String>,
+    /// Human-readable explanation of the status.
+    pub reason: String,
+}
+
+#[pymethods]
+impl CacheLookup {
+    fn __repr__(&self) -> String {
+        format!(
+            "CacheLookup(status={:?}, hash={:?}, reason={:?})",
+            self.status, self.hash, self.reason
+        )
+    }
+}
+
+/// Check whether a palette is cached for a wallpaper, without generating
+/// or writing anything.
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, scheme_type=None, contrast=None, source_index=None, verify=false))]
+pub fn cache_lookup(
+    wallpaper_path: &str,
+    scheme_type: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    verify: bool,
+) -> PyResult<CacheLookup> {
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+    let cache = match ColorCache::load() {
+        Ok(cache) => cache.unwrap_or_default(),
+        Err(e) => {
+            return Ok(CacheLookup {
+                status: CacheStatus::Corrupt,
+                hash: None,
+                reason: format!("Cache file is corrupt: {}", e),
+            });
+        }
+    };
+    let resolved = cache.resolve(wallpaper_path, verify)?;
+    let status = cache.status(&resolved, &options.cache_key());
+    let reason = match (status, &resolved.previous) {
+        (CacheStatus::Hit, Some(_)) => "File changed, but its new content is cached".to_string(),
+        (CacheStatus::Hit, None) => "Cached for this image".to_string(),
+        (CacheStatus::Stale, Some(previous)) => format!(
+            "File changed since it was cached (was {}, now {})",
+            previous, resolved.hash
+        ),
+        _ if cache.entries.iter().any(|e| e.hash == resolved.hash) => {
+            "Nothing cached for these scheme options".to_string()
+        }
+        _ => "Nothing cached for this image".to_string(),
+    };
+    Ok(CacheLookup {
+        status,
+        hash: Some(resolved.hash),
+        reason,
+    })
+}
+
+/// Invalidate the color cache.
+#[pyfunction]
+pub fn invalidate_color_cache() -> PyResult<()> {
+    if let Some(cache_path) = get_cache_path() {
+        let _ = fs::remove_file(cache_path);
+    }
+    Ok(())
+}
+
+/// What the color cache currently holds.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct CacheStats {
+    /// Path of the cache file.
+    pub path: Option<String>,
+    /// Size of the cache file in bytes (0 if it doesn't exist).
+    pub size_bytes: u64,
+    /// Number of cached wallpapers.
+    pub entries: usize,
+    /// Wallpapers kept before the least recently used is dropped.
+    pub max_entries: usize,
+    /// Number of cached palettes across all wallpapers.
+    pub palettes: usize,
+    /// Last used path of each cached wallpaper, most recent first.
+    pub wallpapers: Vec<String>,
+    /// Why the cache file can't be parsed, if it can't.
+    pub corrupt: Option<String>,
+}
+
+#[pymethods]
+impl CacheStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "CacheStats(entries={}, palettes={}, size_bytes={}, corrupt={:?})",
+            self.entries, self.palettes, self.size_bytes, self.corrupt
+        )
+    }
+}
+
+/// Summarize the color cache.
+#[pyfunction]
+pub fn cache_stats() -> CacheStats {
+    let path = get_cache_path();
+    let size_bytes = path
+        .as_ref()
+        .and_then(|p| fs::metadata(p).ok())
+        .map_or(0, |m| m.len());
+    let (cache, corrupt) = match ColorCache::load() {
+        Ok(cache) => (cache.unwrap_or_default(), None),
+        Err(e) => (ColorCache::default(), Some(e)),
+    };
+    CacheStats {
+        path: path.map(|p| p.to_string_lossy().into_owned()),
+        size_bytes,
+        entries: cache.entries.len(),
+        max_entries: MAX_ENTRIES,
+        palettes: cache.entries.iter().map(|e| e.palettes.len()).sum(),
+        wallpapers: cache
+            .entries
+            .iter()
+            .map(|e| e.wallpaper_path.clone())
+            .collect(),
+        corrupt,
+    }
+}
+
+/// Drop all but the `max_entries` most recently used wallpapers from the
+/// color cache. Returns how many were dropped.
+#[pyfunction]
+pub fn prune_cache(max_entries: usize) -> PyResult<usize> {
+    let _lock = CacheLock::acquire()?;
+    let mut cache = match ColorCache::load() {
+        Ok(Some(cache)) => cache,
+        Ok(None) => return Ok(0),
+        Err(e) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Color cache is corrupt: {}",
+                e
+            )));
+        }
+    };
+    let removed = cache.prune(max_entries);
+    if removed > 0 {
+        cache.write()?;
+    }
+    Ok(removed)
+}
+
+/// Get the saved color mode ("dark" or "light").
+#[pyfunction]
+pub fn get_color_mode() -> &'static str {
+    preferred_mode().name()
+}
+
+/// Set the color mode used when `mode` isn't passed explicitly.
+#[pyfunction]
+pub fn set_color_mode(mode: &str) -> PyResult<()> {
+    let mode = Mode::from_name(mode).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown mode {:?} (expected \"dark\" or \"light\")",
+            mode
+        ))
+    })?;
+    save_mode(mode)
+}
+
+/// Get the saved scheme options as a dict with `scheme_type`, `contrast`
+/// and `source_index`.
+#[pyfunction]
+pub fn get_scheme_options(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let options = preferred_options();
+    let dict = PyDict::new(py);
+    dict.set_item("scheme_type", options.scheme_type.name())?;
+    dict.set_item("contrast", options.contrast)?;
+    dict.set_item("source_index", options.source_index)?;
+    Ok(dict.into())
+}
+
+/// Set the scheme options used when they aren't passed explicitly.
+/// Options left as None keep their saved value.
+#[pyfunction]
+#[pyo3(signature = (scheme_type=None, contrast=None, source_index=None))]
+pub fn set_scheme_options(
+    scheme_type: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+) -> PyResult<()> {
+    save_options(material::options_from_args(
+        scheme_type,
+        contrast,
+        source_index,
+    )?)
+}
+
+/// Switch between dark and light. Returns the new mode.
+#[pyfunction]
+pub fn toggle_color_mode() -> PyResult<&'static str> {
+    let mode = preferred_mode().toggled();
+    save_mode(mode)?;
+    Ok(mode.name())
+}
@@ -0,0 +1,301 @@
+//! WCAG contrast audit of a palette.
+//!
+//! Checks the foreground/background role pairs that get drawn on top of
+//! each other, both by Material components and by matuwrap's own Rich
+//! theme (which prints muted text in `outline_variant` on `surface`).
+//! Pairs are text pairs, so AA means 4.5:1 and AAA 7:1.
+//!
+//! With `fix`, failing foregrounds are moved along HCT tone, keeping hue
+//! and chroma, by the smallest step that makes every pair they're part of
+//! pass. When no tone gets there the one with the best worst-case ratio is
+//! kept and the pair is counted as unresolved.
+
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::color::Rgb;
+use crate::colors::{self, Colors, Palette};
+use crate::material::{self, Mode};
+
+const AA: f64 = 4.5;
+const AAA: f64 = 7.0;
+
+/// (foreground, background) roles to check. Pairs with a role missing
+/// from the palette are skipped.
+const PAIRS: &[(&str, &str)] = &[
+    ("on_surface", "surface"),
+    ("on_surface", "surface_dim"),
+    ("on_surface", "surface_bright"),
+    ("on_surface", "surface_container_lowest"),
+    ("on_surface", "surface_container_low"),
+    ("on_surface", "surface_container"),
+    ("on_surface", "surface_container_high"),
+    ("on_surface", "surface_container_highest"),
+    ("on_surface_variant", "surface"),
+    ("on_surface_variant", "surface_variant"),
+    ("on_surface_variant", "surface_container"),
+    ("on_surface_variant", "surface_container_highest"),
+    ("on_background", "background"),
+    ("inverse_on_surface", "inverse_surface"),
+    ("inverse_primary", "inverse_surface"),
+    ("on_primary", "primary"),
+    ("on_primary_container", "primary_container"),
+    ("on_primary_fixed", "primary_fixed"),
+    ("on_primary_fixed_variant", "primary_fixed"),
+    ("on_secondary", "secondary"),
+    ("on_secondary_container", "secondary_container"),
+    ("on_secondary_fixed", "secondary_fixed"),
+    ("on_secondary_fixed_variant", "secondary_fixed"),
+    ("on_tertiary", "tertiary"),
+    ("on_tertiary_container", "tertiary_container"),
+    ("on_tertiary_fixed", "tertiary_fixed"),
+    ("on_tertiary_fixed_variant", "tertiary_fixed"),
+    ("on_error", "error"),
+    ("on_error_container", "error_container"),
+    // Roles the CLI theme prints as text on the terminal background
+    ("primary", "surface"),
+    ("secondary", "surface"),
+    ("tertiary", "surface"),
+    ("error", "surface"),
+    ("outline", "surface"),
+    ("outline_variant", "surface"),
+];
+
+/// One foreground/background pair.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct ContrastPair {
+    pub foreground: String,
+    pub background: String,
+    pub foreground_color: String,
+    pub background_color: String,
+    pub ratio: f64,
+    /// At least 4.5:1.
+    pub aa: bool,
+    /// At least 7:1.
+    pub aaa: bool,
+    /// Meets the level the audit asked for.
+    pub passes: bool,
+    /// Ratio after `fix` changed either role, otherwise None.
+    pub adjusted_ratio: Option<f64>,
+}
+
+#[pymethods]
+impl ContrastPair {
+    fn __repr__(&self) -> String {
+        format!(
+            "ContrastPair({:?} on {:?}, ratio={:.2}, passes={})",
+            self.foreground, self.background, self.ratio, self.passes
+        )
+    }
+}
+
+/// Result of a contrast audit.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct ContrastReport {
+    pub mode: String,
+    /// "AA" or "AAA".
+    pub level: String,
+    /// Minimum ratio for `level`.
+    pub required: f64,
+    pub pairs: Vec<ContrastPair>,
+    /// Pairs below `level`.
+    pub failures: usize,
+    /// Role -> new "#rrggbb" for roles `fix` changed (empty without `fix`).
+    pub adjusted: Colors,
+    /// Pairs still below `level` after `fix` (equal to `failures` without it).
+    pub unresolved: usize,
+}
+
+#[pymethods]
+impl ContrastReport {
+    /// Pairs below the audited level.
+    fn failing(&self) -> Vec<ContrastPair> {
+        self.pairs.iter().filter(|p| !p.passes).cloned().collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ContrastReport(mode={:?}, level={:?}, pairs={}, failures={}, adjusted={}, unresolved={})",
+            self.mode,
+            self.level,
+            self.pairs.len(),
+            self.failures,
+            self.adjusted.len(),
+            self.unresolved
+        )
+    }
+}
+
+/// Parse "aa" or "aaa" (any case) into the level name and its ratio.
+fn parse_level(level: &str) -> Result<(&'static str, f64), String> {
+    match level.to_ascii_lowercase().as_str() {
+        "aa" => Ok(("AA", AA)),
+        "aaa" => Ok(("AAA", AAA)),
+        _ => Err(format!(
+            "Unknown contrast level {:?} (expected \"aa\" or \"aaa\")",
+            level
+        )),
+    }
+}
+
+/// The pairs present in `colors`, as parsed colors.
+fn present_pairs(colors: &Colors) -> Vec<(&'static str, &'static str, Rgb, Rgb)> {
+    let get = |role: &str| colors.get(role).and_then(|hex| Rgb::parse(hex).ok());
+    PAIRS
+        .iter()
+        .filter_map(|&(fg, bg)| Some((fg, bg, get(fg)?, get(bg)?)))
+        .collect()
+}
+
+/// The closest tone of `color` (same HCT hue and chroma) that reaches
+/// `required` against every one of `partners`, or failing that, the tone
+/// with the best worst-case ratio.
+fn adjust_tone(color: Rgb, partners: &[Rgb], required: f64) -> Rgb {
+    let worst = |c: Rgb| {
+        partners
+            .iter()
+            .map(|p| c.contrast(*p))
+            .fold(f64::INFINITY, f64::min)
+    };
+    let (hue, chroma, tone) = color.to_hct();
+    let (mut best, mut best_ratio) = (color, worst(color));
+    for step in 1..=100 {
+        for t in [tone + f64::from(step), tone - f64::from(step)] {
+            if !(0.0..=100.0).contains(&t) {
+                continue;
+            }
+            let candidate = Rgb::from_hct(hue, chroma, t);
+            let ratio = worst(candidate);
+            if ratio >= required {
+                return candidate;
+            }
+            if ratio > best_ratio {
+                (best, best_ratio) = (candidate, ratio);
+            }
+        }
+    }
+    best
+}
+
+/// Audit one scheme's colors, optionally adjusting failing foregrounds.
+pub(crate) fn audit(
+    colors: &Colors,
+    mode: Mode,
+    level: &str,
+    fix: bool,
+) -> Result<ContrastReport, String> {
+    let (level, required) = parse_level(level)?;
+    let pairs = present_pairs(colors);
+
+    // Roles are adjusted one at a time against all their current partners
+    // (contrast is symmetric, so a role that is a background elsewhere
+    // keeps those pairs passing too)
+    let mut current: HashMap<&str, Rgb> = HashMap::new();
+    for &(fg, bg, fg_rgb, bg_rgb) in &pairs {
+        current.insert(fg, fg_rgb);
+        current.insert(bg, bg_rgb);
+    }
+    let mut adjusted: HashMap<&str, Rgb> = HashMap::new();
+    if fix {
+        for &(fg, bg, _, _) in &pairs {
+            if adjusted.contains_key(fg) || current[fg].contrast(current[bg]) >= required {
+                continue;
+            }
+            let partners: Vec<Rgb> = pairs
+                .iter()
+                .filter_map(|&(f, b, _, _)| match (f == fg, b == fg) {
+                    (true, _) => Some(current[b]),
+                    (_, true) => Some(current[f]),
+                    _ => None,
+                })
+                .collect();
+            let new = adjust_tone(current[fg], &partners, required);
+            if new != current[fg] {
+                current.insert(fg, new);
+                adjusted.insert(fg, new);
+            }
+        }
+    }
+
+    let pairs: Vec<ContrastPair> = pairs
+        .iter()
+        .map(|&(fg, bg, fg_rgb, bg_rgb)| {
+            let ratio = fg_rgb.contrast(bg_rgb);
+            let changed = adjusted.contains_key(fg) || adjusted.contains_key(bg);
+            ContrastPair {
+                foreground: fg.to_string(),
+                background: bg.to_string(),
+                foreground_color: fg_rgb.to_hex(),
+                background_color: bg_rgb.to_hex(),
+                ratio,
+                aa: ratio >= AA,
+                aaa: ratio >= AAA,
+                passes: ratio >= required,
+                adjusted_ratio: changed.then(|| current[fg].contrast(current[bg])),
+            }
+        })
+        .collect();
+    let failures = pairs.iter().filter(|p| !p.passes).count();
+    let unresolved = pairs
+        .iter()
+        .filter(|p| p.adjusted_ratio.unwrap_or(p.ratio) < required)
+        .count();
+    Ok(ContrastReport {
+        mode: mode.name().to_string(),
+        level: level.to_string(),
+        required,
+        pairs,
+        failures,
+        adjusted: adjusted
+            .into_iter()
+            .map(|(role, rgb)| (role.to_string(), rgb.to_hex()))
+            .collect(),
+        unresolved,
+    })
+}
+
+fn to_py_err(e: String) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(e)
+}
+
+/// Audit a palette's scheme for `mode` (defaults to the saved mode).
+#[pyfunction]
+#[pyo3(signature = (palette, mode=None, level="aa", fix=false))]
+pub fn audit_palette(
+    palette: PyRef<'_, Palette>,
+    mode: Option<&str>,
+    level: &str,
+    fix: bool,
+) -> PyResult<ContrastReport> {
+    let mode = material::mode_from_arg(mode)?;
+    audit(palette.colors(mode), mode, level, fix).map_err(to_py_err)
+}
+
+/// Audit the cached palette of a wallpaper, generating it if needed.
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, scheme_type=None, mode=None, contrast=None, source_index=None, level="aa", fix=false))]
+#[allow(clippy::too_many_arguments)]
+pub fn audit_contrast(
+    py: Python<'_>,
+    wallpaper_path: &str,
+    scheme_type: Option<&str>,
+    mode: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    level: &str,
+    fix: bool,
+) -> PyResult<ContrastReport> {
+    let mode = material::mode_from_arg(mode)?;
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+    let cached = py.allow_threads(|| colors::cached_palette(wallpaper_path, &options, false));
+    colors::warn_corrupt(py, &cached)?;
+    let palette = cached.palette.ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Could not generate colors for {}",
+            wallpaper_path
+        ))
+    })?;
+    audit(palette.colors(mode), mode, level, fix).map_err(to_py_err)
+}
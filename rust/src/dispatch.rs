@@ -0,0 +1,186 @@
+//! Hyprland dispatchers with reply validation.
+//!
+//! Hyprland answers every `dispatch` with `ok` or a human-readable error.
+//! `dispatch()` checks the reply and raises `HyprlandDispatchError` with
+//! that message; the `dispatch_*` helpers build common argument strings.
+
+use pyo3::create_exception;
+use pyo3::prelude::*;
+
+use crate::ipc::{HyprlandClient, Timeouts, reply_error};
+
+create_exception!(
+    wrp_native,
+    HyprlandDispatchError,
+    pyo3::exceptions::PyRuntimeError,
+    "Raised when Hyprland rejects a dispatcher or its arguments."
+);
+
+/// Workspace argument: numeric ID or any Hyprland workspace selector
+/// (`name:web`, `special:scratch`, `+1`, `e-1`, ...).
+#[derive(FromPyObject)]
+pub(crate) enum WorkspaceArg {
+    Id(i64),
+    Selector(String),
+}
+
+impl WorkspaceArg {
+    fn to_arg(&self) -> String {
+        match self {
+            WorkspaceArg::Id(id) => id.to_string(),
+            WorkspaceArg::Selector(s) => s.clone(),
+        }
+    }
+}
+
+/// Build the `dispatch NAME ARGS` request.
+fn dispatch_request(name: &str, args: &[String]) -> PyResult<String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) || name.contains(';') {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid dispatcher name: {:?}",
+            name
+        )));
+    }
+    let args = args.join(" ");
+    Ok(if args.is_empty() {
+        format!("dispatch {}", name)
+    } else {
+        format!("dispatch {} {}", name, args)
+    })
+}
+
+/// Run a dispatcher on `client` and check Hyprland's reply.
+pub(crate) fn run_dispatch(
+    py: Python<'_>,
+    client: &HyprlandClient,
+    name: &str,
+    args: &[String],
+) -> PyResult<()> {
+    let request = dispatch_request(name, args)?;
+    let reply = client.request(py, &request)?;
+    match reply_error(&request, &reply, false) {
+        Some(msg) if msg.is_empty() => Err(HyprlandDispatchError::new_err(format!(
+            "{}: empty reply from Hyprland",
+            name
+        ))),
+        Some(msg) => Err(HyprlandDispatchError::new_err(format!("{}: {}", name, msg))),
+        None => Ok(()),
+    }
+}
+
+fn dispatch_on(
+    py: Python<'_>,
+    instance: Option<&str>,
+    name: &str,
+    args: &[String],
+) -> PyResult<()> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    run_dispatch(py, &client, name, args)
+}
+
+/// Run a Hyprland dispatcher, raising HyprlandDispatchError unless it replies `ok`.
+#[pyfunction]
+#[pyo3(signature = (name, args=Vec::new(), instance=None))]
+pub fn dispatch(
+    py: Python<'_>,
+    name: &str,
+    args: Vec<String>,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    dispatch_on(py, instance, name, &args)
+}
+
+/// Switch to a workspace.
+#[pyfunction]
+#[pyo3(signature = (workspace, instance=None))]
+pub fn dispatch_workspace(
+    py: Python<'_>,
+    workspace: WorkspaceArg,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    dispatch_on(py, instance, "workspace", &[workspace.to_arg()])
+}
+
+/// Move a window (default: the focused one) to a workspace.
+/// With `silent=True` focus stays on the current workspace.
+#[pyfunction]
+#[pyo3(signature = (workspace, window=None, silent=false, instance=None))]
+pub fn dispatch_move_to_workspace(
+    py: Python<'_>,
+    workspace: WorkspaceArg,
+    window: Option<&str>,
+    silent: bool,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    let name = if silent {
+        "movetoworkspacesilent"
+    } else {
+        "movetoworkspace"
+    };
+    let mut arg = workspace.to_arg();
+    if let Some(window) = window {
+        arg = format!("{},{}", arg, window);
+    }
+    dispatch_on(py, instance, name, &[arg])
+}
+
+/// Focus a monitor by name, ID or direction (`l`, `r`, `+1`, ...).
+#[pyfunction]
+#[pyo3(signature = (monitor, instance=None))]
+pub fn dispatch_focus_monitor(
+    py: Python<'_>,
+    monitor: &str,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    dispatch_on(py, instance, "focusmonitor", &[monitor.to_string()])
+}
+
+/// Set DPMS on (`True`), off (`False`) or toggle it (`None`),
+/// for one monitor or all of them.
+#[pyfunction]
+#[pyo3(signature = (enabled=None, monitor=None, instance=None))]
+pub fn dispatch_dpms(
+    py: Python<'_>,
+    enabled: Option<bool>,
+    monitor: Option<&str>,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    let state = match enabled {
+        Some(true) => "on",
+        Some(false) => "off",
+        None => "toggle",
+    };
+    let mut args = vec![state.to_string()];
+    args.extend(monitor.map(str::to_string));
+    dispatch_on(py, instance, "dpms", &args)
+}
+
+/// Launch a command through Hyprland, optionally with window rules
+/// (e.g. `rules="workspace 3 silent"`).
+#[pyfunction]
+#[pyo3(signature = (command, rules=None, instance=None))]
+pub fn dispatch_exec(
+    py: Python<'_>,
+    command: &str,
+    rules: Option<&str>,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    let arg = match rules {
+        Some(rules) => format!("[{}] {}", rules, command),
+        None => command.to_string(),
+    };
+    dispatch_on(py, instance, "exec", &[arg])
+}
+
+/// Toggle floating for a window (default: the focused one).
+#[pyfunction]
+#[pyo3(signature = (window=None, instance=None))]
+pub fn dispatch_toggle_floating(
+    py: Python<'_>,
+    window: Option<&str>,
+    instance: Option<&str>,
+) -> PyResult<()> {
+    let args: Vec<String> = window.map(str::to_string).into_iter().collect();
+    dispatch_on(py, instance, "togglefloating", &args)
+}
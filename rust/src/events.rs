@@ -0,0 +1,475 @@
+//! Hyprland event stream via `.socket2.sock`.
+//!
+//! Hyprland broadcasts one `EVENT>>DATA` line per state change. Lines are
+//! parsed into `HyprEvent` variants; unknown events are passed through as
+//! `HyprEvent.Other` so newer compositors never break iteration.
+
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant};
+
+use crate::ipc::hypr_socket_path;
+
+/// How long a blocked read waits before re-checking for Python signals.
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A single Hyprland event.
+#[derive(Debug, Clone)]
+#[pyclass(frozen)]
+pub enum HyprEvent {
+    Workspace {
+        name: String,
+    },
+    WorkspaceV2 {
+        id: i64,
+        name: String,
+    },
+    FocusedMonitor {
+        monitor: String,
+        workspace: String,
+    },
+    ActiveWindow {
+        window_class: String,
+        title: String,
+    },
+    ActiveWindowV2 {
+        address: String,
+    },
+    Fullscreen {
+        enabled: bool,
+    },
+    MonitorAdded {
+        name: String,
+    },
+    MonitorAddedV2 {
+        id: i64,
+        name: String,
+        description: String,
+    },
+    MonitorRemoved {
+        name: String,
+    },
+    MonitorRemovedV2 {
+        id: i64,
+        name: String,
+        description: String,
+    },
+    CreateWorkspace {
+        name: String,
+    },
+    DestroyWorkspace {
+        name: String,
+    },
+    MoveWorkspace {
+        workspace: String,
+        monitor: String,
+    },
+    RenameWorkspace {
+        id: i64,
+        name: String,
+    },
+    ActiveSpecial {
+        workspace: String,
+        monitor: String,
+    },
+    ActiveLayout {
+        keyboard: String,
+        layout: String,
+    },
+    OpenWindow {
+        address: String,
+        workspace: String,
+        window_class: String,
+        title: String,
+    },
+    CloseWindow {
+        address: String,
+    },
+    MoveWindow {
+        address: String,
+        workspace: String,
+    },
+    WindowTitle {
+        address: String,
+    },
+    ChangeFloatingMode {
+        address: String,
+        floating: bool,
+    },
+    Urgent {
+        address: String,
+    },
+    OpenLayer {
+        namespace: String,
+    },
+    CloseLayer {
+        namespace: String,
+    },
+    Submap {
+        name: String,
+    },
+    ConfigReloaded {},
+    Other {
+        name: String,
+        data: String,
+    },
+}
+
+#[pymethods]
+impl HyprEvent {
+    /// Raw Hyprland event name (the part before `>>`).
+    #[getter]
+    fn event(&self) -> &str {
+        self.event_name()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("HyprEvent.{:?}", self)
+    }
+}
+
+impl HyprEvent {
+    pub(crate) fn event_name(&self) -> &str {
+        match self {
+            HyprEvent::Workspace { .. } => "workspace",
+            HyprEvent::WorkspaceV2 { .. } => "workspacev2",
+            HyprEvent::FocusedMonitor { .. } => "focusedmon",
+            HyprEvent::ActiveWindow { .. } => "activewindow",
+            HyprEvent::ActiveWindowV2 { .. } => "activewindowv2",
+            HyprEvent::Fullscreen { .. } => "fullscreen",
+            HyprEvent::MonitorAdded { .. } => "monitoradded",
+            HyprEvent::MonitorAddedV2 { .. } => "monitoraddedv2",
+            HyprEvent::MonitorRemoved { .. } => "monitorremoved",
+            HyprEvent::MonitorRemovedV2 { .. } => "monitorremovedv2",
+            HyprEvent::CreateWorkspace { .. } => "createworkspace",
+            HyprEvent::DestroyWorkspace { .. } => "destroyworkspace",
+            HyprEvent::MoveWorkspace { .. } => "moveworkspace",
+            HyprEvent::RenameWorkspace { .. } => "renameworkspace",
+            HyprEvent::ActiveSpecial { .. } => "activespecial",
+            HyprEvent::ActiveLayout { .. } => "activelayout",
+            HyprEvent::OpenWindow { .. } => "openwindow",
+            HyprEvent::CloseWindow { .. } => "closewindow",
+            HyprEvent::MoveWindow { .. } => "movewindow",
+            HyprEvent::WindowTitle { .. } => "windowtitle",
+            HyprEvent::ChangeFloatingMode { .. } => "changefloatingmode",
+            HyprEvent::Urgent { .. } => "urgent",
+            HyprEvent::OpenLayer { .. } => "openlayer",
+            HyprEvent::CloseLayer { .. } => "closelayer",
+            HyprEvent::Submap { .. } => "submap",
+            HyprEvent::ConfigReloaded {} => "configreloaded",
+            HyprEvent::Other { name, .. } => name,
+        }
+    }
+}
+
+/// Split event data into exactly `N` comma-separated fields.
+/// The last field keeps any remaining commas (window titles may contain them).
+fn fields<const N: usize>(data: &str) -> Option<[String; N]> {
+    let parts: Vec<String> = data.splitn(N, ',').map(str::to_string).collect();
+    parts.try_into().ok()
+}
+
+/// Parse one `EVENT>>DATA` line from socket2.
+pub(crate) fn parse_event(line: &str) -> Option<HyprEvent> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (name, data) = line.split_once(">>")?;
+
+    let event = match name {
+        "workspace" => HyprEvent::Workspace { name: data.into() },
+        "workspacev2" => {
+            let [id, name] = fields::<2>(data)?;
+            HyprEvent::WorkspaceV2 {
+                id: id.parse().ok()?,
+                name,
+            }
+        }
+        "focusedmon" => {
+            let [monitor, workspace] = fields::<2>(data)?;
+            HyprEvent::FocusedMonitor { monitor, workspace }
+        }
+        "activewindow" => {
+            let [window_class, title] = fields::<2>(data)?;
+            HyprEvent::ActiveWindow {
+                window_class,
+                title,
+            }
+        }
+        "activewindowv2" => HyprEvent::ActiveWindowV2 {
+            address: data.into(),
+        },
+        "fullscreen" => HyprEvent::Fullscreen {
+            enabled: data == "1",
+        },
+        "monitoradded" => HyprEvent::MonitorAdded { name: data.into() },
+        "monitoraddedv2" => {
+            let [id, name, description] = fields::<3>(data)?;
+            HyprEvent::MonitorAddedV2 {
+                id: id.parse().ok()?,
+                name,
+                description,
+            }
+        }
+        "monitorremoved" => HyprEvent::MonitorRemoved { name: data.into() },
+        "monitorremovedv2" => {
+            let [id, name, description] = fields::<3>(data)?;
+            HyprEvent::MonitorRemovedV2 {
+                id: id.parse().ok()?,
+                name,
+                description,
+            }
+        }
+        "createworkspace" => HyprEvent::CreateWorkspace { name: data.into() },
+        "destroyworkspace" => HyprEvent::DestroyWorkspace { name: data.into() },
+        "moveworkspace" => {
+            let [workspace, monitor] = fields::<2>(data)?;
+            HyprEvent::MoveWorkspace { workspace, monitor }
+        }
+        "renameworkspace" => {
+            let [id, name] = fields::<2>(data)?;
+            HyprEvent::RenameWorkspace {
+                id: id.parse().ok()?,
+                name,
+            }
+        }
+        "activespecial" => {
+            let [workspace, monitor] = fields::<2>(data)?;
+            HyprEvent::ActiveSpecial { workspace, monitor }
+        }
+        "activelayout" => {
+            let [keyboard, layout] = fields::<2>(data)?;
+            HyprEvent::ActiveLayout { keyboard, layout }
+        }
+        "openwindow" => {
+            let [address, workspace, window_class, title] = fields::<4>(data)?;
+            HyprEvent::OpenWindow {
+                address,
+                workspace,
+                window_class,
+                title,
+            }
+        }
+        "closewindow" => HyprEvent::CloseWindow {
+            address: data.into(),
+        },
+        "movewindow" => {
+            let [address, workspace] = fields::<2>(data)?;
+            HyprEvent::MoveWindow { address, workspace }
+        }
+        "windowtitle" => HyprEvent::WindowTitle {
+            address: data.into(),
+        },
+        "changefloatingmode" => {
+            let [address, floating] = fields::<2>(data)?;
+            HyprEvent::ChangeFloatingMode {
+                address,
+                floating: floating == "1",
+            }
+        }
+        "urgent" => HyprEvent::Urgent {
+            address: data.into(),
+        },
+        "openlayer" => HyprEvent::OpenLayer {
+            namespace: data.into(),
+        },
+        "closelayer" => HyprEvent::CloseLayer {
+            namespace: data.into(),
+        },
+        "submap" => HyprEvent::Submap { name: data.into() },
+        "configreloaded" => HyprEvent::ConfigReloaded {},
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// Parse a line, falling back to `HyprEvent::Other` for unknown or
+/// malformed events so callers still see them.
+fn parse_event_or_other(line: &str) -> Option<HyprEvent> {
+    parse_event(line).or_else(|| {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (name, data) = line.split_once(">>")?;
+        Some(HyprEvent::Other {
+            name: name.to_string(),
+            data: data.to_string(),
+        })
+    })
+}
+
+/// Blocking connection to Hyprland's event socket.
+///
+/// Used as an iterator from Python; the GIL is released while waiting.
+/// The reader is locked only while waiting for an event, so `close`
+/// (which sets a flag the wait polls) works from other threads.
+#[pyclass(frozen)]
+pub struct HyprEventStream {
+    reader: Mutex<EventReader>,
+    closed: Arc<AtomicBool>,
+}
+
+/// Reading side of an event stream, also used directly by `hotplug`.
+pub(crate) struct EventReader {
+    reader: Option<BufReader<UnixStream>>,
+    filter: Option<HashSet<String>>,
+    pending: Vec<u8>,
+    closed: Arc<AtomicBool>,
+}
+
+impl EventReader {
+    /// Connect to `.socket2.sock`, optionally keeping only the named events.
+    pub(crate) fn connect(events: Option<Vec<String>>, instance: Option<&str>) -> PyResult<Self> {
+        let socket_path = hypr_socket_path(instance, ".socket2.sock")?;
+        let stream = UnixStream::connect(&socket_path).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyConnectionError, _>(format!(
+                "Failed to connect to Hyprland event socket: {}",
+                e
+            ))
+        })?;
+        stream
+            .set_read_timeout(Some(SIGNAL_POLL_INTERVAL))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+
+        Ok(Self {
+            reader: Some(BufReader::new(stream)),
+            filter: events.map(|names| names.into_iter().collect()),
+            pending: Vec::new(),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Block until the next (matching) event, or `None` on EOF or close.
+    pub(crate) fn next_event(&mut self, py: Python<'_>) -> PyResult<Option<HyprEvent>> {
+        self.next_event_until(py, None)
+    }
+
+    /// Like `next_event`, but also returns `None` once `deadline` passes
+    /// (checked at least every `SIGNAL_POLL_INTERVAL`). `is_connected()`
+    /// tells the two cases apart.
+    pub(crate) fn next_event_until(
+        &mut self,
+        py: Python<'_>,
+        deadline: Option<Instant>,
+    ) -> PyResult<Option<HyprEvent>> {
+        loop {
+            if self.closed.load(Ordering::Relaxed) {
+                self.close();
+                return Ok(None);
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return Ok(None);
+            }
+            let Some(reader) = self.reader.as_mut() else {
+                return Ok(None);
+            };
+            let line = &mut self.pending;
+            let result = py.allow_threads(|| reader.read_until(b'\n', line));
+
+            match result {
+                Ok(0) => {
+                    self.reader = None;
+                    return Ok(None);
+                }
+                Ok(_) => {
+                    let raw = std::mem::take(&mut self.pending);
+                    let line = String::from_utf8_lossy(&raw);
+                    let Some(event) = parse_event_or_other(&line) else {
+                        continue;
+                    };
+                    let wanted = self
+                        .filter
+                        .as_ref()
+                        .is_none_or(|names| names.contains(event.event_name()));
+                    if wanted {
+                        return Ok(Some(event));
+                    }
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    // Partial line stays in `pending`; give Ctrl-C a chance.
+                    py.check_signals()?;
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => py.check_signals()?,
+                Err(e) => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.reader.is_some() && !self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Close the socket. Further reads return `None` immediately.
+    pub(crate) fn close(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.reader = None;
+        self.pending.clear();
+    }
+}
+
+#[pymethods]
+impl HyprEventStream {
+    #[new]
+    #[pyo3(signature = (events=None, instance=None))]
+    fn new(events: Option<Vec<String>>, instance: Option<&str>) -> PyResult<Self> {
+        let reader = EventReader::connect(events, instance)?;
+        Ok(Self {
+            closed: reader.closed.clone(),
+            reader: Mutex::new(reader),
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<HyprEvent>> {
+        let mut reader = match self.reader.try_lock() {
+            Ok(reader) => reader,
+            // A panic mid-read leaves at most a partial line behind
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "HyprEventStream is already waiting for an event",
+                ));
+            }
+        };
+        reader.next_event(py)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) {
+        self.close();
+    }
+
+    /// Close the underlying socket. A blocked iteration stops shortly after.
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        // Close the socket now unless a read holds it; that read closes it
+        // when it sees the flag
+        if let Ok(mut reader) = self.reader.try_lock() {
+            reader.close();
+        }
+    }
+
+    /// Whether the stream is still connected.
+    #[getter]
+    fn connected(&self) -> bool {
+        match self.reader.try_lock() {
+            Ok(reader) => reader.is_connected(),
+            Err(TryLockError::Poisoned(e)) => e.into_inner().is_connected(),
+            // Waiting for an event, so connected unless closed meanwhile
+            Err(TryLockError::WouldBlock) => !self.closed.load(Ordering::Relaxed),
+        }
+    }
+}
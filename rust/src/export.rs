@@ -0,0 +1,489 @@
+//! Palette export to terminal and app color files.
+//!
+//! Instead of a template per terminal, the current scheme can be written
+//! in a few ready-made formats (Xresources, kitty, alacritty, foot, GTK,
+//! CSS custom properties and W3C design tokens) for configs to include.
+//!
+//! Terminals also need the 16 ANSI colors, which a Material scheme doesn't
+//! have. They're derived from it: the grays come from the surface and
+//! outline roles, and red/green/yellow/blue/magenta/cyan are fixed hues
+//! harmonized toward `primary`, at tones that read well on `surface`.
+
+use pyo3::prelude::*;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::color::Rgb;
+use crate::colors::{self, Colors, Palette};
+use crate::material::{self, Mode};
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Xresources,
+    Kitty,
+    Alacritty,
+    Foot,
+    Gtk,
+    Css,
+    Json,
+}
+
+/// Format name, format, and file name used by `export_files`.
+const FORMATS: &[(&str, Format, &str)] = &[
+    ("xresources", Format::Xresources, "colors.Xresources"),
+    ("kitty", Format::Kitty, "kitty-colors.conf"),
+    ("alacritty", Format::Alacritty, "alacritty-colors.toml"),
+    ("foot", Format::Foot, "foot-colors.ini"),
+    ("gtk", Format::Gtk, "gtk-colors.css"),
+    ("css", Format::Css, "colors.css"),
+    ("json", Format::Json, "colors.tokens.json"),
+];
+
+fn parse_format(name: &str) -> Result<(Format, &'static str), String> {
+    FORMATS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, f, file)| (*f, *file))
+        .ok_or_else(|| {
+            let names: Vec<&str> = FORMATS.iter().map(|(n, _, _)| *n).collect();
+            format!(
+                "Unknown export format {:?} (expected one of: {})",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// HCT hues of ANSI red, green, yellow, blue, magenta and cyan before
+/// harmonizing.
+const ANSI_HUES: [f64; 6] = [25.0, 140.0, 95.0, 265.0, 325.0, 200.0];
+
+const ANSI_CHROMA: f64 = 48.0;
+
+/// Libadwaita named colors and the roles they take.
+const GTK_COLORS: &[(&str, &str)] = &[
+    ("accent_color", "primary"),
+    ("accent_bg_color", "primary"),
+    ("accent_fg_color", "on_primary"),
+    ("destructive_color", "error"),
+    ("destructive_bg_color", "error"),
+    ("destructive_fg_color", "on_error"),
+    ("window_bg_color", "surface"),
+    ("window_fg_color", "on_surface"),
+    ("view_bg_color", "surface"),
+    ("view_fg_color", "on_surface"),
+    ("headerbar_bg_color", "surface_container"),
+    ("headerbar_fg_color", "on_surface"),
+    ("sidebar_bg_color", "surface_container_low"),
+    ("sidebar_fg_color", "on_surface"),
+    ("card_bg_color", "surface_container_high"),
+    ("card_fg_color", "on_surface"),
+    ("popover_bg_color", "surface_container_high"),
+    ("popover_fg_color", "on_surface"),
+    ("dialog_bg_color", "surface_container_high"),
+    ("dialog_fg_color", "on_surface"),
+];
+
+/// Colors a terminal theme needs, resolved from one scheme.
+struct Terminal {
+    foreground: Rgb,
+    background: Rgb,
+    cursor: Rgb,
+    cursor_text: Rgb,
+    selection_foreground: Rgb,
+    selection_background: Rgb,
+    url: Rgb,
+    active_border: Rgb,
+    inactive_border: Rgb,
+    ansi: [Rgb; 16],
+}
+
+/// A role's color; `fallback` when the scheme doesn't have it.
+fn role(colors: &Colors, name: &str, fallback: Rgb) -> Rgb {
+    colors
+        .get(name)
+        .and_then(|hex| Rgb::parse(hex).ok())
+        .unwrap_or(fallback)
+}
+
+fn required_role(colors: &Colors, name: &str) -> Result<Rgb, String> {
+    let hex = colors
+        .get(name)
+        .ok_or_else(|| format!("Palette has no {:?} color", name))?;
+    Rgb::parse(hex)
+}
+
+/// The 16 ANSI colors for a scheme.
+pub(crate) fn ansi_colors(colors: &Colors, mode: Mode) -> Result<[Rgb; 16], String> {
+    let primary = required_role(colors, "primary")?;
+    let surface = required_role(colors, "surface")?;
+    let on_surface = required_role(colors, "on_surface")?;
+    let outline = role(colors, "outline", on_surface);
+    // Bright colors are the more emphatic ones: lighter on dark schemes,
+    // darker on light ones
+    let (black, white, bright_white, normal_tone, bright_tone) = match mode {
+        Mode::Dark => (
+            role(colors, "surface_container_highest", surface),
+            role(colors, "on_surface_variant", on_surface),
+            on_surface,
+            70.0,
+            80.0,
+        ),
+        Mode::Light => (
+            on_surface,
+            role(colors, "surface_container_highest", surface),
+            role(colors, "surface_container_lowest", surface),
+            40.0,
+            30.0,
+        ),
+    };
+    let hue = |i: usize, tone: f64| {
+        Rgb::from_hct(ANSI_HUES[i], ANSI_CHROMA, tone)
+            .harmonize(primary)
+            .with_tone(tone)
+    };
+    let mut ansi = [black; 16];
+    for i in 0..6 {
+        ansi[i + 1] = hue(i, normal_tone);
+        ansi[i + 9] = hue(i, bright_tone);
+    }
+    ansi[7] = white;
+    ansi[8] = outline;
+    ansi[15] = bright_white;
+    Ok(ansi)
+}
+
+impl Terminal {
+    fn new(colors: &Colors, mode: Mode) -> Result<Self, String> {
+        let primary = required_role(colors, "primary")?;
+        let background = required_role(colors, "surface")?;
+        let foreground = required_role(colors, "on_surface")?;
+        Ok(Self {
+            foreground,
+            background,
+            cursor: primary,
+            cursor_text: role(colors, "on_primary", background),
+            selection_foreground: role(colors, "on_primary_container", foreground),
+            selection_background: role(colors, "primary_container", primary),
+            url: role(colors, "tertiary", primary),
+            active_border: primary,
+            inactive_border: role(colors, "outline_variant", foreground),
+            ansi: ansi_colors(colors, mode)?,
+        })
+    }
+}
+
+fn header(comment: &str, mode: Mode) -> String {
+    format!(
+        "{} Generated by matuwrap ({} scheme); changes will be overwritten\n",
+        comment,
+        mode.name()
+    )
+}
+
+fn xresources(t: &Terminal, mode: Mode) -> String {
+    let mut out = header("!", mode);
+    for (key, color) in [
+        ("foreground", t.foreground),
+        ("background", t.background),
+        ("cursorColor", t.cursor),
+    ] {
+        out += &format!("*.{}: {}\n", key, color.to_hex());
+    }
+    for (i, color) in t.ansi.iter().enumerate() {
+        out += &format!("*.color{}: {}\n", i, color.to_hex());
+    }
+    out
+}
+
+fn kitty(t: &Terminal, colors: &Colors, mode: Mode) -> String {
+    let surface_container = role(colors, "surface_container", t.background);
+    let on_surface_variant = role(colors, "on_surface_variant", t.foreground);
+    let mut out = header("#", mode);
+    for (key, color) in [
+        ("foreground", t.foreground),
+        ("background", t.background),
+        ("selection_foreground", t.selection_foreground),
+        ("selection_background", t.selection_background),
+        ("cursor", t.cursor),
+        ("cursor_text_color", t.cursor_text),
+        ("url_color", t.url),
+        ("active_border_color", t.active_border),
+        ("inactive_border_color", t.inactive_border),
+        ("active_tab_foreground", t.cursor_text),
+        ("active_tab_background", t.cursor),
+        ("inactive_tab_foreground", on_surface_variant),
+        ("inactive_tab_background", surface_container),
+    ] {
+        out += &format!("{} {}\n", key, color.to_hex());
+    }
+    for (i, color) in t.ansi.iter().enumerate() {
+        out += &format!("color{} {}\n", i, color.to_hex());
+    }
+    out
+}
+
+fn alacritty(t: &Terminal, mode: Mode) -> String {
+    let mut out = header("#", mode);
+    let mut section = |name: &str, entries: &[(&str, Rgb)]| {
+        out += &format!("\n[colors.{}]\n", name);
+        for (key, color) in entries {
+            out += &format!("{} = \"{}\"\n", key, color.to_hex());
+        }
+    };
+    section(
+        "primary",
+        &[("background", t.background), ("foreground", t.foreground)],
+    );
+    section("cursor", &[("text", t.cursor_text), ("cursor", t.cursor)]);
+    section(
+        "selection",
+        &[
+            ("text", t.selection_foreground),
+            ("background", t.selection_background),
+        ],
+    );
+    let named = |offset: usize| -> Vec<(&str, Rgb)> {
+        ANSI_NAMES
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (*name, t.ansi[offset + i]))
+            .collect()
+    };
+    section("normal", &named(0));
+    section("bright", &named(8));
+    out
+}
+
+fn foot(t: &Terminal, mode: Mode) -> String {
+    // foot wants bare "rrggbb"
+    let bare = |c: Rgb| c.to_hex()[1..].to_string();
+    let mut out = header("#", mode);
+    out += &format!(
+        "\n[cursor]\ncolor={} {}\n\n[colors]\n",
+        bare(t.cursor_text),
+        bare(t.cursor)
+    );
+    for (key, color) in [
+        ("foreground", t.foreground),
+        ("background", t.background),
+        ("selection-foreground", t.selection_foreground),
+        ("selection-background", t.selection_background),
+        ("urls", t.url),
+    ] {
+        out += &format!("{}={}\n", key, bare(color));
+    }
+    for (i, color) in t.ansi.iter().enumerate() {
+        let (kind, n) = if i < 8 {
+            ("regular", i)
+        } else {
+            ("bright", i - 8)
+        };
+        out += &format!("{}{}={}\n", kind, n, bare(*color));
+    }
+    out
+}
+
+fn sorted(colors: &Colors) -> Vec<(&String, &String)> {
+    let mut roles: Vec<_> = colors.iter().collect();
+    roles.sort();
+    roles
+}
+
+fn gtk(colors: &Colors, mode: Mode) -> String {
+    let mut out = header("/*", mode).replace('\n', " */\n");
+    for (name, role) in GTK_COLORS {
+        if let Some(hex) = colors.get(*role) {
+            out += &format!("@define-color {} {};\n", name, hex);
+        }
+    }
+    out += "\n";
+    for (role, hex) in sorted(colors) {
+        out += &format!("@define-color {} {};\n", role, hex);
+    }
+    out
+}
+
+fn css(colors: &Colors, ansi: &[Rgb; 16], mode: Mode) -> String {
+    let mut out = header("/*", mode).replace('\n', " */\n");
+    out += &format!(":root {{\n  color-scheme: {};\n", mode.name());
+    for (role, hex) in sorted(colors) {
+        out += &format!("  --{}: {};\n", role.replace('_', "-"), hex);
+    }
+    for (i, color) in ansi.iter().enumerate() {
+        out += &format!("  --ansi-{}: {};\n", i, color.to_hex());
+    }
+    out += "}\n";
+    out
+}
+
+/// W3C design tokens: one color token per role plus an `ansi` group
+/// ("black" to "bright_white").
+fn design_tokens(colors: &Colors, ansi: &[Rgb; 16], mode: Mode) -> String {
+    let token = |hex: &str| json!({ "$value": hex });
+    let mut roles = Map::new();
+    roles.insert("$type".into(), json!("color"));
+    roles.insert(
+        "$description".into(),
+        json!(format!("matuwrap {} scheme", mode.name())),
+    );
+    for (role, hex) in colors {
+        roles.insert(role.clone(), token(hex));
+    }
+    let mut ansi_group = Map::new();
+    ansi_group.insert("$type".into(), json!("color"));
+    for (i, color) in ansi.iter().enumerate() {
+        let name = match i {
+            0..8 => ANSI_NAMES[i].to_string(),
+            _ => format!("bright_{}", ANSI_NAMES[i - 8]),
+        };
+        ansi_group.insert(name, token(&color.to_hex()));
+    }
+    let tokens = json!({ "color": Value::Object(roles), "ansi": Value::Object(ansi_group) });
+    // serde_json's Map is ordered by key, so output is stable
+    serde_json::to_string_pretty(&tokens).unwrap_or_default() + "\n"
+}
+
+/// Export one scheme in `format`.
+pub(crate) fn export(colors: &Colors, mode: Mode, format: &str) -> Result<String, String> {
+    let (format, _) = parse_format(format)?;
+    Ok(match format {
+        Format::Xresources => xresources(&Terminal::new(colors, mode)?, mode),
+        Format::Kitty => kitty(&Terminal::new(colors, mode)?, colors, mode),
+        Format::Alacritty => alacritty(&Terminal::new(colors, mode)?, mode),
+        Format::Foot => foot(&Terminal::new(colors, mode)?, mode),
+        Format::Gtk => gtk(colors, mode),
+        Format::Css => css(colors, &ansi_colors(colors, mode)?, mode),
+        Format::Json => design_tokens(colors, &ansi_colors(colors, mode)?, mode),
+    })
+}
+
+/// Default directory for `export_files`: `~/.cache/matuwrap/export`.
+fn export_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("matuwrap").join("export"))
+}
+
+/// Outcome of writing one export file.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct ExportResult {
+    pub format: String,
+    pub path: String,
+    /// The file's contents changed.
+    pub changed: bool,
+    /// Why exporting or writing failed, if it did.
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl ExportResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "ExportResult({:?}, path={:?}, changed={}, error={:?})",
+            self.format, self.path, self.changed, self.error
+        )
+    }
+}
+
+/// Write `format` into `dir`, only touching the file if it changed.
+fn export_file(colors: &Colors, mode: Mode, format: &str, dir: &Path) -> ExportResult {
+    let (path, result) = match parse_format(format) {
+        Ok((_, file)) => {
+            let path = dir.join(file);
+            let result = export(colors, mode, format).and_then(|data| {
+                if fs::read(&path).is_ok_and(|old| old == data.as_bytes()) {
+                    return Ok(false);
+                }
+                colors::write_atomic(&path, data.as_bytes())
+                    .map(|_| true)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            });
+            (path.display().to_string(), result)
+        }
+        Err(e) => (String::new(), Err(e)),
+    };
+    let (changed, error) = match result {
+        Ok(changed) => (changed, None),
+        Err(e) => (false, Some(e)),
+    };
+    ExportResult {
+        format: format.to_string(),
+        path,
+        changed,
+        error,
+    }
+}
+
+/// Names of the supported export formats.
+#[pyfunction]
+pub fn export_formats() -> Vec<&'static str> {
+    FORMATS.iter().map(|(n, _, _)| *n).collect()
+}
+
+/// Export a palette's scheme for `mode` (defaults to the saved mode).
+#[pyfunction]
+#[pyo3(signature = (palette, format, mode=None))]
+pub fn export_palette(
+    palette: PyRef<'_, Palette>,
+    format: &str,
+    mode: Option<&str>,
+) -> PyResult<String> {
+    let mode = material::mode_from_arg(mode)?;
+    export(palette.colors(mode), mode, format)
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// The 16 ANSI colors derived from a palette's scheme, as "#rrggbb".
+#[pyfunction]
+#[pyo3(signature = (palette, mode=None))]
+pub fn ansi_palette(palette: PyRef<'_, Palette>, mode: Option<&str>) -> PyResult<Vec<String>> {
+    let mode = material::mode_from_arg(mode)?;
+    let ansi = ansi_colors(palette.colors(mode), mode)
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    Ok(ansi.iter().map(|c| c.to_hex()).collect())
+}
+
+/// Write the wallpaper's cached palette in each of `formats` (all by
+/// default) into `directory` (default `~/.cache/matuwrap/export`).
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, directory=None, formats=None, scheme_type=None, mode=None, contrast=None, source_index=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn export_files(
+    py: Python<'_>,
+    wallpaper_path: &str,
+    directory: Option<PathBuf>,
+    formats: Option<Vec<String>>,
+    scheme_type: Option<&str>,
+    mode: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+) -> PyResult<Vec<ExportResult>> {
+    let mode = material::mode_from_arg(mode)?;
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+    let dir = directory.or_else(export_dir).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Could not determine cache directory")
+    })?;
+    let formats =
+        formats.unwrap_or_else(|| export_formats().iter().map(|f| f.to_string()).collect());
+    let cached = py.allow_threads(|| colors::cached_palette(wallpaper_path, &options, false));
+    colors::warn_corrupt(py, &cached)?;
+    let palette = cached.palette.ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Could not generate colors for {}",
+            wallpaper_path
+        ))
+    })?;
+    fs::create_dir_all(&dir)?;
+    let colors = palette.colors(mode);
+    Ok(py.allow_threads(|| {
+        formats
+            .iter()
+            .map(|format| export_file(colors, mode, format, &dir))
+            .collect()
+    }))
+}
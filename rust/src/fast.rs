@@ -0,0 +1,250 @@
+//! `wrp-fast`: hot paths of `wrp` as a native binary.
+//!
+//! Shell startup runs these on every new terminal, where starting Python
+//! costs far more than the work. The binary (src/bin/wrp-fast.rs) only
+//! calls `main`; everything here goes through the same code as the
+//! extension module but never calls into Python, so it runs without
+//! libpython.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::audio;
+use crate::colors::{self, Palette};
+use crate::ipc::{HyprlandClient, Timeouts};
+use crate::material::Mode;
+use crate::models::Monitor;
+use crate::prompt::{self, Shell};
+use crate::terminal::ColorDepth;
+
+const USAGE: &str = "\
+Usage: wrp-fast <command> [args]
+
+Commands:
+  colors get [ROLE]                    Print a palette color as hex (default: primary)
+  colors ps1 [--depth DEPTH]           Print the bash PS1 of `wrp get_colors ps1`
+  prompt [bash|zsh|fish|nu] [FORMAT]   Print a prompt colored from the wallpaper palette
+      --depth truecolor|256|16|none    Color depth (default: from NO_COLOR/COLORTERM/TERM)
+      --mode dark|light                Scheme (default: the saved mode)
+  hypr monitors [--all] [--json]       List Hyprland monitors (--all includes disabled)
+      --instance SIGNATURE             Hyprland instance (default: the running one)
+  audio sinks [--json]                 List PipeWire sinks (* marks the default)
+
+FORMAT segments: {user} {host} {cwd} {git} ({git| (%s)} adds text around it)
+FORMAT colors:   [primary]...[/] (any palette role), [#rrggbb], [bold]";
+
+/// Options and flags taken by name; everything else is positional.
+struct Args {
+    positional: Vec<String>,
+    depth: Option<String>,
+    mode: Option<String>,
+    instance: Option<String>,
+    all: bool,
+    json: bool,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            depth: None,
+            mode: None,
+            instance: None,
+            all: false,
+            json: false,
+        };
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            let slot = match arg.as_str() {
+                "--depth" => &mut parsed.depth,
+                "--mode" => &mut parsed.mode,
+                "--instance" => &mut parsed.instance,
+                "--all" => {
+                    parsed.all = true;
+                    continue;
+                }
+                "--json" => {
+                    parsed.json = true;
+                    continue;
+                }
+                _ => {
+                    parsed.positional.push(arg);
+                    continue;
+                }
+            };
+            *slot = Some(
+                args.next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?,
+            );
+        }
+        Ok(parsed)
+    }
+}
+
+/// The wallpaper as the rest of matuwrap sees it.
+fn wallpaper() -> Result<String, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".current.wall"))
+        .map(|p: PathBuf| p.to_string_lossy().into_owned())
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+/// Cached palette of the current wallpaper with the saved scheme options,
+/// generating it on a miss.
+fn current_palette() -> Result<Option<Palette>, String> {
+    let options = colors::preferred_options();
+    Ok(colors::cached_palette(&wallpaper()?, &options, false).palette)
+}
+
+fn mode(arg: Option<&str>) -> Result<Mode, String> {
+    match arg {
+        Some(name) => Mode::from_name(name)
+            .ok_or_else(|| format!("Unknown mode {:?} (expected \"dark\" or \"light\")", name)),
+        None => Ok(colors::preferred_mode()),
+    }
+}
+
+fn prompt(args: &Args) -> Result<String, String> {
+    let shell = Shell::from_name(args.positional.get(1).map_or("bash", String::as_str))?;
+    let format = args
+        .positional
+        .get(2)
+        .map_or(prompt::DEFAULT_FORMAT, String::as_str);
+    let depth = ColorDepth::from_arg(args.depth.as_deref())?;
+    let mode = mode(args.mode.as_deref())?;
+    // No palette yet: an uncolored prompt beats none
+    let palette = current_palette()?;
+    prompt::render(
+        format,
+        shell,
+        palette.as_ref().map(|p| p.colors(mode)),
+        depth,
+    )
+}
+
+fn colors(args: &Args) -> Result<String, String> {
+    // Check the command and options before loading (or generating) a palette
+    let command = match args.positional.get(1).map(String::as_str) {
+        Some(command @ ("get" | "ps1")) => command,
+        Some(other) => return Err(format!("Unknown colors command {:?}\n\n{}", other, USAGE)),
+        None => return Err(USAGE.to_string()),
+    };
+    let mode = mode(args.mode.as_deref())?;
+    // Same prompt as `wrp get_colors ps1`, which the shell cache stores
+    // (truecolor unless asked)
+    let depth = args
+        .depth
+        .as_deref()
+        .map_or(Ok(ColorDepth::TrueColor), |d| ColorDepth::from_arg(Some(d)))?;
+    let palette = current_palette()?.ok_or_else(|| {
+        format!(
+            "Could not generate colors for {}",
+            wallpaper().unwrap_or_default()
+        )
+    })?;
+    let colors = palette.colors(mode);
+    if command == "ps1" {
+        return prompt::render(prompt::DEFAULT_FORMAT, Shell::Bash, Some(colors), depth);
+    }
+    let role = args.positional.get(2).map_or("primary", String::as_str);
+    colors
+        .get(role)
+        .cloned()
+        .ok_or_else(|| format!("Unknown color role {:?}", role))
+}
+
+fn hypr(args: &Args) -> Result<String, String> {
+    if args.positional.get(1).map(String::as_str) != Some("monitors") {
+        return Err(USAGE.to_string());
+    }
+    let client = HyprlandClient::resolve(args.instance.as_deref(), Timeouts::default())
+        .map_err(|e| e.to_string())?;
+    let request = if args.all {
+        "j/monitors all"
+    } else {
+        "j/monitors"
+    };
+    let reply = client.send(request).map_err(|e| e.to_string())?;
+    let monitors: Vec<Monitor> =
+        serde_json::from_str(&reply).map_err(|e| format!("Invalid JSON from Hyprland: {}", e))?;
+    if args.json {
+        // Hyprland's own JSON, after checking it is a monitor list
+        return Ok(reply.trim_end().to_string());
+    }
+    Ok(monitors
+        .iter()
+        .map(|m| {
+            format!(
+                "{} {}x{}@{:.2} at {},{} scale {:.2}{}{}",
+                m.name,
+                m.width,
+                m.height,
+                m.refresh_rate,
+                m.x,
+                m.y,
+                m.scale,
+                if m.focused { " (focused)" } else { "" },
+                if m.disabled { " (disabled)" } else { "" },
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn audio(args: &Args) -> Result<String, String> {
+    if args.positional.get(1).map(String::as_str) != Some("sinks") {
+        return Err(USAGE.to_string());
+    }
+    let sinks = audio::audio_sinks().map_err(|e| e.to_string())?;
+    if args.json {
+        return serde_json::to_string_pretty(&sinks).map_err(|e| e.to_string());
+    }
+    Ok(sinks
+        .iter()
+        .map(|s| {
+            let volume = match s.volume {
+                _ if s.muted => " [muted]".to_string(),
+                Some(v) => format!(" [{:.0}%]", v * 100.0),
+                None => String::new(),
+            };
+            let marker = if s.is_default { '*' } else { ' ' };
+            format!("{} {:>4}. {}{}", marker, s.id, s.description, volume)
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn run(args: &Args) -> Result<Option<String>, String> {
+    match args.positional.first().map(String::as_str) {
+        Some("colors") => colors(args).map(Some),
+        Some("prompt") => prompt(args).map(Some),
+        Some("hypr") => hypr(args).map(Some),
+        Some("audio") => audio(args).map(Some),
+        Some("-h" | "--help" | "help") => Ok(Some(USAGE.to_string())),
+        Some(other) => Err(format!("Unknown command {:?}\n\n{}", other, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+/// Entry point of the `wrp-fast` binary.
+pub fn main() -> ExitCode {
+    let result = Args::parse(std::env::args().skip(1))
+        .and_then(|args| run(&args))
+        .and_then(|output| match output {
+            Some(output) => match writeln!(io::stdout().lock(), "{}", output) {
+                // The reader went away (`| head`); nothing left to do
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                result => result.map_err(|e| format!("Failed to write output: {}", e)),
+            },
+            None => Ok(()),
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // Unlike eprintln!, doesn't panic if stderr is closed too
+            let _ = writeln!(io::stderr().lock(), "wrp-fast: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
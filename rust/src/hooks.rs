@@ -0,0 +1,300 @@
+//! Post-render hooks for theme templates.
+//!
+//! After a template's output changes, apps usually need a nudge to reload
+//! it: kitty wants `SIGUSR1`, waybar `SIGUSR2`, Hyprland a `keyword`,
+//! swaync a `swaync-client -rs`. Hooks are listed per template in
+//! `templates.json`:
+//!
+//! ```json
+//! "hooks": [
+//!   {"type": "signal", "process": "kitty", "signal": "SIGUSR1"},
+//!   {"type": "command", "command": ["swaync-client", "-rs"], "timeout": 5},
+//!   {"type": "keyword", "keyword": "general:col.active_border",
+//!    "value": "rgb({{colors.primary.default.hex_stripped}})"}
+//! ]
+//! ```
+//!
+//! Command arguments and keyword values are rendered like templates. Each
+//! hook runs independently and reports a `HookResult`; a failing hook
+//! doesn't stop the others.
+
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind};
+
+use crate::ipc::{self, HyprlandClient, Timeouts};
+use crate::templates::{self, Context};
+
+/// Signals a hook may send, by name without the `SIG` prefix.
+const SIGNALS: &[(&str, Signal)] = &[
+    ("HUP", Signal::Hangup),
+    ("INT", Signal::Interrupt),
+    ("QUIT", Signal::Quit),
+    ("TERM", Signal::Term),
+    ("USR1", Signal::User1),
+    ("USR2", Signal::User2),
+    ("CONT", Signal::Continue),
+];
+
+fn default_signal() -> String {
+    "SIGUSR1".to_string()
+}
+
+fn default_timeout() -> f64 {
+    10.0
+}
+
+/// One entry of a template's `hooks` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum Hook {
+    /// Signal every process of the current user with this name.
+    Signal {
+        process: String,
+        #[serde(default = "default_signal")]
+        signal: String,
+    },
+    /// Run a command (no shell), killing it after `timeout` seconds.
+    Command {
+        command: Vec<String>,
+        #[serde(default = "default_timeout")]
+        timeout: f64,
+    },
+    /// Set a Hyprland option via `keyword`.
+    Keyword { keyword: String, value: String },
+}
+
+impl Hook {
+    fn describe(&self) -> String {
+        match self {
+            Hook::Signal { process, signal } => format!("signal {} {}", signal, process),
+            Hook::Command { command, .. } => format!("command {}", command.join(" ")),
+            Hook::Keyword { keyword, value } => format!("keyword {} {}", keyword, value),
+        }
+    }
+}
+
+/// Outcome of one hook.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct HookResult {
+    /// "signal", "command" or "keyword".
+    pub kind: String,
+    /// The hook as configured, e.g. "signal SIGUSR1 kitty".
+    pub hook: String,
+    pub ok: bool,
+    /// Command stdout, Hyprland's reply, or how many processes were signalled.
+    pub output: String,
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl HookResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "HookResult({:?}, ok={}, error={:?})",
+            self.hook, self.ok, self.error
+        )
+    }
+}
+
+fn parse_signal(name: &str) -> Result<Signal, String> {
+    let bare = name.strip_prefix("SIG").unwrap_or(name);
+    SIGNALS
+        .iter()
+        .find(|(n, _)| *n == bare)
+        .map(|(_, s)| *s)
+        .ok_or_else(|| {
+            let names: Vec<String> = SIGNALS.iter().map(|(n, _)| format!("SIG{}", n)).collect();
+            format!(
+                "Unknown signal {:?} (expected one of: {})",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// Signal the current user's processes named `process` (by name or
+/// executable file name). Returns how many were signalled.
+fn signal_processes(process: &str, signal: &str) -> Result<usize, String> {
+    let signal = parse_signal(signal)?;
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_user(UpdateKind::OnlyIfNotSet),
+    );
+    // SAFETY: getuid(2) cannot fail.
+    let uid = unsafe { libc::getuid() };
+    let own_pid = std::process::id();
+    let mut signalled = 0;
+    for (pid, proc) in sys.processes() {
+        let named = proc.name() == process
+            || proc
+                .exe()
+                .and_then(|exe| exe.file_name())
+                .is_some_and(|name| name == process);
+        let owned = proc.user_id().is_some_and(|u| **u == uid);
+        if named && owned && pid.as_u32() != own_pid && proc.kill_with(signal) == Some(true) {
+            signalled += 1;
+        }
+    }
+    if signalled == 0 {
+        return Err(format!("No running process named {:?}", process));
+    }
+    Ok(signalled)
+}
+
+/// How often a running command is checked for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long to wait for output a command wrote just before exiting.
+const OUTPUT_GRACE: Duration = Duration::from_millis(100);
+
+/// Read `pipe` to EOF on a thread, returning the bytes read so far and a
+/// receiver that fires at EOF.
+fn drain(mut pipe: impl Read + Send + 'static) -> (Arc<Mutex<Vec<u8>>>, mpsc::Receiver<()>) {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let (tx, rx) = mpsc::channel();
+    let shared = Arc::clone(&buffer);
+    std::thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+            if let Ok(mut buffer) = shared.lock() {
+                buffer.extend_from_slice(&chunk[..n]);
+            }
+        }
+        let _ = tx.send(());
+    });
+    (buffer, rx)
+}
+
+fn lossy(buffer: &Mutex<Vec<u8>>) -> String {
+    buffer
+        .lock()
+        .map(|b| String::from_utf8_lossy(&b).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Run `argv` without a shell; kill it and everything it started if it
+/// outlives `timeout`.
+///
+/// Only the command's own exit is awaited, not EOF on its output: a hook
+/// like `sh -c "pkill waybar; waybar &"` leaves the pipes open in the
+/// background process, which must neither hang rendering nor be killed.
+fn run_with_timeout(argv: &[String], timeout: Duration) -> Result<String, String> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| "Empty command".to_string())?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Own process group, so a timeout can kill the whole tree
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    let pid = child.id() as libc::pid_t;
+
+    // Drain output on threads so a chatty child can't fill the pipe while
+    // we wait
+    let (stdout, stdout_done) = drain(child.stdout.take().expect("stdout is piped"));
+    let (stderr, stderr_done) = drain(child.stderr.take().expect("stderr is piped"));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // SAFETY: killpg(2) on the group our unreaped child leads.
+            unsafe { libc::killpg(pid, libc::SIGKILL) };
+            let _ = child.wait();
+            return Err(format!(
+                "{} timed out after {}s",
+                program,
+                timeout.as_secs_f64()
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    for done in [stdout_done, stderr_done] {
+        let _ = done.recv_timeout(OUTPUT_GRACE);
+    }
+
+    if !status.success() {
+        let stderr = lossy(&stderr);
+        return Err(if stderr.is_empty() {
+            format!("{} exited with {}", program, status)
+        } else {
+            stderr
+        });
+    }
+    Ok(lossy(&stdout))
+}
+
+fn send_keyword(keyword: &str) -> Result<String, String> {
+    let client = HyprlandClient::resolve(None, Timeouts::default()).map_err(|e| e.to_string())?;
+    let reply = client.send(keyword).map_err(|e| e.to_string())?;
+    match ipc::reply_error(keyword, &reply, false) {
+        Some(error) => Err(error),
+        None => Ok(reply.trim().to_string()),
+    }
+}
+
+/// Render a hook string (command argument or keyword value).
+fn render_arg(arg: &str, ctx: &Context) -> Result<String, String> {
+    templates::render(arg, ctx).map_err(|e| e.to_string())
+}
+
+fn run_hook(hook: &Hook, ctx: &Context) -> Result<String, String> {
+    match hook {
+        Hook::Signal { process, signal } => {
+            signal_processes(process, signal).map(|n| format!("{} process(es)", n))
+        }
+        Hook::Command { command, timeout } => {
+            let timeout = Duration::try_from_secs_f64(*timeout)
+                .map_err(|_| format!("Invalid timeout: {}", timeout))?;
+            let argv = command
+                .iter()
+                .map(|arg| render_arg(arg, ctx))
+                .collect::<Result<Vec<_>, _>>()?;
+            run_with_timeout(&argv, timeout)
+        }
+        Hook::Keyword { keyword, value } => {
+            send_keyword(&format!("keyword {} {}", keyword, render_arg(value, ctx)?))
+        }
+    }
+}
+
+/// Run every hook in order, collecting one result each.
+pub(crate) fn run_hooks(hooks: &[Hook], ctx: &Context) -> Vec<HookResult> {
+    hooks
+        .iter()
+        .map(|hook| {
+            let kind = match hook {
+                Hook::Signal { .. } => "signal",
+                Hook::Command { .. } => "command",
+                Hook::Keyword { .. } => "keyword",
+            };
+            let (ok, output, error) = match run_hook(hook, ctx) {
+                Ok(output) => (true, output, None),
+                Err(e) => (false, String::new(), Some(e)),
+            };
+            HookResult {
+                kind: kind.to_string(),
+                hook: hook.describe(),
+                ok,
+                output,
+                error,
+            }
+        })
+        .collect()
+}
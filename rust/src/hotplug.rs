@@ -0,0 +1,203 @@
+//! Automatic layout switching on monitor hotplug.
+//!
+//! `LayoutWatcher` listens for `monitoradded`/`monitorremoved`, waits for
+//! the burst of events a dock produces to settle, then fingerprints the
+//! connected displays by make/model/serial. When the set changes, the saved
+//! profile with exactly that set of monitors is restored.
+
+use pyo3::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::events::EventReader;
+use crate::ipc::{HyprlandClient, Timeouts};
+use crate::models::{self, Monitor};
+use crate::profiles::{self, Identity, LayoutProfile};
+
+const HOTPLUG_EVENTS: &[&str] = &["monitoradded", "monitorremoved"];
+
+/// Outcome of one change in the connected set of monitors.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct LayoutSwitch {
+    /// Connected monitors as "make model serial", sorted.
+    pub monitors: Vec<String>,
+    /// Profile that matched, or None if no saved profile fits.
+    pub profile: Option<String>,
+    /// `monitor=` lines that were applied (or would be, in dry-run mode).
+    pub lines: Vec<String>,
+    /// Why restoring the matched profile failed, if it did.
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl LayoutSwitch {
+    fn __repr__(&self) -> String {
+        format!(
+            "LayoutSwitch(profile={:?}, monitors={:?}, error={:?})",
+            self.profile, self.monitors, self.error
+        )
+    }
+}
+
+/// Sorted identities of all connected monitors (disabled ones included).
+fn fingerprint(monitors: &[Monitor]) -> Vec<Identity> {
+    let mut ids: Vec<Identity> = monitors.iter().map(profiles::monitor_identity).collect();
+    ids.sort();
+    ids
+}
+
+fn profile_fingerprint(profile: &LayoutProfile) -> Vec<Identity> {
+    let mut ids: Vec<Identity> = profile.monitors.iter().map(|m| m.identity()).collect();
+    ids.sort();
+    ids
+}
+
+/// First saved profile (by name) whose monitors are exactly `connected`.
+/// Profiles that fail to load are skipped.
+pub(crate) fn matching_profile(connected: &[Identity]) -> Option<LayoutProfile> {
+    profiles::profile_names()
+        .iter()
+        .filter_map(|name| profiles::read_profile(name).ok())
+        .find(|p| profile_fingerprint(p) == connected)
+}
+
+/// Watches for monitor hotplug and restores the matching saved profile.
+///
+/// Iterating yields a `LayoutSwitch` each time the set of connected
+/// monitors changes.
+#[pyclass]
+pub struct LayoutWatcher {
+    stream: EventReader,
+    client: HyprlandClient,
+    debounce: Duration,
+    dry_run: bool,
+    apply_on_start: bool,
+    started: bool,
+    last: Option<Vec<Identity>>,
+}
+
+impl LayoutWatcher {
+    fn monitors(&self, py: Python<'_>) -> PyResult<Vec<Monitor>> {
+        models::parse(&self.client.request_json(py, "monitors all")?)
+    }
+
+    fn switch(&mut self, py: Python<'_>, monitors: &[Monitor]) -> LayoutSwitch {
+        let connected = fingerprint(monitors);
+        let profile = matching_profile(&connected);
+        let (lines, error) = match &profile {
+            Some(p) => match profiles::restore_profile(py, &self.client, p, self.dry_run) {
+                Ok(lines) => (lines, None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            },
+            None => (Vec::new(), None),
+        };
+        let labels = connected
+            .iter()
+            .map(|(make, model, serial)| profiles::identity_label(make, model, serial))
+            .collect();
+        self.last = Some(connected);
+        LayoutSwitch {
+            monitors: labels,
+            profile: profile.map(|p| p.name),
+            lines,
+            error,
+        }
+    }
+
+    /// Block until the connected set changes; `None` when Hyprland exits.
+    pub(crate) fn next_switch(&mut self, py: Python<'_>) -> PyResult<Option<LayoutSwitch>> {
+        if !self.started {
+            self.started = true;
+            let monitors = self.monitors(py)?;
+            if self.apply_on_start {
+                return Ok(Some(self.switch(py, &monitors)));
+            }
+            self.last = Some(fingerprint(&monitors));
+        }
+
+        loop {
+            if self.stream.next_event(py)?.is_none() {
+                return Ok(None);
+            }
+            // Docks report each output separately; wait for a quiet period
+            while self
+                .stream
+                .next_event_until(py, Some(Instant::now() + self.debounce))?
+                .is_some()
+            {}
+            if !self.stream.is_connected() {
+                return Ok(None);
+            }
+
+            let monitors = self.monitors(py)?;
+            if self.last.as_ref() != Some(&fingerprint(&monitors)) {
+                return Ok(Some(self.switch(py, &monitors)));
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl LayoutWatcher {
+    #[new]
+    #[pyo3(signature = (debounce=0.5, dry_run=false, apply_on_start=true, instance=None))]
+    fn new(
+        debounce: f64,
+        dry_run: bool,
+        apply_on_start: bool,
+        instance: Option<&str>,
+    ) -> PyResult<Self> {
+        let debounce = Duration::try_from_secs_f64(debounce).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid debounce: {}",
+                debounce
+            ))
+        })?;
+        let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+        let events = HOTPLUG_EVENTS.iter().map(|e| e.to_string()).collect();
+        Ok(Self {
+            stream: EventReader::connect(Some(events), instance)?,
+            client,
+            debounce,
+            dry_run,
+            apply_on_start,
+            started: false,
+            last: None,
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<LayoutSwitch>> {
+        self.next_switch(py)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) {
+        self.close();
+    }
+
+    /// Stop watching. Further iteration stops immediately.
+    fn close(&mut self) {
+        self.stream.close();
+    }
+}
+
+/// Name of the saved profile matching the connected monitors, if any.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn find_matching_profile(py: Python<'_>, instance: Option<&str>) -> PyResult<Option<String>> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    Ok(matching_profile(&fingerprint(&monitors)).map(|p| p.name))
+}
@@ -0,0 +1,157 @@
+//! Hyprland instance discovery.
+//!
+//! Each running compositor owns a directory named after its instance
+//! signature under `$XDG_RUNTIME_DIR/hypr/` (0.40+) or `/tmp/hypr/`.
+//! Outside a Hyprland session (cron, SSH, systemd user units) the
+//! signature env var is missing, so instances are found by scanning
+//! those directories and probing their sockets.
+
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::ipc::{IpcError, connect_with_timeout};
+
+/// How long a liveness probe waits for `.socket.sock` to accept.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A Hyprland instance directory found on disk.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct HyprInstance {
+    /// Instance signature (directory name, HYPRLAND_INSTANCE_SIGNATURE).
+    pub signature: String,
+    /// Instance directory containing the sockets.
+    pub path: String,
+    /// Compositor PID from `hyprland.lock`.
+    pub pid: Option<u32>,
+    /// Wayland display socket name from `hyprland.lock` (e.g. "wayland-1").
+    pub wayland_display: Option<String>,
+    /// Whether `.socket.sock` accepted a connection.
+    pub alive: bool,
+}
+
+#[pymethods]
+impl HyprInstance {
+    fn __repr__(&self) -> String {
+        format!(
+            "HyprInstance(signature={:?}, pid={:?}, alive={})",
+            self.signature, self.pid, self.alive
+        )
+    }
+}
+
+/// Directories that may contain instance directories, in priority order.
+fn runtime_roots() -> Vec<PathBuf> {
+    // Cron and some SSH sessions don't set XDG_RUNTIME_DIR; use systemd's default
+    let xdg = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            // SAFETY: getuid(2) cannot fail.
+            let uid = unsafe { libc::getuid() };
+            PathBuf::from(format!("/run/user/{}", uid))
+        });
+    vec![xdg.join("hypr"), PathBuf::from("/tmp/hypr")]
+}
+
+/// Find the directory of a given instance signature.
+pub(crate) fn instance_dir(signature: &str) -> Option<PathBuf> {
+    runtime_roots()
+        .into_iter()
+        .map(|root| root.join(signature))
+        .find(|dir| dir.is_dir())
+}
+
+/// Parse `hyprland.lock`: PID on the first line, Wayland display on the second.
+fn read_lock(dir: &Path) -> (Option<u32>, Option<String>) {
+    let Ok(data) = fs::read_to_string(dir.join("hyprland.lock")) else {
+        return (None, None);
+    };
+    let mut lines = data.lines().map(str::trim);
+    let pid = lines.next().and_then(|l| l.parse().ok());
+    let display = lines.next().filter(|l| !l.is_empty()).map(str::to_string);
+    (pid, display)
+}
+
+fn probe(dir: &Path) -> bool {
+    connect_with_timeout(&dir.join(".socket.sock"), Some(PROBE_TIMEOUT)).is_ok()
+}
+
+/// Enumerate instance directories, deduplicated by signature
+/// (XDG_RUNTIME_DIR wins over /tmp).
+pub(crate) fn discover() -> Vec<HyprInstance> {
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for root in runtime_roots() {
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let signature = entry.file_name().to_string_lossy().into_owned();
+            found.entry(signature).or_insert(path);
+        }
+    }
+
+    found
+        .into_iter()
+        .map(|(signature, dir)| {
+            let (pid, wayland_display) = read_lock(&dir);
+            HyprInstance {
+                alive: probe(&dir),
+                path: dir.to_string_lossy().into_owned(),
+                signature,
+                pid,
+                wayland_display,
+            }
+        })
+        .collect()
+}
+
+/// Pick the instance to talk to and return `(signature, directory)`.
+///
+/// Order: explicit `instance`, then HYPRLAND_INSTANCE_SIGNATURE, then the
+/// single live instance on disk.
+pub(crate) fn resolve(instance: Option<&str>) -> Result<(String, PathBuf), IpcError> {
+    if let Some(signature) = instance {
+        let dir = instance_dir(signature)
+            .ok_or_else(|| IpcError::UnknownInstance(signature.to_string()))?;
+        return Ok((signature.to_string(), dir));
+    }
+
+    if let Ok(signature) = std::env::var("HYPRLAND_INSTANCE_SIGNATURE") {
+        // Keep the pre-discovery behaviour: trust the env var even if the
+        // directory is missing, so the connect error names the socket.
+        let dir =
+            instance_dir(&signature).unwrap_or_else(|| Path::new("/tmp/hypr").join(&signature));
+        return Ok((signature, dir));
+    }
+
+    let mut live: Vec<HyprInstance> = discover().into_iter().filter(|i| i.alive).collect();
+    match live.len() {
+        0 => Err(IpcError::NoInstance),
+        1 => {
+            let only = live.remove(0);
+            Ok((only.signature, PathBuf::from(only.path)))
+        }
+        _ => Err(IpcError::AmbiguousInstance(
+            live.into_iter().map(|i| i.signature).collect(),
+        )),
+    }
+}
+
+/// List Hyprland instances found under XDG_RUNTIME_DIR and /tmp.
+#[pyfunction]
+#[pyo3(signature = (alive_only=false))]
+pub fn list_instances(py: Python<'_>, alive_only: bool) -> Vec<HyprInstance> {
+    py.allow_threads(|| {
+        discover()
+            .into_iter()
+            .filter(|i| i.alive || !alive_only)
+            .collect()
+    })
+}
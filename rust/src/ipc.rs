@@ -0,0 +1,614 @@
+//! Hyprland IPC over `.socket.sock`.
+//!
+//! `HyprlandClient` resolves the instance socket once and applies
+//! connect/read/write timeouts to every request. The module-level
+//! `hyprctl*` functions build a default client per call.
+
+use pyo3::create_exception;
+use pyo3::prelude::*;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::dispatch;
+use crate::instances;
+use crate::layout;
+use crate::models;
+use crate::profiles;
+
+create_exception!(
+    wrp_native,
+    HyprlandTimeoutError,
+    pyo3::exceptions::PyTimeoutError,
+    "Raised when Hyprland does not answer within the configured timeout."
+);
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Pause before the single retry after ECONNREFUSED (compositor restart).
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Hyprland separates the replies of a `[[BATCH]]` request with this.
+const BATCH_SEPARATOR: &str = "\n\n\n";
+
+/// Commands whose only successful reply is `ok`.
+const ACTION_COMMANDS: &[&str] = &[
+    "dispatch",
+    "keyword",
+    "reload",
+    "kill",
+    "setcursor",
+    "switchxkblayout",
+    "seterror",
+    "setprop",
+    "notify",
+    "dismissnotify",
+    "output",
+    "plugin",
+];
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Debug)]
+pub(crate) enum IpcError {
+    NoInstance,
+    UnknownInstance(String),
+    AmbiguousInstance(Vec<String>),
+    Connect(io::Error),
+    Timeout(&'static str),
+    Io(io::Error),
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcError::NoInstance => write!(
+                f,
+                "HYPRLAND_INSTANCE_SIGNATURE not set and no running Hyprland instance found"
+            ),
+            IpcError::UnknownInstance(sig) => write!(f, "Hyprland instance not found: {}", sig),
+            IpcError::AmbiguousInstance(sigs) => write!(
+                f,
+                "Multiple Hyprland instances running ({}); pass instance=",
+                sigs.join(", ")
+            ),
+            IpcError::Connect(e) => write!(f, "Failed to connect to Hyprland socket: {}", e),
+            IpcError::Timeout(op) => write!(f, "Timed out waiting for Hyprland ({})", op),
+            IpcError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<IpcError> for PyErr {
+    fn from(err: IpcError) -> PyErr {
+        let msg = err.to_string();
+        match err {
+            IpcError::NoInstance
+            | IpcError::UnknownInstance(_)
+            | IpcError::AmbiguousInstance(_) => {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(msg)
+            }
+            IpcError::Connect(_) => PyErr::new::<pyo3::exceptions::PyConnectionError, _>(msg),
+            IpcError::Timeout(_) => HyprlandTimeoutError::new_err(msg),
+            IpcError::Io(_) => PyErr::new::<pyo3::exceptions::PyIOError, _>(msg),
+        }
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+// ============================================================================
+// Socket resolution
+// ============================================================================
+
+/// Resolve the path of a Hyprland socket file for the current instance.
+///
+/// `socket` is the file name inside the instance directory, e.g.
+/// `.socket.sock` (requests) or `.socket2.sock` (events). `instance`
+/// selects a signature explicitly; see `instances::resolve`.
+pub(crate) fn hypr_socket_path(instance: Option<&str>, socket: &str) -> PyResult<String> {
+    let (_, dir) = instances::resolve(instance)?;
+    Ok(dir.join(socket).to_string_lossy().into_owned())
+}
+
+/// Connect to a Unix socket, giving up after `timeout`.
+///
+/// std has no `UnixStream::connect_timeout`, so this does a non-blocking
+/// connect and polls for writability.
+pub(crate) fn connect_with_timeout(
+    path: &Path,
+    timeout: Option<Duration>,
+) -> io::Result<UnixStream> {
+    let Some(timeout) = timeout else {
+        return UnixStream::connect(path);
+    };
+
+    // SAFETY: plain socket(2); the fd is immediately owned by `stream`.
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            0,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` is a freshly created, valid socket we exclusively own.
+    let stream = unsafe { UnixStream::from_raw_fd(fd) };
+
+    // SAFETY: sockaddr_un is plain old data; all-zero is a valid value.
+    let mut addr: libc::sockaddr_un = unsafe { std::mem::zeroed() };
+    addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    let bytes = path.as_os_str().as_bytes();
+    if bytes.len() >= addr.sun_path.len() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "socket path too long",
+        ));
+    }
+    for (dst, src) in addr.sun_path.iter_mut().zip(bytes) {
+        *dst = *src as libc::c_char;
+    }
+    let addr_len = (std::mem::size_of::<libc::sa_family_t>() + bytes.len() + 1) as libc::socklen_t;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        // SAFETY: `addr` is a valid sockaddr_un of `addr_len` bytes.
+        let rc = unsafe {
+            libc::connect(
+                stream.as_raw_fd(),
+                &addr as *const libc::sockaddr_un as *const libc::sockaddr,
+                addr_len,
+            )
+        };
+        if rc == 0 {
+            break;
+        }
+
+        let err = io::Error::last_os_error();
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match err.raw_os_error() {
+            // Listen backlog full: Unix sockets never report EINPROGRESS
+            // for this, so retry until the deadline.
+            Some(libc::EAGAIN) if !remaining.is_zero() => {
+                std::thread::sleep(remaining.min(Duration::from_millis(10)));
+            }
+            Some(libc::EAGAIN) => {
+                return Err(io::Error::new(ErrorKind::TimedOut, "connect timed out"));
+            }
+            Some(libc::EINPROGRESS) => {
+                wait_writable(&stream, remaining)?;
+                break;
+            }
+            Some(libc::EINTR) => {}
+            _ => return Err(err),
+        }
+    }
+
+    stream.set_nonblocking(false)?;
+    Ok(stream)
+}
+
+/// Wait for a pending non-blocking connect and report its outcome.
+fn wait_writable(stream: &UnixStream, timeout: Duration) -> io::Result<()> {
+    let mut pfd = libc::pollfd {
+        fd: stream.as_raw_fd(),
+        events: libc::POLLOUT,
+        revents: 0,
+    };
+    let millis = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+    // SAFETY: `pfd` is a single valid pollfd.
+    let ready = unsafe { libc::poll(&mut pfd, 1, millis) };
+    if ready < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if ready == 0 {
+        return Err(io::Error::new(ErrorKind::TimedOut, "connect timed out"));
+    }
+    match stream.take_error()? {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+// ============================================================================
+// Batch helpers
+// ============================================================================
+
+/// Detect an error in a single Hyprland reply.
+/// Returns the error message, or None if the reply looks successful.
+pub(crate) fn reply_error(command: &str, reply: &str, json: bool) -> Option<String> {
+    let trimmed = reply.trim();
+    if trimmed.eq_ignore_ascii_case("unknown request") {
+        return Some(trimmed.to_string());
+    }
+
+    let verb = command.split_whitespace().next().unwrap_or_default();
+    if ACTION_COMMANDS.contains(&verb) {
+        return (trimmed != "ok").then(|| trimmed.to_string());
+    }
+
+    if json && serde_json::from_str::<serde_json::Value>(trimmed).is_err() {
+        return Some(trimmed.to_string());
+    }
+    None
+}
+
+/// Whether `command` has a `;` Hyprland would split a batch on. Like
+/// Hyprland, `;` inside `[...]` (exec rules such as
+/// `exec [workspace 2 silent; float] kitty`) doesn't count.
+fn splits_batch(command: &str) -> bool {
+    let mut depth = 0i32;
+    command.chars().any(|c| {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        c == ';' && depth == 0
+    })
+}
+
+/// Split a batch response into one reply per command.
+fn split_batch_response(response: &str, count: usize, json: bool) -> Option<Vec<String>> {
+    if count <= 1 {
+        return Some(vec![response.to_string()]);
+    }
+
+    // Hyprland 0.40+: replies separated by blank lines
+    let parts: Vec<&str> = response.split(BATCH_SEPARATOR).collect();
+    if parts.len() == count {
+        return Some(parts.into_iter().map(str::to_string).collect());
+    }
+
+    // Older releases concatenate replies; JSON values are self-delimiting
+    if json {
+        let mut parts = Vec::with_capacity(count);
+        let mut stream =
+            serde_json::Deserializer::from_str(response).into_iter::<serde_json::Value>();
+        let mut start = 0;
+        while let Some(Ok(_)) = stream.next() {
+            let end = stream.byte_offset();
+            parts.push(response[start..end].trim().to_string());
+            start = end;
+        }
+        return (parts.len() == count).then_some(parts);
+    }
+
+    // All-dispatch batches reply with "okok..."
+    (response.trim() == "ok".repeat(count)).then(|| vec!["ok".to_string(); count])
+}
+
+/// Reply to one command of a batched Hyprland request.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct BatchReply {
+    pub command: String,
+    pub output: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl BatchReply {
+    fn __repr__(&self) -> String {
+        format!("BatchReply(command={:?}, ok={})", self.command, self.ok)
+    }
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timeouts {
+    pub connect: Option<Duration>,
+    pub read: Option<Duration>,
+    pub write: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Some(DEFAULT_CONNECT_TIMEOUT),
+            read: Some(DEFAULT_READ_TIMEOUT),
+            write: Some(DEFAULT_WRITE_TIMEOUT),
+        }
+    }
+}
+
+/// A timeout in seconds; must be positive (sockets can't take a zero
+/// timeout, and None already means no timeout).
+fn seconds(value: Option<f64>) -> PyResult<Option<Duration>> {
+    value
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .ok()
+                .filter(|d| !d.is_zero())
+                .ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Invalid timeout: {} (must be positive, or None for no timeout)",
+                        secs
+                    ))
+                })
+        })
+        .transpose()
+}
+
+/// Hyprland IPC client bound to one instance.
+#[pyclass(frozen)]
+pub struct HyprlandClient {
+    signature: String,
+    socket_path: PathBuf,
+    timeouts: Timeouts,
+}
+
+impl HyprlandClient {
+    /// Build a client for `instance`, or the default instance if None.
+    pub(crate) fn resolve(instance: Option<&str>, timeouts: Timeouts) -> Result<Self, IpcError> {
+        let (signature, dir) = instances::resolve(instance)?;
+        let socket_path = dir.join(".socket.sock");
+        Ok(Self {
+            signature,
+            socket_path,
+            timeouts,
+        })
+    }
+
+    /// Send one request, retrying once if the compositor refused the
+    /// connection (typically mid-restart).
+    pub(crate) fn send(&self, request: &str) -> Result<String, IpcError> {
+        match self.send_once(request) {
+            Err(IpcError::Connect(e)) if e.kind() == ErrorKind::ConnectionRefused => {
+                std::thread::sleep(RETRY_DELAY);
+                self.send_once(request)
+            }
+            result => result,
+        }
+    }
+
+    fn send_once(&self, request: &str) -> Result<String, IpcError> {
+        let mut stream =
+            connect_with_timeout(&self.socket_path, self.timeouts.connect).map_err(|e| {
+                if is_timeout(&e) {
+                    IpcError::Timeout("connect")
+                } else {
+                    IpcError::Connect(e)
+                }
+            })?;
+        stream
+            .set_read_timeout(self.timeouts.read)
+            .and_then(|_| stream.set_write_timeout(self.timeouts.write))
+            .map_err(IpcError::Io)?;
+
+        stream.write_all(request.as_bytes()).map_err(|e| {
+            if is_timeout(&e) {
+                IpcError::Timeout("write")
+            } else {
+                IpcError::Io(e)
+            }
+        })?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(|e| {
+            if is_timeout(&e) {
+                IpcError::Timeout("read")
+            } else {
+                IpcError::Io(e)
+            }
+        })?;
+
+        Ok(response)
+    }
+
+    /// Send a request with the GIL released.
+    pub(crate) fn request(&self, py: Python<'_>, request: &str) -> PyResult<String> {
+        Ok(py.allow_threads(|| self.send(request))?)
+    }
+
+    pub(crate) fn request_json(&self, py: Python<'_>, command: &str) -> PyResult<String> {
+        self.request(py, &format!("j/{}", command))
+    }
+
+    pub(crate) fn batch(
+        &self,
+        py: Python<'_>,
+        commands: Vec<String>,
+        json: bool,
+    ) -> PyResult<Vec<BatchReply>> {
+        if commands.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(bad) = commands.iter().find(|c| splits_batch(c)) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Batch command must not contain ';' outside [...]: {:?}",
+                bad
+            )));
+        }
+
+        let prefix = if json { "j/" } else { "" };
+        let request = commands
+            .iter()
+            .map(|c| format!("{}{}", prefix, c.trim()))
+            .collect::<Vec<_>>()
+            .join(";");
+        let response = self.request(py, &format!("[[BATCH]]{}", request))?;
+
+        let parts = split_batch_response(&response, commands.len(), json).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Could not split batch response into {} replies",
+                commands.len()
+            ))
+        })?;
+
+        Ok(commands
+            .into_iter()
+            .zip(parts)
+            .map(|(command, output)| {
+                let error = reply_error(&command, &output, json);
+                BatchReply {
+                    ok: error.is_none(),
+                    command,
+                    output,
+                    error,
+                }
+            })
+            .collect())
+    }
+}
+
+#[pymethods]
+impl HyprlandClient {
+    /// Resolve the instance once. Timeouts are in seconds; None disables one.
+    #[new]
+    #[pyo3(signature = (connect_timeout=1.0, read_timeout=5.0, write_timeout=1.0, instance=None))]
+    fn new(
+        connect_timeout: Option<f64>,
+        read_timeout: Option<f64>,
+        write_timeout: Option<f64>,
+        instance: Option<&str>,
+    ) -> PyResult<Self> {
+        let timeouts = Timeouts {
+            connect: seconds(connect_timeout)?,
+            read: seconds(read_timeout)?,
+            write: seconds(write_timeout)?,
+        };
+        Ok(Self::resolve(instance, timeouts)?)
+    }
+
+    /// Instance signature this client talks to.
+    #[getter]
+    fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Resolved `.socket.sock` path.
+    #[getter]
+    fn socket_path(&self) -> String {
+        self.socket_path.to_string_lossy().into_owned()
+    }
+
+    fn hyprctl(&self, py: Python<'_>, command: &str) -> PyResult<String> {
+        self.request(py, command)
+    }
+
+    fn hyprctl_json(&self, py: Python<'_>, command: &str) -> PyResult<String> {
+        self.request_json(py, command)
+    }
+
+    #[pyo3(signature = (commands, json=false))]
+    fn hyprctl_batch(
+        &self,
+        py: Python<'_>,
+        commands: Vec<String>,
+        json: bool,
+    ) -> PyResult<Vec<BatchReply>> {
+        self.batch(py, commands, json)
+    }
+
+    /// Run a dispatcher; raises HyprlandDispatchError unless Hyprland replies `ok`.
+    #[pyo3(signature = (name, args=Vec::new()))]
+    fn dispatch(&self, py: Python<'_>, name: &str, args: Vec<String>) -> PyResult<()> {
+        dispatch::run_dispatch(py, self, name, &args)
+    }
+
+    /// Validate and apply a monitor layout; returns the `monitor=` config lines.
+    #[pyo3(signature = (configs, dry_run=false))]
+    fn apply_layout(
+        &self,
+        py: Python<'_>,
+        configs: Vec<layout::MonitorConfig>,
+        dry_run: bool,
+    ) -> PyResult<Vec<String>> {
+        layout::apply_configs(py, self, &configs, dry_run)
+    }
+
+    /// Like `save_layout_profile()`, on this client's instance.
+    fn save_layout_profile(&self, py: Python<'_>, name: &str) -> PyResult<profiles::LayoutProfile> {
+        profiles::save_profile(py, self, name)
+    }
+
+    /// Like `restore_layout_profile()`, on this client's instance.
+    #[pyo3(signature = (name, dry_run=false))]
+    fn restore_layout_profile(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        dry_run: bool,
+    ) -> PyResult<Vec<String>> {
+        let profile = profiles::read_profile(name)?;
+        profiles::restore_profile(py, self, &profile, dry_run)
+    }
+
+    #[pyo3(signature = (all=false))]
+    fn get_monitors(&self, py: Python<'_>, all: bool) -> PyResult<Vec<models::Monitor>> {
+        let command = if all { "monitors all" } else { "monitors" };
+        models::parse(&self.request_json(py, command)?)
+    }
+
+    fn get_workspaces(&self, py: Python<'_>) -> PyResult<Vec<models::Workspace>> {
+        models::parse(&self.request_json(py, "workspaces")?)
+    }
+
+    fn get_active_workspace(&self, py: Python<'_>) -> PyResult<models::Workspace> {
+        models::parse(&self.request_json(py, "activeworkspace")?)
+    }
+
+    fn get_clients(&self, py: Python<'_>) -> PyResult<Vec<models::Client>> {
+        models::parse(&self.request_json(py, "clients")?)
+    }
+
+    fn get_active_window(&self, py: Python<'_>) -> PyResult<Option<models::Client>> {
+        models::parse_active_window(&self.request_json(py, "activewindow")?)
+    }
+
+    fn get_layers(&self, py: Python<'_>) -> PyResult<Vec<models::Layer>> {
+        models::parse_layers(&self.request_json(py, "layers")?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("HyprlandClient(signature={:?})", self.signature)
+    }
+}
+
+// ============================================================================
+// Module-level functions
+// ============================================================================
+
+/// Query Hyprland IPC directly via Unix socket.
+#[pyfunction]
+#[pyo3(signature = (command, instance=None))]
+pub fn hyprctl(py: Python<'_>, command: &str, instance: Option<&str>) -> PyResult<String> {
+    HyprlandClient::resolve(instance, Timeouts::default())?.request(py, command)
+}
+
+/// Query Hyprland IPC with JSON output.
+#[pyfunction]
+#[pyo3(signature = (command, instance=None))]
+pub fn hyprctl_json(py: Python<'_>, command: &str, instance: Option<&str>) -> PyResult<String> {
+    HyprlandClient::resolve(instance, Timeouts::default())?.request_json(py, command)
+}
+
+/// Send several commands in one `[[BATCH]]` request.
+/// Returns one BatchReply per command, in order.
+#[pyfunction]
+#[pyo3(signature = (commands, json=false, instance=None))]
+pub fn hyprctl_batch(
+    py: Python<'_>,
+    commands: Vec<String>,
+    json: bool,
+    instance: Option<&str>,
+) -> PyResult<Vec<BatchReply>> {
+    HyprlandClient::resolve(instance, Timeouts::default())?.batch(py, commands, json)
+}
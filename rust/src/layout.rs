@@ -0,0 +1,271 @@
+//! Monitor layout configuration via `keyword monitor`.
+//!
+//! A layout is a list of `MonitorConfig`s. Each is validated against the
+//! modes Hyprland reports for that output, then applied as one batched
+//! `keyword monitor` request. `to_config_line()` gives the equivalent
+//! `monitor=` line for hyprland.conf.
+
+use pyo3::prelude::*;
+
+use crate::ipc::{HyprlandClient, Timeouts};
+use crate::models::{self, Monitor};
+
+/// Mode keywords Hyprland resolves itself; not checked against availableModes.
+const MODE_KEYWORDS: &[&str] = &["preferred", "highres", "highrr", "maxwidth"];
+
+/// Refresh rates within this many Hz are considered the same mode
+/// (Hyprland reports e.g. 164.96Hz for a nominal 165Hz mode).
+const REFRESH_TOLERANCE: f64 = 0.5;
+
+/// Desired configuration for one monitor.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, set_all)]
+pub struct MonitorConfig {
+    /// Connector name (`DP-1`) or `desc:` selector.
+    pub name: String,
+    /// `WxH@Hz`, `WxH`, or a keyword like `preferred`.
+    pub mode: String,
+    /// Top-left position in layout pixels; None lets Hyprland place it.
+    pub position: Option<(i32, i32)>,
+    pub scale: f64,
+    /// Hyprland transform (0-7).
+    pub transform: i32,
+    pub enabled: bool,
+}
+
+impl MonitorConfig {
+    /// Snapshot a monitor's current state as a config.
+    pub(crate) fn from_monitor(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name.clone(),
+            mode: format!(
+                "{}x{}@{:.2}",
+                monitor.width, monitor.height, monitor.refresh_rate
+            ),
+            position: Some((monitor.x, monitor.y)),
+            scale: monitor.scale,
+            transform: monitor.transform,
+            enabled: !monitor.disabled,
+        }
+    }
+
+    /// The rule as passed to `keyword monitor`.
+    pub(crate) fn rule(&self) -> String {
+        if !self.enabled {
+            return format!("{},disable", self.name);
+        }
+        let position = match self.position {
+            Some((x, y)) => format!("{}x{}", x, y),
+            None => "auto".to_string(),
+        };
+        let mut rule = format!("{},{},{},{}", self.name, self.mode, position, self.scale);
+        if self.transform != 0 {
+            rule.push_str(&format!(",transform,{}", self.transform));
+        }
+        rule
+    }
+}
+
+#[pymethods]
+impl MonitorConfig {
+    #[new]
+    #[pyo3(signature = (name, mode="preferred".to_string(), position=None, scale=1.0, transform=0, enabled=true))]
+    fn new(
+        name: String,
+        mode: String,
+        position: Option<(i32, i32)>,
+        scale: f64,
+        transform: i32,
+        enabled: bool,
+    ) -> Self {
+        Self {
+            name,
+            mode,
+            position,
+            scale,
+            transform,
+            enabled,
+        }
+    }
+
+    /// Rule text for `keyword monitor` (without the keyword).
+    fn to_rule(&self) -> String {
+        self.rule()
+    }
+
+    /// Equivalent hyprland.conf line, e.g. `monitor=DP-1,2560x1440@165,0x0,1`.
+    fn to_config_line(&self) -> String {
+        format!("monitor={}", self.rule())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("MonitorConfig({:?})", self.rule())
+    }
+}
+
+/// Parse `WxH[@Hz][Hz]` into (width, height, refresh).
+fn parse_mode(mode: &str) -> Option<(i32, i32, Option<f64>)> {
+    let mode = mode.trim().trim_end_matches("Hz");
+    let (size, refresh) = match mode.split_once('@') {
+        Some((size, refresh)) => (size, Some(refresh.parse().ok()?)),
+        None => (mode, None),
+    };
+    let (w, h) = size.split_once('x')?;
+    Some((w.parse().ok()?, h.parse().ok()?, refresh))
+}
+
+/// Find the monitor a config refers to, by connector name or `desc:` prefix.
+fn find_monitor<'a>(monitors: &'a [Monitor], name: &str) -> Option<&'a Monitor> {
+    match name.strip_prefix("desc:") {
+        Some(desc) => monitors.iter().find(|m| m.description.starts_with(desc)),
+        None => monitors.iter().find(|m| m.name == name),
+    }
+}
+
+/// Check one config against the monitors Hyprland knows about.
+fn config_problems(config: &MonitorConfig, monitors: &[Monitor]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(monitor) = find_monitor(monitors, &config.name) else {
+        problems.push(format!("{}: no such monitor", config.name));
+        return problems;
+    };
+    if !config.enabled {
+        return problems;
+    }
+
+    if !(0..=7).contains(&config.transform) {
+        problems.push(format!(
+            "{}: transform must be 0-7, got {}",
+            config.name, config.transform
+        ));
+    }
+    if config.scale <= 0.0 || !config.scale.is_finite() {
+        problems.push(format!(
+            "{}: scale must be positive, got {}",
+            config.name, config.scale
+        ));
+    }
+
+    if MODE_KEYWORDS.contains(&config.mode.as_str()) {
+        return problems;
+    }
+    let Some((width, height, refresh)) = parse_mode(&config.mode) else {
+        problems.push(format!("{}: invalid mode {:?}", config.name, config.mode));
+        return problems;
+    };
+    let supported = monitor.available_modes.iter().any(|available| {
+        parse_mode(available).is_some_and(|(w, h, r)| {
+            w == width
+                && h == height
+                && match (refresh, r) {
+                    (Some(want), Some(have)) => (want - have).abs() < REFRESH_TOLERANCE,
+                    _ => true,
+                }
+        })
+    });
+    if !supported {
+        problems.push(format!(
+            "{}: mode {} not supported (available: {})",
+            config.name,
+            config.mode,
+            monitor.available_modes.join(", ")
+        ));
+    }
+    problems
+}
+
+/// Validate every config; returns all problems found (empty if valid).
+pub(crate) fn layout_problems(configs: &[MonitorConfig], monitors: &[Monitor]) -> Vec<String> {
+    let mut problems: Vec<String> = configs
+        .iter()
+        .flat_map(|c| config_problems(c, monitors))
+        .collect();
+    // Monitors the layout doesn't mention keep their current state
+    let untouched_enabled = monitors.iter().any(|m| {
+        !m.disabled
+            && !configs
+                .iter()
+                .any(|c| find_monitor(std::slice::from_ref(m), &c.name).is_some())
+    });
+    if !configs.is_empty() && !untouched_enabled && configs.iter().all(|c| !c.enabled) {
+        problems.push("layout disables every monitor".to_string());
+    }
+    problems
+}
+
+/// Validate and apply configs through one batched `keyword monitor` request.
+pub(crate) fn apply_configs(
+    py: Python<'_>,
+    client: &HyprlandClient,
+    configs: &[MonitorConfig],
+    dry_run: bool,
+) -> PyResult<Vec<String>> {
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    let problems = layout_problems(configs, &monitors);
+    if !problems.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            problems.join("; "),
+        ));
+    }
+
+    let lines: Vec<String> = configs
+        .iter()
+        .map(|c| format!("monitor={}", c.rule()))
+        .collect();
+    if dry_run {
+        return Ok(lines);
+    }
+
+    let commands: Vec<String> = configs
+        .iter()
+        .map(|c| format!("keyword monitor {}", c.rule()))
+        .collect();
+    let failures: Vec<String> = client
+        .batch(py, commands, false)?
+        .into_iter()
+        .filter_map(|r| r.error.map(|e| format!("{}: {}", r.command, e)))
+        .collect();
+    if !failures.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+            failures.join("; "),
+        ));
+    }
+    Ok(lines)
+}
+
+/// Check a layout against the connected monitors without applying it.
+/// Returns a list of problems; empty means the layout is valid.
+#[pyfunction]
+#[pyo3(signature = (configs, instance=None))]
+pub fn validate_layout(
+    py: Python<'_>,
+    configs: Vec<MonitorConfig>,
+    instance: Option<&str>,
+) -> PyResult<Vec<String>> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    Ok(layout_problems(&configs, &monitors))
+}
+
+/// Apply a monitor layout. Returns the equivalent `monitor=` config lines.
+/// With `dry_run=True` nothing is sent to Hyprland beyond validation queries.
+#[pyfunction]
+#[pyo3(signature = (configs, dry_run=false, instance=None))]
+pub fn apply_layout(
+    py: Python<'_>,
+    configs: Vec<MonitorConfig>,
+    dry_run: bool,
+    instance: Option<&str>,
+) -> PyResult<Vec<String>> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    apply_configs(py, &client, &configs, dry_run)
+}
+
+/// Snapshot the current layout of all monitors (including disabled ones).
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn current_layout(py: Python<'_>, instance: Option<&str>) -> PyResult<Vec<MonitorConfig>> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    Ok(monitors.iter().map(MonitorConfig::from_monitor).collect())
+}
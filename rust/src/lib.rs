@@ -1,21 +1,59 @@
 //! Native acceleration for matuwrap CLI tool.
 //!
 //! Provides fast implementations of:
-//! - Hyprland IPC via Unix socket (no subprocess overhead)
-//! - Matugen color caching with mtime validation
+//! - Hyprland IPC via Unix socket (no subprocess overhead), with batching
+//!   and timeouts
+//! - Hyprland dispatchers with reply validation
+//! - Hyprland instance discovery (works without HYPRLAND_INSTANCE_SIGNATURE)
+//! - Hyprland event stream subscription (socket2)
+//! - Typed Hyprland data model (monitors, workspaces, clients, layers)
+//! - Monitor layout validation and application via `keyword monitor`,
+//!   with named profiles matched by make/model/serial and automatic
+//!   switching on hotplug
+//! - Matugen color caching keyed by wallpaper content hash (bounded LRU),
+//!   per scheme type, contrast and source index, keeping dark and light
+//!   together; native Material You extraction when matugen is not installed
+//! - Color math: hex/RGB/HSV/HSL/XYZ/xy/Lab/OKLCH/HCT conversions, WCAG
+//!   contrast and tone adjustments (`wrp_native.color`)
+//! - WCAG contrast audit of palette role pairs, with optional tone fixes
+//! - Palette export to terminal and app color files (Xresources, kitty,
+//!   alacritty, foot, GTK, CSS, design tokens) with derived ANSI colors
+//! - Theme file templates rendered from the cached palette, with reload
+//!   hooks (signals, commands, Hyprland keywords)
+//! - Terminal color depth detection (truecolor, 256, 16, `NO_COLOR`) and
+//!   palette quantization to the perceptually nearest xterm colors (OKLab)
+//! - Shell prompts for bash, zsh, fish and nushell from a small format
+//!   language, in truecolor, 256 or 16 colors depending on the terminal
+//! - Wallpaper watching via inotify, regenerating colors, templates and
+//!   shell cache files when it changes
 //! - PipeWire sink enumeration via pw-dump
 //! - System information queries
+//! - `wrp-fast`, a binary built from the same code without Python for
+//!   hot paths (colors, prompts, monitors, sinks)
+
+mod audio;
+mod color;
+mod colors;
+mod contrast;
+mod dispatch;
+mod events;
+mod export;
+pub mod fast;
+mod hooks;
+mod hotplug;
+mod instances;
+mod ipc;
+mod layout;
+mod material;
+mod models;
+mod profiles;
+mod prompt;
+mod templates;
+mod terminal;
+mod watcher;
 
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::io::{Read, Write};
-use std::os::unix::net::UnixStream;
-use std::path::PathBuf;
 use std::process::Command;
-use std::time::SystemTime;
 
 // ============================================================================
 // Command execution
@@ -39,330 +77,6 @@ fn run_command(program: &str, args: Vec<String>) -> PyResult<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-// ============================================================================
-// Hyprland IPC
-// ============================================================================
-
-/// Query Hyprland IPC directly via Unix socket.
-#[pyfunction]
-fn hyprctl(command: &str) -> PyResult<String> {
-    let his = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").map_err(|_| {
-        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("HYPRLAND_INSTANCE_SIGNATURE not set")
-    })?;
-
-    // Try XDG_RUNTIME_DIR first (Hyprland 0.40+), fallback to /tmp
-    let socket_path = if let Ok(xdg) = std::env::var("XDG_RUNTIME_DIR") {
-        let xdg_path = format!("{}/hypr/{}/.socket.sock", xdg, his);
-        if std::path::Path::new(&xdg_path).exists() {
-            xdg_path
-        } else {
-            format!("/tmp/hypr/{}/.socket.sock", his)
-        }
-    } else {
-        format!("/tmp/hypr/{}/.socket.sock", his)
-    };
-
-    let mut stream = UnixStream::connect(&socket_path).map_err(|e| {
-        PyErr::new::<pyo3::exceptions::PyConnectionError, _>(format!(
-            "Failed to connect to Hyprland socket: {}",
-            e
-        ))
-    })?;
-
-    stream
-        .write_all(command.as_bytes())
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
-
-    let mut response = String::new();
-    stream
-        .read_to_string(&mut response)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
-
-    Ok(response)
-}
-
-/// Query Hyprland IPC with JSON output.
-#[pyfunction]
-fn hyprctl_json(command: &str) -> PyResult<String> {
-    hyprctl(&format!("j/{}", command))
-}
-
-// ============================================================================
-// Matugen color caching
-// ============================================================================
-
-#[derive(Serialize, Deserialize)]
-struct ColorCache {
-    wallpaper_path: String,
-    wallpaper_mtime: u64,
-    colors: HashMap<String, String>,
-}
-
-fn get_cache_path() -> Option<PathBuf> {
-    dirs::cache_dir().map(|p| p.join("matuwrap").join("colors.json"))
-}
-
-fn get_mtime(path: &str) -> Option<u64> {
-    fs::metadata(path)
-        .ok()?
-        .modified()
-        .ok()?
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .ok()
-        .map(|d| d.as_secs())
-}
-
-fn load_cache(wallpaper_path: &str) -> Option<HashMap<String, String>> {
-    let cache_path = get_cache_path()?;
-    let data = fs::read_to_string(&cache_path).ok()?;
-    let cache: ColorCache = serde_json::from_str(&data).ok()?;
-
-    // Validate cache
-    if cache.wallpaper_path != wallpaper_path {
-        return None;
-    }
-
-    let current_mtime = get_mtime(wallpaper_path)?;
-    if cache.wallpaper_mtime != current_mtime {
-        return None;
-    }
-
-    Some(cache.colors)
-}
-
-fn save_cache(wallpaper_path: &str, colors: &HashMap<String, String>) -> Option<()> {
-    let cache_path = get_cache_path()?;
-    fs::create_dir_all(cache_path.parent()?).ok()?;
-
-    let cache = ColorCache {
-        wallpaper_path: wallpaper_path.to_string(),
-        wallpaper_mtime: get_mtime(wallpaper_path)?,
-        colors: colors.clone(),
-    };
-
-    let json = serde_json::to_string(&cache).ok()?;
-    fs::write(&cache_path, json).ok()?;
-    Some(())
-}
-
-fn run_matugen(wallpaper_path: &str) -> Option<HashMap<String, String>> {
-    // Resolve symlinks so matugen gets a real file path
-    let resolved = std::fs::canonicalize(wallpaper_path)
-        .ok()?;
-    let resolved_str = resolved.to_str()?;
-
-    let output = Command::new("matugen")
-        .args([
-            "image",
-            resolved_str,
-            "-t",
-            "scheme-tonal-spot",
-            "-m",
-            "dark",
-            "-j",
-            "hex",
-            "--source-color-index",
-            "0",
-        ])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    
-    // Parse JSON (matugen outputs on stdout)
-    let json: serde_json::Value = serde_json::from_str(&stdout).ok()?;
-    let colors_obj = json.get("colors")?;
-    let mut colors = HashMap::new();
-
-    if let Some(obj) = colors_obj.as_object() {
-        for (key, val) in obj {
-            // matugen 4.0.0 structure: {color_name: {dark: {color: "#hex"}, light: {...}}}
-            let color = val
-                .get("dark")
-                .and_then(|dark| dark.get("color"))
-                .and_then(|c| c.as_str())
-                .map(|s| s.to_string())
-                .or_else(|| {
-                    val.get("default")
-                        .and_then(|def| def.get("color"))
-                        .and_then(|c| c.as_str())
-                        .map(|s| s.to_string())
-                })
-                .or_else(|| {
-                    val.get("light")
-                        .and_then(|light| light.get("color"))
-                        .and_then(|c| c.as_str())
-                        .map(|s| s.to_string())
-                });
-
-            if let Some(hex) = color {
-                colors.insert(key.clone(), hex);
-            }
-        }
-    }
-
-    Some(colors)
-}
-/// Get matugen colors with caching.
-/// Returns a dict of color_name -> hex_value.
-/// Returns None if matugen fails (caller should use defaults).
-#[pyfunction]
-fn get_cached_colors(py: Python<'_>, wallpaper_path: &str) -> PyResult<Option<PyObject>> {
-    // Try cache first
-    if let Some(colors) = load_cache(wallpaper_path) {
-        let dict = PyDict::new(py);
-        for (k, v) in colors {
-            dict.set_item(k, v)?;
-        }
-        return Ok(Some(dict.into()));
-    }
-
-    // Run matugen
-    let colors = match run_matugen(wallpaper_path) {
-        Some(c) => c,
-        None => return Ok(None),
-    };
-
-    // Save to cache
-    let _ = save_cache(wallpaper_path, &colors);
-
-    // Return as Python dict
-    let dict = PyDict::new(py);
-    for (k, v) in colors {
-        dict.set_item(k, v)?;
-    }
-    Ok(Some(dict.into()))
-}
-
-/// Invalidate the color cache.
-#[pyfunction]
-fn invalidate_color_cache() -> PyResult<()> {
-    if let Some(cache_path) = get_cache_path() {
-        let _ = fs::remove_file(cache_path);
-    }
-    Ok(())
-}
-
-// ============================================================================
-// PipeWire sinks via wpctl status (fast text parsing)
-// ============================================================================
-
-#[derive(Debug, Clone)]
-#[pyclass]
-struct AudioSink {
-    #[pyo3(get)]
-    id: u32,
-    #[pyo3(get)]
-    name: String,
-    #[pyo3(get)]
-    description: String,
-    #[pyo3(get)]
-    volume: Option<f64>,
-    #[pyo3(get)]
-    is_default: bool,
-}
-
-#[pymethods]
-impl AudioSink {
-    fn __repr__(&self) -> String {
-        format!(
-            "AudioSink(id={}, name={:?}, is_default={})",
-            self.id, self.name, self.is_default
-        )
-    }
-}
-
-/// Parse a sink line from wpctl status output.
-/// Format: " │  *   34. HyperX Cloud Alpha... [vol: 0.60]"
-fn parse_sink_line(line: &str) -> Option<AudioSink> {
-    let is_default = line.contains('*');
-
-    // Remove tree chars and asterisk, find the ID
-    let cleaned: String = line
-        .chars()
-        .skip_while(|c| !c.is_ascii_digit())
-        .collect();
-
-    // Parse "34. Name [vol: 0.60]"
-    let dot_pos = cleaned.find('.')?;
-    let id: u32 = cleaned[..dot_pos].trim().parse().ok()?;
-
-    let rest = cleaned[dot_pos + 1..].trim();
-
-    // Extract volume if present
-    let (name, volume) = if let Some(vol_start) = rest.find("[vol:") {
-        let name = rest[..vol_start].trim();
-        let vol_str = &rest[vol_start + 5..];
-        let vol_end = vol_str.find(']').unwrap_or(vol_str.len());
-        let volume: Option<f64> = vol_str[..vol_end].trim().parse().ok();
-        (name, volume)
-    } else {
-        (rest, None)
-    };
-
-    Some(AudioSink {
-        id,
-        name: name.to_string(),
-        description: String::new(),
-        volume,
-        is_default,
-    })
-}
-
-/// Get audio sinks from PipeWire via wpctl status.
-/// Fast: single subprocess + efficient text parsing.
-#[pyfunction]
-fn get_audio_sinks() -> PyResult<Vec<AudioSink>> {
-    let output = Command::new("wpctl")
-        .arg("status")
-        .output()
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()))?;
-
-    if !output.status.success() {
-        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-            "wpctl status failed",
-        ));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-
-    // Find Sinks section and parse
-    let mut in_sinks = false;
-    let mut sinks = Vec::new();
-
-    for line in stdout.lines() {
-        if line.contains("Sinks:") {
-            in_sinks = true;
-            continue;
-        }
-        if in_sinks && (line.contains("Sources:") || line.contains("Streams:") || line.contains("Filters:")) {
-            break;
-        }
-        if in_sinks {
-            if let Some(sink) = parse_sink_line(line) {
-                sinks.push(sink);
-            }
-        }
-    }
-
-    Ok(sinks)
-}
-
-/// Set the default audio sink by ID.
-#[pyfunction]
-fn set_default_sink(sink_id: u32) -> PyResult<bool> {
-    let output = Command::new("wpctl")
-        .args(["set-default", &sink_id.to_string()])
-        .output()
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()))?;
-
-    Ok(output.status.success())
-}
-
 // ============================================================================
 // System info
 // ============================================================================
@@ -401,17 +115,126 @@ fn wrp_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(run_command, m)?)?;
 
     // Hyprland
-    m.add_function(wrap_pyfunction!(hyprctl, m)?)?;
-    m.add_function(wrap_pyfunction!(hyprctl_json, m)?)?;
+    m.add_function(wrap_pyfunction!(ipc::hyprctl, m)?)?;
+    m.add_function(wrap_pyfunction!(ipc::hyprctl_json, m)?)?;
+    m.add_class::<ipc::BatchReply>()?;
+    m.add_function(wrap_pyfunction!(ipc::hyprctl_batch, m)?)?;
+    m.add_class::<ipc::HyprlandClient>()?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_workspace, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_move_to_workspace, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_focus_monitor, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_dpms, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_exec, m)?)?;
+    m.add_function(wrap_pyfunction!(dispatch::dispatch_toggle_floating, m)?)?;
+    m.add(
+        "HyprlandDispatchError",
+        m.py().get_type::<dispatch::HyprlandDispatchError>(),
+    )?;
+    m.add_class::<instances::HyprInstance>()?;
+    m.add_function(wrap_pyfunction!(instances::list_instances, m)?)?;
+    m.add(
+        "HyprlandTimeoutError",
+        m.py().get_type::<ipc::HyprlandTimeoutError>(),
+    )?;
+    m.add_class::<models::WorkspaceRef>()?;
+    m.add_class::<models::Monitor>()?;
+    m.add_class::<models::Workspace>()?;
+    m.add_class::<models::Client>()?;
+    m.add_class::<models::Layer>()?;
+    m.add_function(wrap_pyfunction!(models::get_monitors, m)?)?;
+    m.add_function(wrap_pyfunction!(models::get_workspaces, m)?)?;
+    m.add_function(wrap_pyfunction!(models::get_active_workspace, m)?)?;
+    m.add_function(wrap_pyfunction!(models::get_clients, m)?)?;
+    m.add_function(wrap_pyfunction!(models::get_active_window, m)?)?;
+    m.add_function(wrap_pyfunction!(models::get_layers, m)?)?;
+    m.add_class::<layout::MonitorConfig>()?;
+    m.add_function(wrap_pyfunction!(layout::current_layout, m)?)?;
+    m.add_function(wrap_pyfunction!(layout::validate_layout, m)?)?;
+    m.add_function(wrap_pyfunction!(layout::apply_layout, m)?)?;
+    m.add_class::<profiles::ProfileMonitor>()?;
+    m.add_class::<profiles::LayoutProfile>()?;
+    m.add_function(wrap_pyfunction!(profiles::save_layout_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::load_layout_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::list_layout_profiles, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::delete_layout_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(profiles::restore_layout_profile, m)?)?;
+    m.add_class::<hotplug::LayoutSwitch>()?;
+    m.add_class::<hotplug::LayoutWatcher>()?;
+    m.add_function(wrap_pyfunction!(hotplug::find_matching_profile, m)?)?;
+    m.add_class::<events::HyprEvent>()?;
+    m.add_class::<events::HyprEventStream>()?;
 
     // Colors
-    m.add_function(wrap_pyfunction!(get_cached_colors, m)?)?;
-    m.add_function(wrap_pyfunction!(invalidate_color_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::get_cached_colors, m)?)?;
+    m.add_class::<colors::Palette>()?;
+    m.add_class::<colors::ColorScheme>()?;
+    m.add_function(wrap_pyfunction!(colors::get_cached_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::invalidate_color_cache, m)?)?;
+    m.add_class::<colors::CacheStats>()?;
+    m.add_function(wrap_pyfunction!(colors::cache_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::prune_cache, m)?)?;
+    m.add_class::<colors::CacheStatus>()?;
+    m.add_class::<colors::CacheLookup>()?;
+    m.add_function(wrap_pyfunction!(colors::cache_lookup, m)?)?;
+    m.add(
+        "ColorCacheCorruptWarning",
+        m.py().get_type::<colors::ColorCacheCorruptWarning>(),
+    )?;
+    m.add_function(wrap_pyfunction!(colors::get_color_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::set_color_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::toggle_color_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::get_scheme_options, m)?)?;
+    m.add_function(wrap_pyfunction!(colors::set_scheme_options, m)?)?;
+    m.add_function(wrap_pyfunction!(material::extract_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(material::extract_palette, m)?)?;
+
+    // Color math, as the `color` submodule
+    let color = color::module(m.py())?;
+    m.add_submodule(&color)?;
+    // add_submodule only sets an attribute; register it so that
+    // `from matuwrap.wrp_native.color import ...` works too
+    m.py()
+        .import("sys")?
+        .getattr("modules")?
+        .set_item("matuwrap.wrp_native.color", &color)?;
+
+    // Contrast audit
+    m.add_class::<contrast::ContrastPair>()?;
+    m.add_class::<contrast::ContrastReport>()?;
+    m.add_function(wrap_pyfunction!(contrast::audit_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(contrast::audit_contrast, m)?)?;
+
+    // Palette export
+    m.add_class::<export::ExportResult>()?;
+    m.add_function(wrap_pyfunction!(export::export_formats, m)?)?;
+    m.add_function(wrap_pyfunction!(export::export_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(export::ansi_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(export::export_files, m)?)?;
+
+    // Templates
+    m.add_class::<templates::TemplateResult>()?;
+    m.add_class::<hooks::HookResult>()?;
+    m.add_function(wrap_pyfunction!(templates::render_template, m)?)?;
+    m.add_function(wrap_pyfunction!(templates::render_templates, m)?)?;
+
+    // Terminal colors
+    m.add_class::<terminal::TerminalColor>()?;
+    m.add_function(wrap_pyfunction!(terminal::detect_color_depth, m)?)?;
+    m.add_function(wrap_pyfunction!(terminal::ansi_escape, m)?)?;
+    m.add_function(wrap_pyfunction!(terminal::quantize_palette, m)?)?;
+
+    // Shell prompts
+    m.add_function(wrap_pyfunction!(prompt::render_prompt, m)?)?;
+
+    // Wallpaper watching
+    m.add_class::<watcher::WallpaperChange>()?;
+    m.add_class::<watcher::WallpaperWatcher>()?;
 
     // Audio
-    m.add_class::<AudioSink>()?;
-    m.add_function(wrap_pyfunction!(get_audio_sinks, m)?)?;
-    m.add_function(wrap_pyfunction!(set_default_sink, m)?)?;
+    m.add_class::<audio::AudioSink>()?;
+    m.add_function(wrap_pyfunction!(audio::get_audio_sinks, m)?)?;
+    m.add_function(wrap_pyfunction!(audio::set_default_sink, m)?)?;
 
     // System info
     m.add_function(wrap_pyfunction!(memory_info, m)?)?;
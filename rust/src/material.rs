@@ -0,0 +1,312 @@
+//! Native Material You color extraction.
+//!
+//! Mirrors what `matugen image` does, without the binary: decode the
+//! wallpaper, downscale it, quantize with Celebi (Wu + WSMeans), rank the
+//! clusters with Material's score, and build the requested scheme from the
+//! chosen source color. Keys match matugen's JSON output.
+
+use image::ImageReader;
+use image::imageops::FilterType;
+use material_colors::color::Argb;
+use material_colors::dynamic_color::DynamicScheme;
+use material_colors::hct::Hct;
+use material_colors::quantize::{Quantizer, QuantizerCelebi};
+use material_colors::scheme::Scheme;
+use material_colors::scheme::variant::{
+    SchemeContent, SchemeExpressive, SchemeFidelity, SchemeFruitSalad, SchemeMonochrome,
+    SchemeNeutral, SchemeRainbow, SchemeTonalSpot, SchemeVibrant,
+};
+use material_colors::score::Score;
+use pyo3::prelude::*;
+use std::fmt;
+use std::path::Path;
+
+use crate::colors::{Colors, Palette};
+
+/// matugen downsamples to this size before quantizing.
+const RESIZE: u32 = 128;
+/// Clusters requested from the quantizer (matugen and Material use 128).
+const MAX_COLORS: usize = 128;
+
+/// Scheme variants, by matugen's `-t` name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SchemeType {
+    Content,
+    Expressive,
+    Fidelity,
+    FruitSalad,
+    Monochrome,
+    Neutral,
+    Rainbow,
+    TonalSpot,
+    Vibrant,
+}
+
+const SCHEME_TYPES: &[(&str, SchemeType)] = &[
+    ("scheme-content", SchemeType::Content),
+    ("scheme-expressive", SchemeType::Expressive),
+    ("scheme-fidelity", SchemeType::Fidelity),
+    ("scheme-fruit-salad", SchemeType::FruitSalad),
+    ("scheme-monochrome", SchemeType::Monochrome),
+    ("scheme-neutral", SchemeType::Neutral),
+    ("scheme-rainbow", SchemeType::Rainbow),
+    ("scheme-tonal-spot", SchemeType::TonalSpot),
+    ("scheme-vibrant", SchemeType::Vibrant),
+];
+
+impl SchemeType {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        SCHEME_TYPES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, t)| *t)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        SCHEME_TYPES
+            .iter()
+            .find(|(_, t)| *t == self)
+            .map(|(n, _)| *n)
+            .unwrap_or("scheme-tonal-spot")
+    }
+
+    fn build(self, source: Hct, dark: bool, contrast: Option<f64>) -> DynamicScheme {
+        match self {
+            SchemeType::Content => SchemeContent::new(source, dark, contrast).scheme,
+            SchemeType::Expressive => SchemeExpressive::new(source, dark, contrast).scheme,
+            SchemeType::Fidelity => SchemeFidelity::new(source, dark, contrast).scheme,
+            SchemeType::FruitSalad => SchemeFruitSalad::new(source, dark, contrast).scheme,
+            SchemeType::Monochrome => SchemeMonochrome::new(source, dark, contrast).scheme,
+            SchemeType::Neutral => SchemeNeutral::new(source, dark, contrast).scheme,
+            SchemeType::Rainbow => SchemeRainbow::new(source, dark, contrast).scheme,
+            SchemeType::TonalSpot => SchemeTonalSpot::new(source, dark, contrast).scheme,
+            SchemeType::Vibrant => SchemeVibrant::new(source, dark, contrast).scheme,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    Dark,
+    Light,
+}
+
+impl Mode {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Mode::Dark),
+            "light" => Some(Mode::Light),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Mode::Dark => "dark",
+            Mode::Light => "light",
+        }
+    }
+
+    pub(crate) fn toggled(self) -> Self {
+        match self {
+            Mode::Dark => Mode::Light,
+            Mode::Light => Mode::Dark,
+        }
+    }
+}
+
+/// Everything that selects a palette for a given image. Mode isn't part
+/// of it: a palette always has both the dark and light scheme.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SchemeOptions {
+    pub scheme_type: SchemeType,
+    /// -1.0 (reduced) to 1.0 (high); 0.0 is the standard spec.
+    pub contrast: f64,
+    pub source_index: usize,
+}
+
+impl SchemeOptions {
+    pub(crate) fn new(
+        scheme_type: &str,
+        contrast: f64,
+        source_index: usize,
+    ) -> Result<Self, String> {
+        let scheme_type = SchemeType::from_name(scheme_type).ok_or_else(|| {
+            let names: Vec<&str> = SCHEME_TYPES.iter().map(|(n, _)| *n).collect();
+            format!(
+                "Unknown scheme type {:?} (expected one of: {})",
+                scheme_type,
+                names.join(", ")
+            )
+        })?;
+        if !(-1.0..=1.0).contains(&contrast) {
+            return Err(format!(
+                "Contrast must be between -1.0 and 1.0, got {}",
+                contrast
+            ));
+        }
+        Ok(Self {
+            scheme_type,
+            contrast,
+            source_index,
+        })
+    }
+
+    /// Key identifying this palette in the color cache.
+    pub(crate) fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.scheme_type.name(),
+            self.contrast,
+            self.source_index
+        )
+    }
+}
+
+impl Default for SchemeOptions {
+    fn default() -> Self {
+        Self {
+            scheme_type: SchemeType::TonalSpot,
+            contrast: 0.0,
+            source_index: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ExtractError {
+    Io(std::io::Error),
+    Decode(image::ImageError),
+    NoSourceColor(usize),
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::Io(e) => write!(f, "Failed to read image: {}", e),
+            ExtractError::Decode(e) => write!(f, "Failed to decode image: {}", e),
+            ExtractError::NoSourceColor(index) => {
+                write!(f, "Image has no source color at index {}", index)
+            }
+        }
+    }
+}
+
+impl From<ExtractError> for PyErr {
+    fn from(e: ExtractError) -> PyErr {
+        match e {
+            ExtractError::Io(_) => PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()),
+            _ => PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()),
+        }
+    }
+}
+
+/// Decode an image and rank its candidate source colors, best first.
+pub(crate) fn source_colors(path: &Path) -> Result<Vec<Argb>, ExtractError> {
+    let image = ImageReader::open(path)
+        .map_err(ExtractError::Io)?
+        .with_guessed_format()
+        .map_err(ExtractError::Io)?
+        .decode()
+        .map_err(ExtractError::Decode)?
+        .resize_exact(RESIZE, RESIZE, FilterType::Triangle)
+        .into_rgba8();
+
+    let pixels: Vec<Argb> = image
+        .pixels()
+        .map(|p| {
+            let [r, g, b, a] = p.0;
+            Argb::new(a, r, g, b)
+        })
+        .collect();
+    let result = QuantizerCelebi::quantize(&pixels, MAX_COLORS);
+    Ok(Score::score(&result.color_to_count, None, None, None))
+}
+
+/// Scheme for `source`, as matugen-style `name -> #rrggbb`.
+pub(crate) fn scheme_colors(source: Argb, options: &SchemeOptions, mode: Mode) -> Colors {
+    let scheme = Scheme::from(options.scheme_type.build(
+        Hct::new(source),
+        mode == Mode::Dark,
+        Some(options.contrast),
+    ));
+    let mut colors: Colors = scheme
+        .into_iter()
+        .map(|(name, color)| (name, color.to_hex_with_pound()))
+        .collect();
+    colors.insert("source_color".to_string(), source.to_hex_with_pound());
+    colors
+}
+
+/// Extract both schemes of a palette from an image.
+pub(crate) fn extract(path: &Path, options: &SchemeOptions) -> Result<Palette, ExtractError> {
+    let ranked = source_colors(path)?;
+    let source = *ranked
+        .get(options.source_index)
+        .ok_or(ExtractError::NoSourceColor(options.source_index))?;
+    Ok(Palette {
+        dark: scheme_colors(source, options, Mode::Dark),
+        light: scheme_colors(source, options, Mode::Light),
+    })
+}
+
+/// Parse a mode argument; `None` uses the saved light/dark preference.
+pub(crate) fn mode_from_arg(mode: Option<&str>) -> PyResult<Mode> {
+    match mode {
+        Some(name) => Mode::from_name(name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown mode {:?} (expected \"dark\" or \"light\")",
+                name
+            ))
+        }),
+        None => Ok(crate::colors::preferred_mode()),
+    }
+}
+
+/// Parse scheme arguments as the Python API takes them; `None` uses the
+/// saved scheme options.
+pub(crate) fn options_from_args(
+    scheme_type: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+) -> PyResult<SchemeOptions> {
+    let saved = crate::colors::preferred_options();
+    SchemeOptions::new(
+        scheme_type.unwrap_or(saved.scheme_type.name()),
+        contrast.unwrap_or(saved.contrast),
+        source_index.unwrap_or(saved.source_index),
+    )
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Generate a Material You palette from an image without matugen.
+/// Returns a dict of color_name -> hex_value with the same keys as matugen.
+#[pyfunction]
+#[pyo3(signature = (image_path, scheme_type=None, mode=None, contrast=None, source_index=None))]
+pub fn extract_colors(
+    py: Python<'_>,
+    image_path: &str,
+    scheme_type: Option<&str>,
+    mode: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+) -> PyResult<Colors> {
+    let mode = mode_from_arg(mode)?;
+    let options = options_from_args(scheme_type, contrast, source_index)?;
+    let palette = py.allow_threads(|| extract(Path::new(image_path), &options))?;
+    Ok(palette.into_colors(mode))
+}
+
+/// Generate both the dark and light scheme from an image without matugen.
+#[pyfunction]
+#[pyo3(signature = (image_path, scheme_type=None, contrast=None, source_index=None))]
+pub fn extract_palette(
+    py: Python<'_>,
+    image_path: &str,
+    scheme_type: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+) -> PyResult<Palette> {
+    let options = options_from_args(scheme_type, contrast, source_index)?;
+    Ok(py.allow_threads(|| extract(Path::new(image_path), &options))?)
+}
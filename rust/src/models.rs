@@ -0,0 +1,314 @@
+//! Typed Hyprland data model.
+//!
+//! Serde mirrors of Hyprland's `j/` IPC responses, exposed to Python as
+//! read-only pyclasses so callers never touch raw JSON.
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+use crate::ipc::hyprctl_json;
+
+/// Whether a Hyprland transform (0-7) swaps width and height.
+pub(crate) fn is_rotated(transform: i32) -> bool {
+    matches!(transform, 1 | 3 | 5 | 7)
+}
+
+/// Hyprland reports `fullscreen` as a bool (< 0.42) or a mode int (>= 0.42).
+fn int_or_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum IntOrBool {
+        Int(i32),
+        Bool(bool),
+    }
+    Ok(match IntOrBool::deserialize(deserializer)? {
+        IntOrBool::Int(i) => i,
+        IntOrBool::Bool(b) => b as i32,
+    })
+}
+
+pub(crate) fn parse<T: for<'de> Deserialize<'de>>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid JSON from Hyprland: {}",
+            e
+        ))
+    })
+}
+
+/// Workspace reference embedded in monitors and clients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+#[serde(default)]
+pub struct WorkspaceRef {
+    pub id: i64,
+    pub name: String,
+}
+
+#[pymethods]
+impl WorkspaceRef {
+    fn __repr__(&self) -> String {
+        format!("WorkspaceRef(id={}, name={:?})", self.id, self.name)
+    }
+}
+
+/// A monitor as reported by `j/monitors`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Monitor {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub make: String,
+    pub model: String,
+    pub serial: String,
+    pub width: i32,
+    pub height: i32,
+    pub physical_width: i32,
+    pub physical_height: i32,
+    pub refresh_rate: f64,
+    pub x: i32,
+    pub y: i32,
+    pub active_workspace: WorkspaceRef,
+    pub special_workspace: WorkspaceRef,
+    pub reserved: Vec<i32>,
+    pub scale: f64,
+    pub transform: i32,
+    pub focused: bool,
+    pub dpms_status: bool,
+    pub vrr: bool,
+    pub disabled: bool,
+    pub current_format: String,
+    pub mirror_of: String,
+    pub available_modes: Vec<String>,
+}
+
+#[pymethods]
+impl Monitor {
+    /// Whether the transform swaps width and height (90°/270°).
+    #[getter]
+    fn is_rotated(&self) -> bool {
+        is_rotated(self.transform)
+    }
+
+    /// Pixel width after applying the transform.
+    #[getter]
+    pub fn transformed_width(&self) -> i32 {
+        if is_rotated(self.transform) {
+            self.height
+        } else {
+            self.width
+        }
+    }
+
+    /// Pixel height after applying the transform.
+    #[getter]
+    pub fn transformed_height(&self) -> i32 {
+        if is_rotated(self.transform) {
+            self.width
+        } else {
+            self.height
+        }
+    }
+
+    /// Layout width in logical pixels (transformed, divided by scale).
+    #[getter]
+    fn logical_width(&self) -> i32 {
+        logical(self.transformed_width(), self.scale)
+    }
+
+    /// Layout height in logical pixels (transformed, divided by scale).
+    #[getter]
+    fn logical_height(&self) -> i32 {
+        logical(self.transformed_height(), self.scale)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Monitor(id={}, name={:?}, {}x{}@{:.2}, transform={})",
+            self.id, self.name, self.width, self.height, self.refresh_rate, self.transform
+        )
+    }
+}
+
+fn logical(pixels: i32, scale: f64) -> i32 {
+    if scale > 0.0 {
+        (pixels as f64 / scale).round() as i32
+    } else {
+        pixels
+    }
+}
+
+/// A workspace as reported by `j/workspaces`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+#[serde(default)]
+pub struct Workspace {
+    pub id: i64,
+    pub name: String,
+    pub monitor: String,
+    #[serde(rename = "monitorID")]
+    pub monitor_id: i64,
+    pub windows: i32,
+    #[serde(rename = "hasfullscreen")]
+    pub has_fullscreen: bool,
+    #[serde(rename = "lastwindow")]
+    pub last_window: String,
+    #[serde(rename = "lastwindowtitle")]
+    pub last_window_title: String,
+    #[serde(rename = "ispersistent")]
+    pub is_persistent: bool,
+}
+
+#[pymethods]
+impl Workspace {
+    fn __repr__(&self) -> String {
+        format!(
+            "Workspace(id={}, name={:?}, monitor={:?}, windows={})",
+            self.id, self.name, self.monitor, self.windows
+        )
+    }
+}
+
+/// A window as reported by `j/clients`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Client {
+    pub address: String,
+    pub mapped: bool,
+    pub hidden: bool,
+    pub at: (i32, i32),
+    pub size: (i32, i32),
+    pub workspace: WorkspaceRef,
+    pub floating: bool,
+    pub pseudo: bool,
+    pub monitor: i64,
+    #[serde(rename = "class")]
+    pub window_class: String,
+    pub title: String,
+    pub initial_class: String,
+    pub initial_title: String,
+    pub pid: i64,
+    pub xwayland: bool,
+    pub pinned: bool,
+    #[serde(deserialize_with = "int_or_bool")]
+    pub fullscreen: i32,
+    pub grouped: Vec<String>,
+    pub tags: Vec<String>,
+    #[serde(rename = "focusHistoryID")]
+    pub focus_history_id: i32,
+}
+
+#[pymethods]
+impl Client {
+    fn __repr__(&self) -> String {
+        format!(
+            "Client(address={:?}, class={:?}, title={:?}, workspace={:?})",
+            self.address, self.window_class, self.title, self.workspace.name
+        )
+    }
+}
+
+/// A layer-shell surface as reported by `j/layers`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+#[serde(default)]
+pub struct Layer {
+    pub address: String,
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+    pub namespace: String,
+    pub pid: i64,
+    /// Monitor name (filled in from the enclosing `j/layers` key).
+    #[serde(skip_deserializing)]
+    pub monitor: String,
+    /// Layer level 0-3 (background, bottom, top, overlay).
+    #[serde(skip_deserializing)]
+    pub level: i32,
+}
+
+#[pymethods]
+impl Layer {
+    fn __repr__(&self) -> String {
+        format!(
+            "Layer(namespace={:?}, monitor={:?}, level={})",
+            self.namespace, self.monitor, self.level
+        )
+    }
+}
+
+/// `j/layers` shape: `{monitor: {"levels": {"0": [layer, ...], ...}}}`.
+#[derive(Deserialize)]
+struct LayerMonitor {
+    #[serde(default)]
+    levels: BTreeMap<String, Vec<Layer>>,
+}
+
+pub(crate) fn parse_active_window(json: &str) -> PyResult<Option<Client>> {
+    let client: Client = parse(json)?;
+    Ok((!client.address.is_empty()).then_some(client))
+}
+
+pub(crate) fn parse_layers(json: &str) -> PyResult<Vec<Layer>> {
+    let monitors: BTreeMap<String, LayerMonitor> = parse(json)?;
+    let mut layers = Vec::new();
+    for (monitor, entry) in monitors {
+        for (level, surfaces) in entry.levels {
+            let level = level.parse().unwrap_or_default();
+            for mut layer in surfaces {
+                layer.monitor = monitor.clone();
+                layer.level = level;
+                layers.push(layer);
+            }
+        }
+    }
+    Ok(layers)
+}
+
+/// Get all monitors. With `all=True`, disabled monitors are included.
+#[pyfunction]
+#[pyo3(signature = (all=false, instance=None))]
+pub fn get_monitors(py: Python<'_>, all: bool, instance: Option<&str>) -> PyResult<Vec<Monitor>> {
+    let command = if all { "monitors all" } else { "monitors" };
+    parse(&hyprctl_json(py, command, instance)?)
+}
+
+/// Get all workspaces.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn get_workspaces(py: Python<'_>, instance: Option<&str>) -> PyResult<Vec<Workspace>> {
+    parse(&hyprctl_json(py, "workspaces", instance)?)
+}
+
+/// Get the currently active workspace.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn get_active_workspace(py: Python<'_>, instance: Option<&str>) -> PyResult<Workspace> {
+    parse(&hyprctl_json(py, "activeworkspace", instance)?)
+}
+
+/// Get all windows.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn get_clients(py: Python<'_>, instance: Option<&str>) -> PyResult<Vec<Client>> {
+    parse(&hyprctl_json(py, "clients", instance)?)
+}
+
+/// Get the focused window, or None if nothing is focused.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn get_active_window(py: Python<'_>, instance: Option<&str>) -> PyResult<Option<Client>> {
+    parse_active_window(&hyprctl_json(py, "activewindow", instance)?)
+}
+
+/// Get all layer-shell surfaces across monitors.
+#[pyfunction]
+#[pyo3(signature = (instance=None))]
+pub fn get_layers(py: Python<'_>, instance: Option<&str>) -> PyResult<Vec<Layer>> {
+    parse_layers(&hyprctl_json(py, "layers", instance)?)
+}
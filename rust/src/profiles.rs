@@ -0,0 +1,325 @@
+//! Named monitor layout profiles.
+//!
+//! A profile is a snapshot of every monitor's mode, position, scale,
+//! transform and DPMS state, stored as JSON under
+//! `~/.config/matuwrap/layouts/<name>.json`. Monitors are identified by
+//! make/model/serial rather than connector name, so a profile still
+//! applies after DP-1/DP-2 get renumbered.
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::dispatch;
+use crate::ipc::{HyprlandClient, Timeouts};
+use crate::layout::{self, MonitorConfig};
+use crate::models::{self, Monitor};
+
+/// One monitor in a saved profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+pub struct ProfileMonitor {
+    pub make: String,
+    pub model: String,
+    pub serial: String,
+    /// Connector name at save time; informational only.
+    #[serde(default)]
+    pub name: String,
+    pub mode: String,
+    pub position: (i32, i32),
+    pub scale: f64,
+    pub transform: i32,
+    pub enabled: bool,
+    pub dpms: bool,
+}
+
+#[pymethods]
+impl ProfileMonitor {
+    fn __repr__(&self) -> String {
+        format!(
+            "ProfileMonitor({:?}, mode={:?}, position={:?}, enabled={})",
+            identity_label(&self.make, &self.model, &self.serial),
+            self.mode,
+            self.position,
+            self.enabled
+        )
+    }
+}
+
+/// A named monitor layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[pyclass(get_all, frozen)]
+pub struct LayoutProfile {
+    pub name: String,
+    pub monitors: Vec<ProfileMonitor>,
+}
+
+#[pymethods]
+impl LayoutProfile {
+    fn __repr__(&self) -> String {
+        format!(
+            "LayoutProfile({:?}, monitors={})",
+            self.name,
+            self.monitors.len()
+        )
+    }
+}
+
+/// Monitor identity as (make, model, serial).
+pub(crate) type Identity = (String, String, String);
+
+pub(crate) fn monitor_identity(monitor: &Monitor) -> Identity {
+    (
+        monitor.make.clone(),
+        monitor.model.clone(),
+        monitor.serial.clone(),
+    )
+}
+
+impl ProfileMonitor {
+    pub(crate) fn identity(&self) -> Identity {
+        (self.make.clone(), self.model.clone(), self.serial.clone())
+    }
+
+    fn from_monitor(monitor: &Monitor) -> Self {
+        let config = MonitorConfig::from_monitor(monitor);
+        Self {
+            make: monitor.make.clone(),
+            model: monitor.model.clone(),
+            serial: monitor.serial.clone(),
+            name: monitor.name.clone(),
+            mode: config.mode,
+            position: (monitor.x, monitor.y),
+            scale: monitor.scale,
+            transform: monitor.transform,
+            enabled: !monitor.disabled,
+            dpms: monitor.dpms_status,
+        }
+    }
+
+    fn to_config(&self, connector: &str) -> MonitorConfig {
+        MonitorConfig {
+            name: connector.to_string(),
+            mode: self.mode.clone(),
+            position: Some(self.position),
+            scale: self.scale,
+            transform: self.transform,
+            enabled: self.enabled,
+        }
+    }
+}
+
+pub(crate) fn identity_label(make: &str, model: &str, serial: &str) -> String {
+    [make, model, serial]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Directory holding saved profiles.
+pub(crate) fn profiles_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("matuwrap").join("layouts"))
+}
+
+fn profile_path(name: &str) -> PyResult<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\0']) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid profile name: {:?}",
+            name
+        )));
+    }
+    let dir = profiles_dir().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Could not determine config directory")
+    })?;
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Read a profile from disk.
+pub(crate) fn read_profile(name: &str) -> PyResult<LayoutProfile> {
+    let path = profile_path(name)?;
+    let data = fs::read_to_string(&path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(format!(
+                "No layout profile named {:?}",
+                name
+            ))
+        } else {
+            PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))
+        }
+    })?;
+    serde_json::from_str(&data).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid layout profile {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Names of all saved profiles, sorted.
+pub(crate) fn profile_names() -> Vec<String> {
+    let Some(entries) = profiles_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "json")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Map each profile monitor to a connected connector name.
+///
+/// Identical monitors without serials are matched in connector order, each
+/// connected monitor being used at most once.
+fn match_monitors(
+    profile: &LayoutProfile,
+    monitors: &[Monitor],
+) -> Result<Vec<(ProfileMonitor, String)>, Vec<String>> {
+    let mut used = vec![false; monitors.len()];
+    let mut matched = Vec::new();
+    let mut missing = Vec::new();
+    for wanted in &profile.monitors {
+        let identity = wanted.identity();
+        let found = monitors
+            .iter()
+            .enumerate()
+            .find(|(i, m)| !used[*i] && monitor_identity(m) == identity);
+        match found {
+            Some((i, m)) => {
+                used[i] = true;
+                matched.push((wanted.clone(), m.name.clone()));
+            }
+            None => missing.push(format!(
+                "{}: not connected",
+                identity_label(&wanted.make, &wanted.model, &wanted.serial)
+            )),
+        }
+    }
+    if missing.is_empty() {
+        Ok(matched)
+    } else {
+        Err(missing)
+    }
+}
+
+/// Apply a profile on `client`. Returns the equivalent `monitor=` lines.
+pub(crate) fn restore_profile(
+    py: Python<'_>,
+    client: &HyprlandClient,
+    profile: &LayoutProfile,
+    dry_run: bool,
+) -> PyResult<Vec<String>> {
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    let matched = match_monitors(profile, &monitors).map_err(|missing| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Profile {:?} does not match connected monitors: {}",
+            profile.name,
+            missing.join("; ")
+        ))
+    })?;
+
+    let configs: Vec<MonitorConfig> = matched
+        .iter()
+        .map(|(wanted, connector)| wanted.to_config(connector))
+        .collect();
+    let lines = layout::apply_configs(py, client, &configs, dry_run)?;
+    if dry_run {
+        return Ok(lines);
+    }
+
+    for (wanted, connector) in matched.iter().filter(|(m, _)| m.enabled) {
+        let state = if wanted.dpms { "on" } else { "off" };
+        dispatch::run_dispatch(py, client, "dpms", &[state.to_string(), connector.clone()])?;
+    }
+    Ok(lines)
+}
+
+/// Snapshot the monitors on `client` and write the profile to disk.
+pub(crate) fn save_profile(
+    py: Python<'_>,
+    client: &HyprlandClient,
+    name: &str,
+) -> PyResult<LayoutProfile> {
+    let path = profile_path(name)?;
+    let monitors: Vec<Monitor> = models::parse(&client.request_json(py, "monitors all")?)?;
+    let profile = LayoutProfile {
+        name: name.to_string(),
+        monitors: monitors.iter().map(ProfileMonitor::from_monitor).collect(),
+    };
+
+    let json = serde_json::to_string_pretty(&profile).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Failed to serialize profile: {}",
+            e
+        ))
+    })?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, json)?;
+    Ok(profile)
+}
+
+/// Snapshot the current monitor arrangement and save it as a named profile.
+/// An existing profile with the same name is replaced.
+#[pyfunction]
+#[pyo3(signature = (name, instance=None))]
+pub fn save_layout_profile(
+    py: Python<'_>,
+    name: &str,
+    instance: Option<&str>,
+) -> PyResult<LayoutProfile> {
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    save_profile(py, &client, name)
+}
+
+/// Load a saved profile without applying it.
+#[pyfunction]
+pub fn load_layout_profile(name: &str) -> PyResult<LayoutProfile> {
+    read_profile(name)
+}
+
+/// Names of all saved layout profiles.
+#[pyfunction]
+pub fn list_layout_profiles() -> Vec<String> {
+    profile_names()
+}
+
+/// Delete a saved profile. Returns False if it didn't exist.
+#[pyfunction]
+pub fn delete_layout_profile(name: &str) -> PyResult<bool> {
+    match fs::remove_file(profile_path(name)?) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Restore a saved profile: match its monitors by make/model/serial, apply
+/// the layout, then set DPMS. Returns the equivalent `monitor=` lines.
+#[pyfunction]
+#[pyo3(signature = (name, dry_run=false, instance=None))]
+pub fn restore_layout_profile(
+    py: Python<'_>,
+    name: &str,
+    dry_run: bool,
+    instance: Option<&str>,
+) -> PyResult<Vec<String>> {
+    let profile = read_profile(name)?;
+    let client = HyprlandClient::resolve(instance, Timeouts::default())?;
+    restore_profile(py, &client, &profile, dry_run)
+}
@@ -0,0 +1,434 @@
+//! Shell prompts colored from the palette.
+//!
+//! A small format language describes the prompt once for every shell:
+//!
+//! - `{user}`, `{host}`, `{cwd}` and `{git}` (the current branch) are
+//!   segments, left for the shell to expand each time the prompt is drawn.
+//!   `{git| (%s)}` puts the value into a template and drops the whole
+//!   segment when it's empty, e.g. outside a repository.
+//! - `[role]...[/]` colors text with a palette role (`[primary]`,
+//!   `[on_surface_variant]`, ...) or a literal `[#rrggbb]`, and `[bold]`
+//!   makes it bold. Tags nest; `[/]` closes the innermost one.
+//! - `{{`, `}}` and `[[` stand for literal `{`, `}` and `[`.
+//!
+//! The result is what goes into the shell's config: a `PS1` string for
+//! bash, a `PROMPT` string for zsh (which needs `setopt prompt_subst`), a
+//! `fish_prompt` function for fish, and a `$env.PROMPT_COMMAND` closure
+//! for nushell. Colors follow the terminal's depth (see `terminal`).
+
+use pyo3::prelude::*;
+
+use crate::color::Rgb;
+use crate::colors::{Colors, Palette};
+use crate::material;
+use crate::terminal::{self, ColorDepth};
+
+/// `user@host:cwd`, as the bash integration has always drawn it.
+pub(crate) const DEFAULT_FORMAT: &str = "[primary]{user}[/]@[primary]{host}[/]:[tertiary]{cwd}[/]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+}
+
+const SHELLS: &[(&str, Shell)] = &[
+    ("bash", Shell::Bash),
+    ("zsh", Shell::Zsh),
+    ("fish", Shell::Fish),
+    ("nu", Shell::Nu),
+];
+
+impl Shell {
+    pub(crate) fn from_name(name: &str) -> Result<Self, String> {
+        let name = if name == "nushell" { "nu" } else { name };
+        SHELLS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, s)| *s)
+            .ok_or_else(|| format!("Unknown shell {:?} (expected bash, zsh, fish or nu)", name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    User,
+    Host,
+    Cwd,
+    Git,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Color(Rgb),
+    Bold,
+    /// A role tag rendered without a palette: keeps tags balanced.
+    Plain,
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Segment {
+        segment: Segment,
+        prefix: String,
+        suffix: String,
+    },
+    Push(Style),
+    Pop,
+}
+
+fn parse_segment(body: &str) -> Result<Token, String> {
+    let (name, template) = body.split_once('|').unwrap_or((body, "%s"));
+    let segment = match name.trim() {
+        "user" => Segment::User,
+        "host" => Segment::Host,
+        "cwd" => Segment::Cwd,
+        "git" => Segment::Git,
+        other => {
+            return Err(format!(
+                "Unknown prompt segment {:?} (expected user, host, cwd or git)",
+                other
+            ));
+        }
+    };
+    let (prefix, suffix) = template
+        .split_once("%s")
+        .ok_or_else(|| format!("Segment template {:?} has no %s", template))?;
+    Ok(Token::Segment {
+        segment,
+        prefix: prefix.to_string(),
+        suffix: suffix.to_string(),
+    })
+}
+
+fn parse_style(body: &str, colors: Option<&Colors>) -> Result<Style, String> {
+    if body == "bold" {
+        return Ok(Style::Bold);
+    }
+    if body.starts_with('#') {
+        return Rgb::parse(body).map(Style::Color);
+    }
+    let Some(colors) = colors else {
+        return Ok(Style::Plain);
+    };
+    let hex = colors
+        .get(body)
+        .ok_or_else(|| format!("Unknown color role {:?}", body))?;
+    Rgb::parse(hex).map(Style::Color)
+}
+
+/// Split a format into tokens. Role tags are resolved against `colors`;
+/// without a palette they render as nothing.
+fn parse(format: &str, colors: Option<&Colors>) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut open = 0usize;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '{' | '[' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                text.push(c);
+                continue;
+            }
+            '{' => '}',
+            '[' => ']',
+            _ => {
+                text.push(c);
+                continue;
+            }
+        };
+        let mut body = String::new();
+        loop {
+            match chars.next() {
+                Some(ch) if ch == close => break,
+                Some(ch) => body.push(ch),
+                None => return Err(format!("Unclosed {:?} in prompt format", c)),
+            }
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        let token = match c {
+            '{' => parse_segment(&body)?,
+            _ if body.starts_with('/') => {
+                open = open
+                    .checked_sub(1)
+                    .ok_or_else(|| format!("[{}] closes nothing", body))?;
+                Token::Pop
+            }
+            _ => {
+                open += 1;
+                Token::Push(parse_style(&body, colors)?)
+            }
+        };
+        tokens.push(token);
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+/// fish's names for the 16 base colors.
+const FISH_COLORS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "brblack",
+    "brred",
+    "brgreen",
+    "bryellow",
+    "brblue",
+    "brmagenta",
+    "brcyan",
+    "brwhite",
+];
+
+/// Branch of the repository in the working directory, for bash and zsh.
+const SH_GIT_BRANCH: &str = "b=$(git symbolic-ref --short HEAD 2>/dev/null)";
+
+/// Single-quote for sh: `it's` -> `'it'\''s'`.
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Single-quote for fish, where `\` and `'` are escaped with `\`.
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Double-quote for nushell.
+fn nu_quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Pieces of the prompt in one shell's syntax.
+struct Output {
+    shell: Shell,
+    depth: ColorDepth,
+    items: Vec<String>,
+}
+
+impl Output {
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let item = match self.shell {
+            // bash decodes prompt escapes before expanding `$` and
+            // backticks, so both layers need escaping
+            Shell::Bash => text
+                .replace('\\', r"\\\\")
+                .replace('$', r"\\$")
+                .replace('`', r"\`"),
+            Shell::Zsh => text
+                .replace('%', "%%")
+                .replace('\\', r"\\")
+                .replace('$', r"\$")
+                .replace('`', r"\`"),
+            Shell::Fish => format!("printf '%s' {}", fish_quote(text)),
+            Shell::Nu => nu_quote(text),
+        };
+        self.items.push(item);
+    }
+
+    fn segment(&mut self, segment: Segment, prefix: &str, suffix: &str) {
+        let item = match (self.shell, segment) {
+            (Shell::Bash | Shell::Zsh, Segment::Git) => {
+                // Inside single quotes only bash's backslash decoding and
+                // zsh's percent escapes still apply
+                let affix = |text: &str| match self.shell {
+                    Shell::Bash => sh_quote(&text.replace('\\', r"\\")),
+                    _ => sh_quote(&text.replace('%', "%%")),
+                };
+                format!(
+                    "$({} && printf '%s%s%s' {} \"$b\" {})",
+                    SH_GIT_BRANCH,
+                    affix(prefix),
+                    affix(suffix)
+                )
+            }
+            (Shell::Fish, Segment::Git) => {
+                let template = format!(
+                    "{}%s{}",
+                    prefix.replace('%', "%%"),
+                    suffix.replace('%', "%%")
+                );
+                format!("fish_git_prompt {}", fish_quote(&template))
+            }
+            (Shell::Nu, Segment::Git) => format!(
+                "(do -i {{ let b = (^git symbolic-ref --short HEAD | complete); \
+                 if $b.exit_code == 0 {{ [{} ($b.stdout | str trim) {}] | str join }} \
+                 else {{ \"\" }} }} | default \"\")",
+                nu_quote(prefix),
+                nu_quote(suffix)
+            ),
+            // Never empty, so the affixes are plain text around the value
+            (shell, segment) => {
+                let value = match (shell, segment) {
+                    (Shell::Bash, Segment::User) => r"\u",
+                    (Shell::Bash, Segment::Host) => r"\h",
+                    (Shell::Bash, _) => r"\w",
+                    (Shell::Zsh, Segment::User) => "%n",
+                    (Shell::Zsh, Segment::Host) => "%m",
+                    (Shell::Zsh, _) => "%~",
+                    (Shell::Fish, Segment::User) => "printf '%s' $USER",
+                    (Shell::Fish, Segment::Host) => "printf '%s' (prompt_hostname)",
+                    (Shell::Fish, _) => "printf '%s' (prompt_pwd)",
+                    (_, Segment::User) => r#"($env.USER? | default "")"#,
+                    (_, Segment::Host) => r#"(sys host | get hostname | split row "." | first)"#,
+                    _ => r#"($env.PWD | str replace $nu.home-path "~")"#,
+                };
+                self.text(prefix);
+                self.items.push(value.to_string());
+                self.text(suffix);
+                return;
+            }
+        };
+        self.items.push(item);
+    }
+
+    /// Escape sequence for bash and nushell.
+    fn sgr(&self, codes: &str) -> String {
+        match self.shell {
+            Shell::Bash => format!(r"\[\033[{}m\]", codes),
+            _ => format!(r#""\e[{}m""#, codes),
+        }
+    }
+
+    fn style(&mut self, style: Style) {
+        let item = match (self.shell, style) {
+            (_, Style::Plain) => return,
+            (Shell::Bash | Shell::Nu, Style::Bold) => self.sgr("1"),
+            (Shell::Bash | Shell::Nu, Style::Color(rgb)) => {
+                match terminal::sgr_foreground(rgb, self.depth) {
+                    Some(codes) => self.sgr(&codes),
+                    None => return,
+                }
+            }
+            (Shell::Zsh, Style::Bold) => "%B".to_string(),
+            (Shell::Zsh, Style::Color(rgb)) => match self.depth {
+                ColorDepth::TrueColor => format!("%F{{{}}}", rgb.to_hex()),
+                ColorDepth::Ansi256 => format!("%F{{{}}}", terminal::ansi256(rgb)),
+                ColorDepth::Ansi16 => format!("%F{{{}}}", terminal::ansi16(rgb)),
+                ColorDepth::NoColor => return,
+            },
+            (Shell::Fish, Style::Bold) => "set_color --bold".to_string(),
+            // fish maps hex to what the terminal supports by itself
+            (Shell::Fish, Style::Color(rgb)) => match self.depth {
+                ColorDepth::Ansi16 => {
+                    format!("set_color {}", FISH_COLORS[terminal::ansi16(rgb) as usize])
+                }
+                ColorDepth::NoColor => return,
+                _ => format!("set_color {}", &rgb.to_hex()[1..]),
+            },
+        };
+        self.items.push(item);
+    }
+
+    fn reset(&mut self) {
+        let item = match self.shell {
+            Shell::Bash | Shell::Nu => self.sgr("0"),
+            Shell::Zsh => "%f%b".to_string(),
+            Shell::Fish => "set_color normal".to_string(),
+        };
+        self.items.push(item);
+    }
+
+    fn finish(self) -> String {
+        match self.shell {
+            Shell::Bash | Shell::Zsh => self.items.concat(),
+            Shell::Fish => {
+                let body: Vec<String> = self.items.iter().map(|i| format!("    {}\n", i)).collect();
+                format!("function fish_prompt\n{}end", body.concat())
+            }
+            Shell::Nu => {
+                let body: Vec<String> = self.items.iter().map(|i| format!("    {}\n", i)).collect();
+                format!(
+                    "$env.PROMPT_COMMAND = {{||\n  [\n{}  ] | str join\n}}",
+                    body.concat()
+                )
+            }
+        }
+    }
+}
+
+/// Render `format` for `shell`, coloring roles from `colors` (uncolored
+/// when None) at `depth`.
+pub(crate) fn render(
+    format: &str,
+    shell: Shell,
+    colors: Option<&Colors>,
+    depth: ColorDepth,
+) -> Result<String, String> {
+    let mut output = Output {
+        shell,
+        depth,
+        items: Vec::new(),
+    };
+    let mut styles: Vec<Style> = Vec::new();
+    for token in parse(format, colors)? {
+        match token {
+            Token::Text(text) => output.text(&text),
+            Token::Segment {
+                segment,
+                prefix,
+                suffix,
+            } => output.segment(segment, &prefix, &suffix),
+            Token::Push(style) => {
+                // Without color only bold is left to undo
+                let style = match style {
+                    Style::Color(_) if depth == ColorDepth::NoColor => Style::Plain,
+                    style => style,
+                };
+                output.style(style);
+                styles.push(style);
+            }
+            // Shells can't undo one attribute, so reset and reapply
+            Token::Pop => {
+                if let Some(Style::Plain) = styles.pop() {
+                    continue;
+                }
+                output.reset();
+                for style in &styles {
+                    output.style(*style);
+                }
+            }
+        }
+    }
+    if styles.iter().any(|s| !matches!(s, Style::Plain)) {
+        output.reset();
+    }
+    Ok(output.finish())
+}
+
+/// Render a prompt for `shell` from a palette's scheme for `mode`.
+#[pyfunction]
+#[pyo3(signature = (palette, shell="bash", format=None, mode=None, depth=None))]
+pub fn render_prompt(
+    palette: Option<PyRef<'_, Palette>>,
+    shell: &str,
+    format: Option<&str>,
+    mode: Option<&str>,
+    depth: Option<&str>,
+) -> PyResult<String> {
+    let to_py_err = PyErr::new::<pyo3::exceptions::PyValueError, _>;
+    let shell = Shell::from_name(shell).map_err(to_py_err)?;
+    let depth = ColorDepth::from_arg(depth).map_err(to_py_err)?;
+    let mode = material::mode_from_arg(mode)?;
+    let colors = palette.as_ref().map(|p| p.colors(mode));
+    render(format.unwrap_or(DEFAULT_FORMAT), shell, colors, depth).map_err(to_py_err)
+}
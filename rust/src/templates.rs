@@ -0,0 +1,450 @@
+//! Theme file templates.
+//!
+//! Renders user templates from `~/.config/matuwrap/templates/` with the
+//! cached palette, so one palette themes kitty, waybar, Hyprland borders,
+//! etc. without a separate matugen template run. `templates.json` in that
+//! directory maps each template to its output; relative paths are resolved
+//! against the templates directory:
+//!
+//! ```json
+//! {"kitty": {"input": "kitty.conf", "output": "~/.config/kitty/colors.conf"}}
+//! ```
+//!
+//! Placeholders follow matugen: `{{colors.primary.default.hex}}`, where the
+//! variant is `default` (the current mode), `dark` or `light`, and the
+//! format (optional, `hex` if omitted) is one of `FORMATS`. `{{mode}}` and
+//! `{{image}}` give the mode and wallpaper path. Filters chain after `|`:
+//! `{{colors.surface.default.hex | darken: 10 | alpha: 0.8 | rgba}}`.
+//! Outputs are written atomically and only when their contents change,
+//! and a changed output runs the template's hooks (see `hooks`).
+
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::color::Rgb;
+use crate::colors::{self, Palette};
+use crate::hooks::{self, Hook, HookResult};
+use crate::material::{self, Mode};
+
+const MANIFEST: &str = "templates.json";
+
+/// Output formats, usable as the last path segment or as a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Hex,
+    HexStripped,
+    Rgb,
+    Rgba,
+    Hsl,
+    Hsla,
+}
+
+const FORMATS: &[(&str, Format)] = &[
+    ("hex", Format::Hex),
+    ("hex_stripped", Format::HexStripped),
+    ("rgb", Format::Rgb),
+    ("rgba", Format::Rgba),
+    ("hsl", Format::Hsl),
+    ("hsla", Format::Hsla),
+];
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        FORMATS.iter().find(|(n, _)| *n == name).map(|(_, f)| *f)
+    }
+}
+
+/// An sRGB color with alpha from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy)]
+struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: f64,
+}
+
+impl Color {
+    /// Parse "#rrggbb" or "#rrggbbaa" (the `#` is optional).
+    fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+            a: if hex.len() == 8 {
+                f64::from(channel(6)?) / 255.0
+            } else {
+                1.0
+            },
+        })
+    }
+
+    fn rgb(self) -> Rgb {
+        Rgb {
+            r: self.r,
+            g: self.g,
+            b: self.b,
+        }
+    }
+
+    /// Shift HSL lightness by `amount` percentage points (negative darkens).
+    fn lighten(self, amount: f64) -> Self {
+        let (h, s, l) = self.rgb().to_hsl();
+        let Rgb { r, g, b } = Rgb::from_hsl(h, s, l + amount / 100.0);
+        Self { r, g, b, a: self.a }
+    }
+
+    fn format(self, format: Format) -> String {
+        let hex = if self.a < 1.0 {
+            format!(
+                "{:02x}{:02x}{:02x}{:02x}",
+                self.r,
+                self.g,
+                self.b,
+                (self.a * 255.0).round() as u8
+            )
+        } else {
+            format!("{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        };
+        let (h, s, l) = self.rgb().to_hsl();
+        let (h, s, l) = (h.round(), (s * 100.0).round(), (l * 100.0).round());
+        match format {
+            Format::Hex => format!("#{}", hex),
+            Format::HexStripped => hex,
+            Format::Rgb => format!("rgb({}, {}, {})", self.r, self.g, self.b),
+            Format::Rgba => format!(
+                "rgba({}, {}, {}, {})",
+                self.r,
+                self.g,
+                self.b,
+                format_alpha(self.a)
+            ),
+            Format::Hsl => format!("hsl({}, {}%, {}%)", h, s, l),
+            Format::Hsla => format!("hsla({}, {}%, {}%, {})", h, s, l, format_alpha(self.a)),
+        }
+    }
+}
+
+/// Alpha with at most two decimals and no trailing zeros ("1", "0.5").
+fn format_alpha(a: f64) -> String {
+    let s = format!("{:.2}", a);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// What a placeholder evaluates to before formatting.
+enum Value {
+    Text(String),
+    Color(Color, Format),
+}
+
+/// Everything a template can refer to.
+pub(crate) struct Context<'a> {
+    pub palette: &'a Palette,
+    pub mode: Mode,
+    pub image: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TemplateError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn lookup(path: &str, ctx: &Context) -> Result<Value, String> {
+    let segments: Vec<&str> = path.split('.').map(str::trim).collect();
+    match segments.as_slice() {
+        ["mode"] => Ok(Value::Text(ctx.mode.name().to_string())),
+        ["image"] => ctx
+            .image
+            .map(|image| Value::Text(image.to_string()))
+            .ok_or_else(|| "No image to fill in {{image}}".to_string()),
+        ["colors", role, variant, rest @ ..] if rest.len() <= 1 => {
+            let mode = match *variant {
+                "default" => ctx.mode,
+                other => Mode::from_name(other).ok_or_else(|| {
+                    format!(
+                        "Unknown variant {:?} (expected default, dark or light)",
+                        other
+                    )
+                })?,
+            };
+            let format = match rest.first() {
+                Some(name) => {
+                    Format::from_name(name).ok_or_else(|| format!("Unknown format {:?}", name))?
+                }
+                None => Format::Hex,
+            };
+            let hex = ctx
+                .palette
+                .colors(mode)
+                .get(*role)
+                .ok_or_else(|| format!("Unknown color role {:?}", role))?;
+            let color = Color::from_hex(hex)
+                .ok_or_else(|| format!("Invalid color {:?} for role {:?}", hex, role))?;
+            Ok(Value::Color(color, format))
+        }
+        _ => Err(format!("Unknown placeholder {:?}", path)),
+    }
+}
+
+fn apply_filter(value: Value, filter: &str) -> Result<Value, String> {
+    let (name, arg) = match filter.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (filter.trim(), None),
+    };
+    let Value::Color(color, format) = value else {
+        return Err(format!("Filter {:?} needs a color", name));
+    };
+    let number = || {
+        arg.and_then(|a| a.parse::<f64>().ok())
+            .filter(|n| n.is_finite())
+            .ok_or_else(|| format!("Filter {:?} needs a numeric argument", name))
+    };
+    match name {
+        "lighten" => Ok(Value::Color(color.lighten(number()?), format)),
+        "darken" => Ok(Value::Color(color.lighten(-number()?), format)),
+        "alpha" => {
+            let a = number()?;
+            if !(0.0..=1.0).contains(&a) {
+                return Err(format!("Alpha must be between 0 and 1, got {}", a));
+            }
+            Ok(Value::Color(Color { a, ..color }, format))
+        }
+        _ => match Format::from_name(name) {
+            Some(format) if arg.is_none() => Ok(Value::Color(color, format)),
+            Some(_) => Err(format!("Filter {:?} takes no argument", name)),
+            None => Err(format!("Unknown filter {:?}", name)),
+        },
+    }
+}
+
+fn eval(expr: &str, ctx: &Context) -> Result<String, String> {
+    let mut parts = expr.split('|');
+    let mut value = lookup(parts.next().unwrap_or("").trim(), ctx)?;
+    for filter in parts {
+        value = apply_filter(value, filter)?;
+    }
+    Ok(match value {
+        Value::Text(text) => text,
+        Value::Color(color, format) => color.format(format),
+    })
+}
+
+/// Fill in every `{{ ... }}` placeholder of `source`.
+pub(crate) fn render(source: &str, ctx: &Context) -> Result<String, TemplateError> {
+    let line_at = |offset: usize| source[..offset].matches('\n').count() + 1;
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    while let Some(start) = source[pos..].find("{{").map(|i| pos + i) {
+        out.push_str(&source[pos..start]);
+        let Some(end) = source[start + 2..].find("}}").map(|i| start + 2 + i) else {
+            return Err(TemplateError {
+                line: line_at(start),
+                message: "Unclosed {{".to_string(),
+            });
+        };
+        let value = eval(&source[start + 2..end], ctx).map_err(|message| TemplateError {
+            line: line_at(start),
+            message,
+        })?;
+        out.push_str(&value);
+        pos = end + 2;
+    }
+    out.push_str(&source[pos..]);
+    Ok(out)
+}
+
+/// One entry of `templates.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TemplateConfig {
+    pub input: String,
+    pub output: String,
+    /// Run after the output changes.
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+}
+
+pub(crate) fn templates_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("matuwrap").join("templates"))
+}
+
+/// Expand `~/` and resolve relative paths against `base`.
+fn resolve_path(path: &str, base: &Path) -> PathBuf {
+    match path.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((rest, home)) => home.join(rest),
+        None => base.join(path),
+    }
+}
+
+/// Templates by name; empty if there is no manifest.
+pub(crate) fn read_manifest() -> PyResult<BTreeMap<String, TemplateConfig>> {
+    let dir = templates_dir().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Could not determine config directory")
+    })?;
+    let path = dir.join(MANIFEST);
+    let data = match fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    serde_json::from_str(&data).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid template manifest {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Outcome of rendering one template.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct TemplateResult {
+    /// Name in templates.json.
+    pub name: String,
+    /// Resolved output path.
+    pub output: String,
+    /// The output's contents changed (or would, in dry-run mode).
+    pub changed: bool,
+    /// Why reading, rendering or writing failed, if it did.
+    pub error: Option<String>,
+    /// Hooks that ran because the output changed (none in dry-run mode).
+    pub hooks: Vec<HookResult>,
+}
+
+#[pymethods]
+impl TemplateResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "TemplateResult({:?}, output={:?}, changed={}, error={:?})",
+            self.name, self.output, self.changed, self.error
+        )
+    }
+}
+
+/// Render one template to its output. Symlinked outputs are written
+/// through, so outputs kept in a dotfiles repo stay links.
+fn render_to_output(
+    config: &TemplateConfig,
+    dir: &Path,
+    output: &Path,
+    ctx: &Context,
+    dry_run: bool,
+) -> Result<bool, String> {
+    let input = resolve_path(&config.input, dir);
+    let source = fs::read_to_string(&input)
+        .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let rendered = render(&source, ctx).map_err(|e| format!("{}: {}", input.display(), e))?;
+
+    let target = fs::canonicalize(output)
+        .or_else(|_| {
+            // Dangling link: create its target
+            fs::read_link(output).map(|link| output.parent().unwrap_or(Path::new("/")).join(link))
+        })
+        .unwrap_or_else(|_| output.to_path_buf());
+    if fs::read(&target).is_ok_and(|old| old == rendered.as_bytes()) {
+        return Ok(false);
+    }
+    if !dry_run {
+        colors::write_atomic(&target, rendered.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+    Ok(true)
+}
+
+pub(crate) fn render_all(ctx: &Context, dry_run: bool) -> PyResult<Vec<TemplateResult>> {
+    let manifest = read_manifest()?;
+    let dir = templates_dir().unwrap_or_default();
+    Ok(manifest
+        .iter()
+        .map(|(name, config)| {
+            let output = resolve_path(&config.output, &dir);
+            let (changed, error) = match render_to_output(config, &dir, &output, ctx, dry_run) {
+                Ok(changed) => (changed, None),
+                Err(e) => (false, Some(e)),
+            };
+            let hooks = if changed && !dry_run {
+                hooks::run_hooks(&config.hooks, ctx)
+            } else {
+                Vec::new()
+            };
+            TemplateResult {
+                name: name.clone(),
+                output: output.to_string_lossy().into_owned(),
+                changed,
+                error,
+                hooks,
+            }
+        })
+        .collect())
+}
+
+/// Render a template string with a palette.
+#[pyfunction]
+#[pyo3(signature = (template, palette, mode=None, image=None))]
+pub fn render_template(
+    template: &str,
+    palette: PyRef<'_, Palette>,
+    mode: Option<&str>,
+    image: Option<&str>,
+) -> PyResult<String> {
+    let ctx = Context {
+        palette: &palette,
+        mode: material::mode_from_arg(mode)?,
+        image,
+    };
+    render(template, &ctx)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Render every template in templates.json with the wallpaper's cached
+/// palette. Errors in one template are reported in its result.
+#[pyfunction]
+#[pyo3(signature = (wallpaper_path, scheme_type=None, mode=None, contrast=None, source_index=None, dry_run=false))]
+pub fn render_templates(
+    py: Python<'_>,
+    wallpaper_path: &str,
+    scheme_type: Option<&str>,
+    mode: Option<&str>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    dry_run: bool,
+) -> PyResult<Vec<TemplateResult>> {
+    let mode = material::mode_from_arg(mode)?;
+    let options = material::options_from_args(scheme_type, contrast, source_index)?;
+    let cached = py.allow_threads(|| colors::cached_palette(wallpaper_path, &options, false));
+    colors::warn_corrupt(py, &cached)?;
+    let palette = cached.palette.ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Could not generate colors for {}",
+            wallpaper_path
+        ))
+    })?;
+    let ctx = Context {
+        palette: &palette,
+        mode,
+        image: Some(wallpaper_path),
+    };
+    py.allow_threads(|| render_all(&ctx, dry_run))
+}
@@ -0,0 +1,274 @@
+//! Terminal color support and SGR color sequences.
+//!
+//! Terminals that advertise 24-bit color (`COLORTERM=truecolor`) get the
+//! palette as is. Others get the perceptually nearest entry (by OKLab
+//! distance) of the xterm 256-color table (`TERM=*-256color`, e.g. tmux
+//! without truecolor) or of the 16 base colors (the Linux console).
+//! `NO_COLOR` and dumb terminals get no color at all.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::color::Rgb;
+use crate::colors::Palette;
+use crate::material;
+
+/// How many colors a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    NoColor,
+}
+
+const DEPTHS: &[(&str, ColorDepth)] = &[
+    ("truecolor", ColorDepth::TrueColor),
+    ("256", ColorDepth::Ansi256),
+    ("16", ColorDepth::Ansi16),
+    ("none", ColorDepth::NoColor),
+];
+
+/// Terminals known to do 24-bit color without setting COLORTERM.
+const TRUECOLOR_TERMS: &[&str] = &[
+    "xterm-kitty",
+    "alacritty",
+    "foot",
+    "foot-extra",
+    "wezterm",
+    "xterm-ghostty",
+    "contour",
+];
+
+impl ColorDepth {
+    pub(crate) fn all() -> impl Iterator<Item = Self> {
+        DEPTHS.iter().map(|(_, d)| *d)
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        DEPTHS.iter().find(|(n, _)| *n == name).map(|(_, d)| *d)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        DEPTHS
+            .iter()
+            .find(|(_, d)| *d == self)
+            .map_or("16", |(n, _)| n)
+    }
+
+    /// Depth implied by `NO_COLOR`, `COLORTERM` and `TERM`.
+    pub(crate) fn detect_from(
+        no_color: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+    ) -> Self {
+        // https://no-color.org: set and not empty
+        if no_color.is_some_and(|v| !v.is_empty()) || term == Some("dumb") {
+            return ColorDepth::NoColor;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            Some(t) if TRUECOLOR_TERMS.contains(&t) || t.ends_with("-direct") => {
+                ColorDepth::TrueColor
+            }
+            Some(t) if t.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    /// Depth of the terminal this process runs in.
+    pub(crate) fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        let (no_color, colorterm, term) = (var("NO_COLOR"), var("COLORTERM"), var("TERM"));
+        Self::detect_from(no_color.as_deref(), colorterm.as_deref(), term.as_deref())
+    }
+
+    /// Parse a depth name, detecting it from the environment for None.
+    pub(crate) fn from_arg(depth: Option<&str>) -> Result<Self, String> {
+        match depth {
+            Some(name) => Self::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown color depth {:?} (expected \"truecolor\", \"256\", \"16\" or \"none\")",
+                    name
+                )
+            }),
+            None => Ok(Self::detect()),
+        }
+    }
+}
+
+/// xterm's default base colors.
+const XTERM_16: [[u8; 3]; 16] = [
+    [0x00, 0x00, 0x00],
+    [0xcd, 0x00, 0x00],
+    [0x00, 0xcd, 0x00],
+    [0xcd, 0xcd, 0x00],
+    [0x00, 0x00, 0xee],
+    [0xcd, 0x00, 0xcd],
+    [0x00, 0xcd, 0xcd],
+    [0xe5, 0xe5, 0xe5],
+    [0x7f, 0x7f, 0x7f],
+    [0xff, 0x00, 0x00],
+    [0x00, 0xff, 0x00],
+    [0xff, 0xff, 0x00],
+    [0x5c, 0x5c, 0xff],
+    [0xff, 0x00, 0xff],
+    [0x00, 0xff, 0xff],
+    [0xff, 0xff, 0xff],
+];
+
+/// Channel levels of the 6x6x6 cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The color xterm shows for a 256-color index.
+pub(crate) fn xterm_color(index: u8) -> Rgb {
+    match index {
+        0..16 => {
+            let [r, g, b] = XTERM_16[index as usize];
+            Rgb { r, g, b }
+        }
+        16..232 => {
+            let i = index - 16;
+            Rgb {
+                r: CUBE_LEVELS[(i / 36) as usize],
+                g: CUBE_LEVELS[(i / 6 % 6) as usize],
+                b: CUBE_LEVELS[(i % 6) as usize],
+            }
+        }
+        // Grays 232-255 run from 8 to 238 in steps of 10
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            Rgb {
+                r: level,
+                g: level,
+                b: level,
+            }
+        }
+    }
+}
+
+/// Index in `indices` whose color is nearest to `rgb` in OKLab.
+fn nearest(rgb: Rgb, indices: impl Iterator<Item = u8>) -> u8 {
+    let [l, a, b] = rgb.to_oklab();
+    indices
+        .map(|i| {
+            let [l2, a2, b2] = xterm_color(i).to_oklab();
+            (i, (l - l2).powi(2) + (a - a2).powi(2) + (b - b2).powi(2))
+        })
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Nearest xterm-256 index. Only the cube and grays (16-255) are
+/// candidates: the base colors are whatever the terminal theme says.
+pub(crate) fn ansi256(rgb: Rgb) -> u8 {
+    nearest(rgb, 16..=255)
+}
+
+/// Nearest of the 16 base colors (as xterm shows them).
+pub(crate) fn ansi16(rgb: Rgb) -> u8 {
+    nearest(rgb, 0..16)
+}
+
+/// SGR parameters setting the foreground to `rgb` at `depth`, e.g.
+/// "38;2;255;178;183", "38;5;217" or "91"; None without color.
+pub(crate) fn sgr_foreground(rgb: Rgb, depth: ColorDepth) -> Option<String> {
+    let codes = match depth {
+        ColorDepth::TrueColor => format!("38;2;{};{};{}", rgb.r, rgb.g, rgb.b),
+        ColorDepth::Ansi256 => format!("38;5;{}", ansi256(rgb)),
+        ColorDepth::Ansi16 => match ansi16(rgb) {
+            i @ 0..8 => format!("{}", 30 + i),
+            i => format!("{}", 90 + i - 8),
+        },
+        ColorDepth::NoColor => return None,
+    };
+    Some(codes)
+}
+
+/// Escape sequence setting the foreground, empty without color.
+fn escape(rgb: Rgb, depth: ColorDepth) -> String {
+    sgr_foreground(rgb, depth)
+        .map(|codes| format!("\x1b[{}m", codes))
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Python API
+// ============================================================================
+
+fn to_py_err(e: String) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(e)
+}
+
+/// A color with its nearest xterm-256 and base-16 indices.
+#[pyclass(get_all, frozen)]
+pub struct TerminalColor {
+    pub hex: String,
+    pub ansi256: u8,
+    pub ansi16: u8,
+}
+
+impl TerminalColor {
+    fn new(rgb: Rgb) -> Self {
+        Self {
+            hex: rgb.to_hex(),
+            ansi256: ansi256(rgb),
+            ansi16: ansi16(rgb),
+        }
+    }
+}
+
+#[pymethods]
+impl TerminalColor {
+    /// Foreground escape sequence at `depth` (detected when None).
+    #[pyo3(signature = (depth=None))]
+    fn escape(&self, depth: Option<&str>) -> PyResult<String> {
+        let depth = ColorDepth::from_arg(depth).map_err(to_py_err)?;
+        let rgb = Rgb::parse(&self.hex).map_err(to_py_err)?;
+        Ok(escape(rgb, depth))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TerminalColor(hex={:?}, ansi256={}, ansi16={})",
+            self.hex, self.ansi256, self.ansi16
+        )
+    }
+}
+
+/// Color depth of the current terminal: "truecolor", "256", "16" or "none".
+#[pyfunction]
+pub fn detect_color_depth() -> &'static str {
+    ColorDepth::detect().name()
+}
+
+/// Foreground escape sequence for a hex color at `depth` (detected when
+/// None); empty when the terminal should get no color.
+#[pyfunction]
+#[pyo3(signature = (color, depth=None))]
+pub fn ansi_escape(color: &str, depth: Option<&str>) -> PyResult<String> {
+    let depth = ColorDepth::from_arg(depth).map_err(to_py_err)?;
+    Ok(escape(Rgb::parse(color).map_err(to_py_err)?, depth))
+}
+
+/// Every role of a palette's scheme for `mode`, with the nearest terminal
+/// colors.
+#[pyfunction]
+#[pyo3(signature = (palette, mode=None))]
+pub fn quantize_palette(
+    palette: PyRef<'_, Palette>,
+    mode: Option<&str>,
+) -> PyResult<HashMap<String, TerminalColor>> {
+    let mode = material::mode_from_arg(mode)?;
+    palette
+        .colors(mode)
+        .iter()
+        .map(|(role, hex)| {
+            let rgb = Rgb::parse(hex).map_err(to_py_err)?;
+            Ok((role.clone(), TerminalColor::new(rgb)))
+        })
+        .collect()
+}
@@ -0,0 +1,531 @@
+//! Regenerating colors when the wallpaper changes.
+//!
+//! `WallpaperWatcher` watches `~/.current.wall` with inotify. Both the
+//! directory holding the symlink and the directory holding its target are
+//! watched, so re-pointing the link and rewriting the image in place are
+//! both seen. After the burst of events a wallpaper switch produces has
+//! settled, the palette is regenerated into the cache, templates are
+//! rendered, and the shell cache files read by the bash integration
+//! (`~/.cache/matuwrap/ps1`, one `ps1-*` per lower color depth, and
+//! `color`) are rewritten.
+//!
+//! A change is only reported when the target path or its stamp (see
+//! `colors::FileStamp`) differs from the last one, so touching the link or
+//! saving an identical file does nothing.
+
+use pyo3::prelude::*;
+use std::ffi::{CString, OsStr};
+use std::fs;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant};
+
+use crate::colors::{self, FileStamp, Palette};
+use crate::material;
+use crate::prompt::{self, Shell};
+use crate::templates::{self, Context, TemplateResult};
+use crate::terminal::ColorDepth;
+
+/// How often a blocked watcher checks for Ctrl-C and `close`.
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Directory events that can mean the link or the image changed.
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_CLOSE_WRITE
+    | libc::IN_ATTRIB;
+
+struct Event {
+    wd: i32,
+    mask: u32,
+    name: Vec<u8>,
+}
+
+/// Minimal inotify wrapper over libc.
+struct Inotify {
+    fd: OwnedFd,
+}
+
+impl Inotify {
+    fn new() -> io::Result<Self> {
+        // SAFETY: inotify_init1 takes only flags; the result is checked.
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: fd is a fresh descriptor we own.
+        Ok(Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+        })
+    }
+
+    /// Watch a directory. Watching the same directory twice returns the
+    /// same descriptor.
+    fn add_watch(&self, dir: &Path) -> io::Result<i32> {
+        let path = CString::new(dir.as_os_str().as_bytes())?;
+        // SAFETY: path is NUL-terminated and outlives the call.
+        let wd = unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(wd)
+    }
+
+    fn rm_watch(&self, wd: i32) {
+        // SAFETY: removing a stale descriptor just fails with EINVAL.
+        unsafe { libc::inotify_rm_watch(self.fd.as_raw_fd(), wd) };
+    }
+
+    /// Events available within `timeout` (empty if none arrived).
+    fn read(&self, timeout: Duration) -> io::Result<Vec<Event>> {
+        let mut pfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: pfd is a single valid pollfd.
+        let ready = unsafe { libc::poll(&mut pfd, 1, timeout.as_millis() as libc::c_int) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            return if err.kind() == io::ErrorKind::Interrupted {
+                Ok(Vec::new())
+            } else {
+                Err(err)
+            };
+        }
+        if ready == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buf = [0u8; 4096];
+        // SAFETY: buf is writable for its whole length.
+        let n = unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => Ok(Vec::new()),
+                _ => Err(err),
+            };
+        }
+
+        let header = mem::size_of::<libc::inotify_event>();
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while offset + header <= n as usize {
+            // SAFETY: the kernel wrote a whole inotify_event at `offset`;
+            // the buffer may not be aligned for it, hence read_unaligned.
+            let raw: libc::inotify_event =
+                unsafe { std::ptr::read_unaligned(buf[offset..].as_ptr().cast()) };
+            let start = offset + header;
+            let end = (start + raw.len as usize).min(n as usize);
+            let name = buf[start..end].split(|&b| b == 0).next().unwrap_or(&[]);
+            events.push(Event {
+                wd: raw.wd,
+                mask: raw.mask,
+                name: name.to_vec(),
+            });
+            offset = end;
+        }
+        Ok(events)
+    }
+}
+
+/// What the watcher did for one wallpaper change.
+#[derive(Debug, Clone)]
+#[pyclass(get_all, frozen)]
+pub struct WallpaperChange {
+    /// The image the wallpaper link now points to.
+    pub wallpaper: String,
+    /// The regenerated palette, None if generation failed.
+    pub palette: Option<Palette>,
+    /// Templates rendered with the new palette (empty if disabled).
+    pub templates: Vec<TemplateResult>,
+    /// Shell cache files that were rewritten.
+    pub shell_cache: Vec<String>,
+    /// Why generating colors, rendering or writing failed, if it did.
+    pub error: Option<String>,
+}
+
+#[pymethods]
+impl WallpaperChange {
+    fn __repr__(&self) -> String {
+        format!(
+            "WallpaperChange({:?}, templates={}, error={:?})",
+            self.wallpaper,
+            self.templates.len(),
+            self.error
+        )
+    }
+}
+
+/// Shell cache file of the bash prompt at `depth`: "ps1" for truecolor,
+/// else e.g. "ps1-256".
+fn ps1_cache_name(depth: ColorDepth) -> String {
+    match depth {
+        ColorDepth::TrueColor => "ps1".to_string(),
+        depth => format!("ps1-{}", depth.name()),
+    }
+}
+
+/// Rewrite the files the bash integration loads at startup.
+fn write_shell_cache(colors: &colors::Colors) -> Result<Vec<String>, String> {
+    let dir = dirs::cache_dir()
+        .map(|p| p.join("matuwrap"))
+        .ok_or_else(|| "Could not determine cache directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    // One prompt per color depth; each terminal loads the one it supports
+    let mut files = Vec::new();
+    for depth in ColorDepth::all() {
+        let ps1 = prompt::render(prompt::DEFAULT_FORMAT, Shell::Bash, Some(colors), depth)?;
+        files.push((dir.join(ps1_cache_name(depth)), ps1));
+    }
+    if let Some(primary) = colors.get("primary") {
+        files.push((dir.join("color"), primary.clone()));
+    }
+    files
+        .into_iter()
+        .map(|(path, contents)| {
+            colors::write_atomic(&path, format!("{}\n", contents).as_bytes())
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Ok(path.display().to_string())
+        })
+        .collect()
+}
+
+/// Watches the wallpaper link and regenerates colors when it changes.
+///
+/// Iterating yields a `WallpaperChange` for each new wallpaper. The
+/// watcher state is locked only while waiting for a change, so `close`
+/// (which just sets a flag the wait polls) works from the callback of
+/// `run` and from other threads.
+#[pyclass(frozen)]
+pub struct WallpaperWatcher {
+    watcher: Mutex<Watcher>,
+    closed: Arc<AtomicBool>,
+}
+
+struct Watcher {
+    inotify: Option<Inotify>,
+    closed: Arc<AtomicBool>,
+    wallpaper: PathBuf,
+    /// Scheme options passed to the constructor; the rest are read from
+    /// the saved options on each change, like the mode.
+    scheme_type: Option<String>,
+    contrast: Option<f64>,
+    source_index: Option<usize>,
+    debounce: Duration,
+    render: bool,
+    shell_cache: bool,
+    apply_on_start: bool,
+    started: bool,
+    link_wd: Option<i32>,
+    /// Watch on the target's directory, and the target's file name.
+    target_wd: Option<i32>,
+    target_name: Option<Vec<u8>>,
+    last: Option<(PathBuf, FileStamp)>,
+}
+
+impl Watcher {
+    /// (Re)watch the link's directory and the current target's directory.
+    fn watch(&mut self) -> io::Result<()> {
+        let Some(inotify) = &self.inotify else {
+            return Ok(());
+        };
+        let parent = |p: &Path| match p.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        self.link_wd = Some(inotify.add_watch(&parent(&self.wallpaper))?);
+
+        // The target may be missing mid-switch; the link's directory
+        // still sees it come back
+        let target = fs::canonicalize(&self.wallpaper).ok();
+        let target_wd = target
+            .as_deref()
+            .and_then(|t| inotify.add_watch(&parent(t)).ok());
+        if let Some(old) = self.target_wd
+            && Some(old) != target_wd
+            && Some(old) != self.link_wd
+        {
+            inotify.rm_watch(old);
+        }
+        self.target_wd = target_wd;
+        self.target_name = target
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|n| n.as_bytes().to_vec());
+        Ok(())
+    }
+
+    fn is_relevant(&self, event: &Event) -> bool {
+        let link_name = self.wallpaper.file_name().map(OsStr::as_bytes);
+        event.mask & (libc::IN_Q_OVERFLOW | libc::IN_IGNORED) != 0
+            || (Some(event.wd) == self.link_wd && Some(event.name.as_slice()) == link_name)
+            || (Some(event.wd) == self.target_wd && Some(&event.name) == self.target_name.as_ref())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Wait for events until `deadline` (or forever). Empty when the
+    /// deadline passed or the watcher was closed.
+    fn wait(&self, py: Python<'_>, deadline: Option<Instant>) -> PyResult<Vec<Event>> {
+        loop {
+            let Some(inotify) = &self.inotify else {
+                return Ok(Vec::new());
+            };
+            if self.is_closed() {
+                return Ok(Vec::new());
+            }
+            let timeout = match deadline {
+                Some(d) => d
+                    .saturating_duration_since(Instant::now())
+                    .min(SIGNAL_POLL_INTERVAL),
+                None => SIGNAL_POLL_INTERVAL,
+            };
+            let events = py.allow_threads(|| inotify.read(timeout))?;
+            if !events.is_empty() {
+                return Ok(events);
+            }
+            py.check_signals()?;
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return Ok(Vec::new());
+            }
+        }
+    }
+
+    /// The current target and its stamp, if it exists.
+    fn current(&self) -> Option<(PathBuf, FileStamp)> {
+        let target = fs::canonicalize(&self.wallpaper).ok()?;
+        let stamp = FileStamp::of(target.to_str()?).ok()?;
+        Some((target, stamp))
+    }
+
+    /// Regenerate everything for the wallpaper at `target`.
+    fn apply(&self, py: Python<'_>, target: &Path) -> PyResult<WallpaperChange> {
+        let wallpaper = target.to_string_lossy().into_owned();
+        let options = material::options_from_args(
+            self.scheme_type.as_deref(),
+            self.contrast,
+            self.source_index,
+        )?;
+        let cached = py.allow_threads(|| colors::cached_palette(&wallpaper, &options, false));
+        colors::warn_corrupt(py, &cached)?;
+        let Some(palette) = cached.palette else {
+            return Ok(WallpaperChange {
+                error: Some(format!("Could not generate colors for {}", wallpaper)),
+                wallpaper,
+                palette: None,
+                templates: Vec::new(),
+                shell_cache: Vec::new(),
+            });
+        };
+
+        let mode = colors::preferred_mode();
+        let mut errors = Vec::new();
+        let mut rendered = Vec::new();
+        if self.render {
+            let ctx = Context {
+                palette: &palette,
+                mode,
+                image: Some(&wallpaper),
+            };
+            match py.allow_threads(|| templates::render_all(&ctx, false)) {
+                Ok(results) => rendered = results,
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        let mut shell_cache = Vec::new();
+        if self.shell_cache {
+            match write_shell_cache(palette.colors(mode)) {
+                Ok(files) => shell_cache = files,
+                Err(e) => errors.push(e),
+            }
+        }
+        Ok(WallpaperChange {
+            wallpaper,
+            palette: Some(palette),
+            templates: rendered,
+            shell_cache,
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+        })
+    }
+
+    /// Apply the wallpaper if it differs from the last one seen.
+    fn check(&mut self, py: Python<'_>) -> PyResult<Option<WallpaperChange>> {
+        let Some(current) = self.current() else {
+            return Ok(None);
+        };
+        if self.last.as_ref() == Some(&current) {
+            return Ok(None);
+        }
+        let change = self.apply(py, &current.0)?;
+        self.last = Some(current);
+        Ok(Some(change))
+    }
+
+    /// Block until the wallpaper changes; `None` once closed.
+    fn next_change(&mut self, py: Python<'_>) -> PyResult<Option<WallpaperChange>> {
+        if self.is_closed() {
+            self.inotify = None;
+            return Ok(None);
+        }
+        if !self.started {
+            self.started = true;
+            if self.apply_on_start {
+                if let Some(change) = self.check(py)? {
+                    return Ok(Some(change));
+                }
+            } else {
+                self.last = self.current();
+            }
+        }
+
+        loop {
+            let events = self.wait(py, None)?;
+            if self.is_closed() || self.inotify.is_none() {
+                self.inotify = None;
+                return Ok(None);
+            }
+            if !events.iter().any(|e| self.is_relevant(e)) {
+                continue;
+            }
+            // Swapping a link is a delete and a create, and images are
+            // often written in several steps; wait for a quiet period
+            while !self
+                .wait(py, Some(Instant::now() + self.debounce))?
+                .is_empty()
+            {}
+            if self.is_closed() {
+                continue;
+            }
+            self.watch()?;
+            if let Some(change) = self.check(py)? {
+                return Ok(Some(change));
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl WallpaperWatcher {
+    #[new]
+    #[pyo3(signature = (wallpaper_path=None, debounce=0.3, scheme_type=None, contrast=None, source_index=None, render=true, shell_cache=true, apply_on_start=true))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        wallpaper_path: Option<PathBuf>,
+        debounce: f64,
+        scheme_type: Option<String>,
+        contrast: Option<f64>,
+        source_index: Option<usize>,
+        render: bool,
+        shell_cache: bool,
+        apply_on_start: bool,
+    ) -> PyResult<Self> {
+        let debounce = Duration::try_from_secs_f64(debounce).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid debounce: {}",
+                debounce
+            ))
+        })?;
+        let wallpaper = match wallpaper_path {
+            Some(path) => path,
+            None => dirs::home_dir()
+                .ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "Could not determine home directory",
+                    )
+                })?
+                .join(".current.wall"),
+        };
+        // Reject invalid options now rather than on the first change
+        material::options_from_args(scheme_type.as_deref(), contrast, source_index)?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let mut watcher = Watcher {
+            inotify: Some(Inotify::new()?),
+            closed: closed.clone(),
+            wallpaper,
+            scheme_type,
+            contrast,
+            source_index,
+            debounce,
+            render,
+            shell_cache,
+            apply_on_start,
+            started: false,
+            link_wd: None,
+            target_wd: None,
+            target_name: None,
+            last: None,
+        };
+        watcher.watch()?;
+        Ok(Self {
+            watcher: Mutex::new(watcher),
+            closed,
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<WallpaperChange>> {
+        self.next_change(py)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) {
+        self.close();
+    }
+
+    /// Call `callback` with each change until closed (or the callback raises).
+    fn run(&self, py: Python<'_>, callback: PyObject) -> PyResult<()> {
+        while let Some(change) = self.next_change(py)? {
+            callback.call1(py, (change,))?;
+        }
+        Ok(())
+    }
+
+    /// Stop watching. A blocked `run` or iteration returns shortly after.
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        // Release the inotify descriptor now unless a wait holds it; that
+        // wait drops it when it sees the flag
+        if let Ok(mut watcher) = self.watcher.try_lock() {
+            watcher.inotify = None;
+        }
+    }
+}
+
+impl WallpaperWatcher {
+    /// Lock the watcher state and wait for the next change.
+    fn next_change(&self, py: Python<'_>) -> PyResult<Option<WallpaperChange>> {
+        let mut watcher = match self.watcher.try_lock() {
+            Ok(watcher) => watcher,
+            // A panic mid-wait leaves nothing half-updated that matters
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "WallpaperWatcher is already waiting for a change",
+                ));
+            }
+        };
+        watcher.next_change(py)
+    }
+}
@@ -31,7 +31,7 @@ COMMAND = {
 def _find_sink(sinks: list[AudioSink], pattern: str) -> AudioSink | None:
     """Find sink matching pattern."""
     for sink in sinks:
-        if re.search(pattern, sink.name, re.IGNORECASE):
+        if re.search(pattern, f"{sink.description} {sink.name}", re.IGNORECASE):
             return sink
     return None
 
@@ -95,8 +95,13 @@ def _show() -> int:
             indicator = "[muted]○[/muted]"
             id_fmt = fmt(sink.id)
 
-        vol_str = f" [muted]vol:[/muted] {fmt(sink.volume)}" if sink.volume is not None else ""
-        console.print(f"  {indicator} {id_fmt}[muted].[/muted] {fmt(sink.name)}{vol_str}")
+        if sink.muted:
+            vol_str = " [muted]vol: muted[/muted]"
+        elif sink.volume is not None:
+            vol_str = f" [muted]vol:[/muted] {fmt(round(sink.volume * 100), '%')}"
+        else:
+            vol_str = ""
+        console.print(f"  {indicator} {id_fmt}[muted].[/muted] {fmt(sink.description)}{vol_str}")
 
     console.print()
     return 0
@@ -1,13 +1,41 @@
 """Matugen cached colors json command."""
+import sys
 from pathlib import Path
 
-from matuwrap.wrp_native import get_cached_colors
+from matuwrap.wrp_native import (
+    ansi_escape,
+    audit_contrast,
+    cache_lookup,
+    cache_stats,
+    export_files,
+    export_formats,
+    export_palette,
+    get_cached_palette,
+    get_cached_colors,
+    get_color_mode,
+    get_scheme_options,
+    prune_cache,
+    render_prompt,
+    render_templates,
+    set_color_mode,
+    set_scheme_options,
+    toggle_color_mode,
+    WallpaperWatcher,
+)
 #from matuwrap.core.theme import console, print_header, print_kv, print_error, fmt
 
 COMMAND = {
     "description": "Get cached wallpaper color",
     "subcommands": [
-        ("ps1", "", "Output bash PS1 fragment with matugen colors"),
+        ("ps1", "[truecolor|256|16|none]", "Output bash PS1 fragment with matugen colors"),
+        ("prompt", "[bash|zsh|fish|nu] [<format>]", "Output a prompt, e.g. '[primary]{user}[/]@{host} {cwd}{git| (%s)}'"),
+        ("mode", "[dark|light|toggle]", "Show or set the light/dark color mode"),
+        ("scheme", "[<type>] [<contrast>] [<index>]", "Show or set the scheme type, contrast and source color index"),
+        ("cache", "[prune <n>]", "Show color cache stats, or keep the <n> most recent wallpapers"),
+        ("render", "[preview]", "Render templates from ~/.config/matuwrap/templates"),
+        ("contrast", "[aaa] [fix]", "Check WCAG contrast of color role pairs, optionally fixing tones"),
+        ("export", "[<format>|<dir>]", "Print one color file format, or write all to <dir> (~/.cache/matuwrap/export)"),
+        ("watch", "", "Regenerate colors, templates and shell cache whenever the wallpaper changes"),
     ],
 }
 
@@ -25,51 +53,201 @@ def primary():
     return hex_color
 
 def hex_to_ansi(hex_color: str) -> str:
-    hex_color = hex_color.lstrip("#")
-    r = int(hex_color[0:2], 16)
-    g = int(hex_color[2:4], 16)
-    b = int(hex_color[4:6], 16)
-    return f"\033[38;2;{r};{g};{b}m"
-
-def _hex_to_ps1(hex_color: str) -> str:
-    """Convert hex color to bash PS1 color escape (literal, for bash to interpret)."""
-    hex_color = hex_color.lstrip("#")
-    r = int(hex_color[0:2], 16)
-    g = int(hex_color[2:4], 16)
-    b = int(hex_color[4:6], 16)
-    return f"\\[\\033[38;2;{r};{g};{b}m\\]"
-
-def ps1() -> str:
-    """Output bash PS1 fragment: colored user@host:workdir."""
-    colors = get_cached_colors(str(WALLPAPER_PATH))
-    reset = "\\[\\033[0m\\]"
+    """Foreground escape for the terminal's color depth (empty without color)."""
+    return ansi_escape(hex_color)
 
-    if not colors:
-        return "\\u@\\h:\\w"
+def ps1(depth: str = "truecolor") -> str:
+    """Output bash PS1 fragment: colored user@host:workdir.
 
-    parts = []
-    primary = colors.get("primary")
-    if primary:
-        parts.append(f"{_hex_to_ps1(primary)}\\u{reset}@{_hex_to_ps1(primary)}\\h{reset}")
-    else:
-        parts.append("\\u@\\h")
+    24-bit unless asked: the shell integration caches one per depth.
+    """
+    palette = get_cached_palette(str(WALLPAPER_PATH))
+    return render_prompt(palette, "bash", depth=depth)
 
-    parts.append(":")
+def prompt(args: tuple[str, ...]) -> int:
+    """Print a prompt for a shell, colored for the current terminal."""
+    shell = args[0] if args else "bash"
+    fmt = args[1] if len(args) > 1 else None
+    palette = get_cached_palette(str(WALLPAPER_PATH))
+    try:
+        print(render_prompt(palette, shell, fmt))
+    except ValueError as e:
+        print(e, file=sys.stderr)
+        return 1
+    return 0
 
-    tertiary = colors.get("tertiary")
-    if tertiary:
-        parts.append(f"{_hex_to_ps1(tertiary)}\\w{reset}")
+def mode(arg: str | None) -> int:
+    """Print the color mode, or set/toggle it and print the new one."""
+    if arg is None:
+        print(get_color_mode())
+    elif arg == "toggle":
+        print(toggle_color_mode())
+    elif arg in ("dark", "light"):
+        set_color_mode(arg)
+        print(arg)
     else:
-        parts.append("\\w")
+        print(f"Unknown mode: {arg}", file=sys.stderr)
+        return 1
+    return 0
+
+def scheme(args: tuple[str, ...]) -> int:
+    """Print the saved scheme options, or set them and print the new ones."""
+    if len(args) > 3:
+        print("Usage: scheme [<type>] [<contrast>] [<index>]", file=sys.stderr)
+        return 1
+    if args:
+        try:
+            set_scheme_options(
+                args[0],
+                float(args[1]) if len(args) > 1 else None,
+                int(args[2]) if len(args) > 2 else None,
+            )
+        except ValueError as e:
+            print(e, file=sys.stderr)
+            return 1
+    options = get_scheme_options()
+    print(f"{options['scheme_type']} contrast={options['contrast']} index={options['source_index']}")
+    return 0
+
+def cache(args: tuple[str, ...]) -> int:
+    """Print cache stats, or prune the cache to the given number of wallpapers."""
+    if args and args[0] == "prune":
+        if len(args) < 2 or not args[1].isdigit():
+            print("Usage: cache prune <n>", file=sys.stderr)
+            return 1
+        print(f"Dropped {prune_cache(int(args[1]))} wallpaper(s)")
+        return 0
+    if args:
+        print(f"Unknown cache command: {args[0]}", file=sys.stderr)
+        return 1
+    stats = cache_stats()
+    print(f"{stats.path} ({stats.size_bytes} bytes)")
+    print(f"{stats.entries}/{stats.max_entries} wallpapers, {stats.palettes} palettes")
+    if stats.corrupt:
+        print(f"Corrupt: {stats.corrupt}", file=sys.stderr)
+    for wallpaper in stats.wallpapers:
+        print(f"  {wallpaper}")
+    if WALLPAPER_PATH.exists():
+        lookup = cache_lookup(str(WALLPAPER_PATH))
+        print(f"{WALLPAPER_PATH}: {lookup.status} ({lookup.reason})")
+    return 0
+
+def render(preview: bool) -> int:
+    """Render theme templates with the current wallpaper's palette."""
+    results = render_templates(str(WALLPAPER_PATH.resolve()), dry_run=preview)
+    if not results:
+        print("No templates configured", file=sys.stderr)
+        return 1
+    failed = 0
+    for result in results:
+        if result.error:
+            failed += 1
+            print(f"{result.name}: {result.error}", file=sys.stderr)
+        else:
+            state = "updated" if result.changed else "unchanged"
+            if preview and result.changed:
+                state = "would update"
+            print(f"{result.name}: {state} {result.output}")
+        for hook in result.hooks:
+            if not hook.ok:
+                failed += 1
+                print(f"{result.name}: {hook.hook}: {hook.error}", file=sys.stderr)
+    return 1 if failed else 0
+
+def contrast(args: tuple[str, ...]) -> int:
+    """Print the role pairs failing WCAG AA (or AAA), and tone fixes with `fix`."""
+    unknown = [a for a in args if a not in ("aaa", "fix")]
+    if unknown:
+        print(f"Unknown contrast option: {unknown[0]}", file=sys.stderr)
+        return 1
+    report = audit_contrast(
+        str(WALLPAPER_PATH.resolve()),
+        level="aaa" if "aaa" in args else "aa",
+        fix="fix" in args,
+    )
+    print(
+        f"{report.mode}, {report.level} ({report.required}:1): "
+        f"{report.failures} of {len(report.pairs)} pairs fail"
+    )
+    for pair in report.failing():
+        line = (
+            f"  {pair.foreground} on {pair.background}: "
+            f"{pair.foreground_color} on {pair.background_color} {pair.ratio:.2f}:1"
+        )
+        if pair.adjusted_ratio is not None:
+            line += f" -> {pair.adjusted_ratio:.2f}:1"
+        print(line)
+    if report.adjusted:
+        print("Adjusted:")
+    for role, hex_color in sorted(report.adjusted.items()):
+        print(f"  {role}: {hex_color}")
+    return 1 if report.unresolved else 0
+
+def export(target: str | None) -> int:
+    """Print the palette in one format, or write every format to a directory."""
+    wallpaper = str(WALLPAPER_PATH.resolve())
+    if target in export_formats():
+        palette = get_cached_palette(wallpaper)
+        if palette is None:
+            print(f"Could not generate colors for {wallpaper}", file=sys.stderr)
+            return 1
+        print(export_palette(palette, target), end="")
+        return 0
+    failed = 0
+    for result in export_files(wallpaper, directory=target):
+        if result.error:
+            failed += 1
+            print(f"{result.format}: {result.error}", file=sys.stderr)
+        else:
+            state = "updated" if result.changed else "unchanged"
+            print(f"{result.format}: {state} {result.path}")
+    return 1 if failed else 0
+
+def watch() -> int:
+    """Refresh colors on every wallpaper change until interrupted."""
+    def report(change) -> None:
+        if change.error:
+            print(f"{change.wallpaper}: {change.error}", file=sys.stderr)
+        updated = [r.name for r in change.templates if r.changed]
+        summary = f"{len(updated)} templates updated" if updated else "no templates changed"
+        print(f"{change.wallpaper}: {summary}", flush=True)
 
-    return "".join(parts)
+    try:
+        with WallpaperWatcher(WALLPAPER_PATH) as watcher:
+            watcher.run(report)
+    except KeyboardInterrupt:
+        pass
+    except OSError as e:
+        print(f"Failed to watch {WALLPAPER_PATH}: {e}", file=sys.stderr)
+        return 1
+    return 0
 
 def run(*args: str) -> int:
     if args and args[0] == "ps1":
-        print(ps1())
+        try:
+            print(ps1(*args[1:2]))
+        except ValueError as e:
+            print(e, file=sys.stderr)
+            return 1
         return 0
-    color = primary()
-    if color:
-        print(color)
+    if args and args[0] == "prompt":
+        return prompt(args[1:])
+    if args and args[0] == "mode":
+        return mode(args[1] if len(args) > 1 else None)
+    if args and args[0] == "scheme":
+        return scheme(args[1:])
+    if args and args[0] == "cache":
+        return cache(args[1:])
+    if args and args[0] == "render":
+        return render(len(args) > 1 and args[1] == "preview")
+    if args and args[0] == "contrast":
+        return contrast(args[1:])
+    if args and args[0] == "export":
+        return export(args[1] if len(args) > 1 else None)
+    if args and args[0] == "watch":
+        return watch()
+    hex_color = primary()
+    if hex_color:
+        print(hex_color)
         return 0
     return 1
\ No newline at end of file
@@ -2,6 +2,8 @@
 
 from __future__ import annotations
 
+import colorsys
+import math
 import os
 import requests
 from pathlib import Path
@@ -21,6 +23,14 @@ from matuwrap.core.theme import (
     fmt,
 )
 
+# Try native implementation
+try:
+    from matuwrap.wrp_native import color
+    _USE_NATIVE = True
+except ImportError:
+    _USE_NATIVE = False
+    color = None  # type: ignore[assignment]
+
 load_dotenv()
 HUE_BRIDGE_IP = os.environ.get("HUE_BRIDGE_IP")
 HUE_USERNAME = os.environ.get("HUE_USERNAME")
@@ -105,71 +115,70 @@ def _check_config() -> bool:
     return True
 
 
+def _to_hex(r: float, g: float, b: float) -> str:
+    """Hex string from sRGB channels in 0.0-1.0."""
+    return "#" + "".join(f"{max(0, min(255, round(c * 255))):02x}" for c in (r, g, b))
+
+
+def _xy_to_hex(x: float, y: float, brightness: float) -> str:
+    """CIE xy + luminance to hex, as `color.from_xy` does it natively."""
+    if y <= 0:
+        return "#000000"
+    Y = max(0.0, min(1.0, brightness))
+    X, Z = Y / y * x, Y / y * (1.0 - x - y)
+    rgb = [
+        max(0.0, X * 3.2404542 - Y * 1.5371385 - Z * 0.4985314),
+        max(0.0, -X * 0.9692660 + Y * 1.8760108 + Z * 0.0415560),
+        max(0.0, X * 0.0556434 - Y * 0.2040259 + Z * 1.0572252),
+    ]
+    # Scale out-of-gamut colors down instead of clamping to keep the hue
+    peak = max(rgb)
+    if peak > 1.0:
+        rgb = [c / peak for c in rgb]
+    gamma = [12.92 * c if c <= 0.0031308 else 1.055 * c ** (1 / 2.4) - 0.055 for c in rgb]
+    return _to_hex(*gamma)
+
+
+def _kelvin_to_hex(kelvin: float) -> str:
+    """Approximate color of a black body (Tanner Helland's fit)."""
+    temp = max(1000.0, min(40000.0, kelvin)) / 100.0
+    if temp <= 66:
+        r = 255.0
+        g = 99.4708025861 * math.log(temp) - 161.1195681661
+        b = 0.0 if temp <= 19 else 138.5177312231 * math.log(temp - 10) - 305.0447927307
+    else:
+        r = 329.698727446 * pow(temp - 60, -0.1332047592)
+        g = 288.1221695283 * pow(temp - 60, -0.0755148492)
+        b = 255.0
+    return _to_hex(r / 255, g / 255, b / 255)
+
+
 def _hue_state_to_hex(state: dict[str, Any]) -> str | None:
     """Convert Hue light state to hex color string.
 
-    Handles both color (hue/sat) and color temperature (ct) modes.
+    Handles color (xy and hue/sat) and color temperature (ct) modes.
     Returns None if no color info available.
     """
     colormode = state.get("colormode")
+    bri = state.get("bri", 254) / 254.0
     if colormode == "xy":
         xy = state.get("xy")
         if xy and len(xy) == 2:
-            x, y = xy
-            bri = state.get("bri", 254)
-            # CIE xy to RGB via XYZ
-            z = 1.0 - x - y
-            Y = bri / 254.0
-            X = (Y / y) * x if y > 0 else 0
-            Z = (Y / y) * z if y > 0 else 0
-            # Wide RGB D65 conversion
-            r = X * 1.656492 - Y * 0.354851 - Z * 0.255038
-            g = -X * 0.707196 + Y * 1.655397 + Z * 0.036152
-            b = X * 0.051713 - Y * 0.121364 + Z * 1.011530
-            # Gamma correction
-            for name, val in [("r", r), ("g", g), ("b", b)]:
-                if val <= 0.0031308:
-                    val = 12.92 * val
-                else:
-                    val = (1.0 + 0.055) * pow(val, 1.0 / 2.4) - 0.055
-                if name == "r":
-                    r = val
-                elif name == "g":
-                    g = val
-                else:
-                    b = val
-            ri = max(0, min(255, int(r * 255)))
-            gi = max(0, min(255, int(g * 255)))
-            bi = max(0, min(255, int(b * 255)))
-            return f"#{ri:02x}{gi:02x}{bi:02x}"
+            if _USE_NATIVE and color is not None:
+                return color.from_xy(xy[0], xy[1], bri)
+            return _xy_to_hex(xy[0], xy[1], bri)
     if colormode == "hs" or (state.get("hue") is not None and state.get("sat") is not None):
         h = state.get("hue", 0) / 65535.0
         s = state.get("sat", 0) / 254.0
-        v = state.get("bri", 254) / 254.0
-        import colorsys
-        r, g, b = colorsys.hsv_to_rgb(h, s, v)
-        return f"#{int(r*255):02x}{int(g*255):02x}{int(b*255):02x}"
+        if _USE_NATIVE and color is not None:
+            return color.from_hsv(h * 360.0, s, bri)
+        return _to_hex(*colorsys.hsv_to_rgb(h, s, bri))
     if colormode == "ct" or state.get("ct") is not None:
-        ct = state.get("ct", 326)
-        # Approximate mired to RGB
-        kelvin = 1000000 / ct
-        # Tanner Helland algorithm
-        temp = kelvin / 100.0
-        if temp <= 66:
-            r = 255
-            g = 99.4708025861 * __import__("math").log(temp) - 161.1195681661
-            if temp <= 19:
-                b = 0
-            else:
-                b = 138.5177312231 * __import__("math").log(temp - 10) - 305.0447927307
-        else:
-            r = 329.698727446 * pow(temp - 60, -0.1332047592)
-            g = 288.1221695283 * pow(temp - 60, -0.0755148492)
-            b = 255
-        ri = max(0, min(255, int(r)))
-        gi = max(0, min(255, int(g)))
-        bi = max(0, min(255, int(b)))
-        return f"#{ri:02x}{gi:02x}{bi:02x}"
+        # ct is in mireds
+        kelvin = 1_000_000 / state.get("ct", 326)
+        if _USE_NATIVE and color is not None:
+            return color.from_kelvin(kelvin)
+        return _kelvin_to_hex(kelvin)
     return None
 
 
@@ -288,29 +297,14 @@ def _hex_to_hue(hex_color: str) -> tuple[int, int]:
 
     Returns (hue: 0-65535, sat: 0-254).
     """
-    hex_color = hex_color.lstrip("#")
-    r, g, b = (int(hex_color[i : i + 2], 16) / 255.0 for i in (0, 2, 4))
-
-    max_c = max(r, g, b)
-    min_c = min(r, g, b)
-    diff = max_c - min_c
-
-    # Hue calculation
-    if diff == 0:
-        h = 0.0
-    elif max_c == r:
-        h = ((g - b) / diff) % 6
-    elif max_c == g:
-        h = (b - r) / diff + 2
+    if _USE_NATIVE and color is not None:
+        h, s, _ = color.to_hsv(hex_color)
     else:
-        h = (r - g) / diff + 4
-    h = h / 6.0  # Normalize to 0-1
-
-    # Saturation calculation
-    s = 0.0 if max_c == 0 else diff / max_c
-
-    # Convert to Hue ranges
-    hue_val = int(h * 65535)
+        hex_color = hex_color.lstrip("#")
+        r, g, b = (int(hex_color[i : i + 2], 16) / 255.0 for i in (0, 2, 4))
+        h, s, _ = colorsys.rgb_to_hsv(r, g, b)
+        h *= 360.0
+    hue_val = int(h / 360.0 * 65535)
     sat_val = int(s * 254)
 
     return hue_val, sat_val
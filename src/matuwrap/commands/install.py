@@ -25,15 +25,41 @@ BASH_INTEGRATION = r"""# matuwrap shell integration
 # --- Matugen color loading (cached) --------------------------------------
 
 _MW_CACHE_DIR="${XDG_CACHE_HOME:-$HOME/.cache}/matuwrap"
-_MW_CACHE_PS1="$_MW_CACHE_DIR/ps1"
 _MW_CACHE_COLOR="$_MW_CACHE_DIR/color"
+_MW_CONFIG_DIR="${XDG_CONFIG_HOME:-$HOME/.config}/matuwrap"
 _MW_WALL="$HOME/.current.wall"
 
-# Regenerate shell cache files via wrp (slow, only on wallpaper change)
+# Color depth of this terminal, detected like wrp_native.detect_color_depth
+if [ -n "$NO_COLOR" ] || [ "$TERM" = dumb ]; then
+    _MW_DEPTH=none
+elif [ "$COLORTERM" = truecolor ] || [ "$COLORTERM" = 24bit ]; then
+    _MW_DEPTH=truecolor
+else
+    case "$TERM" in
+        xterm-kitty|alacritty|foot|foot-extra|wezterm|xterm-ghostty|contour|*-direct)
+            _MW_DEPTH=truecolor ;;
+        *256color*) _MW_DEPTH=256 ;;
+        *)          _MW_DEPTH=16 ;;
+    esac
+fi
+# One prompt per depth: ps1 (truecolor), ps1-256, ps1-16, ps1-none
+if [ "$_MW_DEPTH" = truecolor ]; then
+    _MW_CACHE_PS1="$_MW_CACHE_DIR/ps1"
+else
+    _MW_CACHE_PS1="$_MW_CACHE_DIR/ps1-$_MW_DEPTH"
+fi
+
+# Regenerate shell cache files (slow, only when stale)
 _mw_regen_cache() {
     mkdir -p "$_MW_CACHE_DIR"
-    wrp get_colors ps1 2>/dev/null > "$_MW_CACHE_PS1"
-    wrp get_colors     2>/dev/null > "$_MW_CACHE_COLOR"
+    # wrp-fast (`make fast`) gives the same output without starting Python
+    if command -v wrp-fast >/dev/null 2>&1; then
+        wrp-fast colors ps1 --depth "$_MW_DEPTH" 2>/dev/null > "$_MW_CACHE_PS1"
+        wrp-fast colors get 2>/dev/null > "$_MW_CACHE_COLOR"
+    else
+        wrp get_colors ps1 "$_MW_DEPTH" 2>/dev/null > "$_MW_CACHE_PS1"
+        wrp get_colors     2>/dev/null > "$_MW_CACHE_COLOR"
+    fi
 }
 
 # Load colors from cache files (instant)
@@ -49,19 +75,24 @@ reload-colors() {
     _mw_load_cache
 }
 
-# Check if wallpaper symlink changed since cache was written.
-# Uses stat on the symlink itself (not -L) so re-pointing the symlink
-# is detected even if the target image file is older than the cache.
+# Check if the cache is newer than the wallpaper symlink and the saved
+# mode/scheme. Uses stat on the symlink itself (not -L) so re-pointing the
+# symlink is detected even if the target image file is older than the cache.
 _mw_cache_fresh() {
     [ -f "$_MW_CACHE_PS1" ] || return 1
     [ -e "$_MW_WALL" ]      || return 1
     local wall_mt cache_mt
     wall_mt=$(stat -c %Y "$_MW_WALL" 2>/dev/null)      || return 1
     cache_mt=$(stat -c %Y "$_MW_CACHE_PS1" 2>/dev/null) || return 1
-    [ "$wall_mt" -le "$cache_mt" ]
+    [ "$wall_mt" -le "$cache_mt" ]                      || return 1
+    [ ! "$_MW_CONFIG_DIR/color_mode" -nt "$_MW_CACHE_PS1" ] &&
+        [ ! "$_MW_CONFIG_DIR/scheme.json" -nt "$_MW_CACHE_PS1" ]
 }
 
-# On shell startup: use cache if fresh, else regenerate
+# On shell startup: use the cache if fresh, else regenerate.
+# `wrp get_colors watch` (e.g. `exec-once` in hyprland.conf) rewrites it
+# whenever the wallpaper changes, so with the watcher running the cache is
+# always fresh; the check only matters without it.
 if command -v wrp >/dev/null 2>&1; then
     if _mw_cache_fresh; then
         _mw_load_cache
@@ -93,26 +124,29 @@ __matuwrap_venv() {
 
 # --- PS1 ------------------------------------------------------------------
 
-case "$TERM" in
-    xterm-color|*-256color|xterm-kitty)
-        _MW_RED='\[\033[31m\]'
-        _MW_BOLD='\[\033[1m\]'
-        _MW_NC='\[\033[0m\]'
+if [ "$_MW_DEPTH" != none ]; then
+    _MW_RED='\[\033[31m\]'
+    _MW_BOLD='\[\033[1m\]'
+    _MW_NC='\[\033[0m\]'
 
-        if [ -n "$SSH_CLIENT" ] || [ -n "$SSH_TTY" ]; then
-            _MW_HOST="${_MW_BOLD}\[\033[38;5;214m\]\u@\h:\w${_MW_NC}"
+    if [ -n "$SSH_CLIENT" ] || [ -n "$SSH_TTY" ]; then
+        # Orange, or yellow on 16-color terminals like the Linux console
+        if [ "$_MW_DEPTH" = 16 ]; then
+            _MW_HOST="${_MW_BOLD}\[\033[33m\]\u@\h:\w${_MW_NC}"
         else
-            _MW_HOST="${_MW_BOLD}${WRP_PS1}${_MW_NC}"
+            _MW_HOST="${_MW_BOLD}\[\033[38;5;214m\]\u@\h:\w${_MW_NC}"
         fi
+    else
+        _MW_HOST="${_MW_BOLD}${WRP_PS1}${_MW_NC}"
+    fi
 
-        PS1="\$(__matuwrap_venv)${_MW_HOST}${_MW_RED}\$(__git_ps1 \" (%s)\")${_MW_NC}\n\$ "
+    PS1="\$(__matuwrap_venv)${_MW_HOST}${_MW_RED}\$(__git_ps1 \" (%s)\")${_MW_NC}\n\$ "
 
-        # Terminal title
-        case "$TERM" in
-            xterm*|rxvt*) PS1="\[\e]0;\u@\h: \w\a\]$PS1" ;;
-        esac
-        ;;
-esac
+    # Terminal title
+    case "$TERM" in
+        xterm*|rxvt*) PS1="\[\e]0;\u@\h: \w\a\]$PS1" ;;
+    esac
+fi
 
 # --- Fastfetch override ---------------------------------------------------
 
@@ -278,6 +312,7 @@ def _install_bash() -> int:
 
     console.print()
     console.print("[muted]Reload:  source ~/.bashrc[/muted]")
+    console.print("[muted]Keep colors fresh:  exec-once = wrp get_colors watch  (hyprland.conf)[/muted]")
     return 0
 
 
@@ -1,15 +1,38 @@
 """Monitor information command."""
 
+from __future__ import annotations
+
 from matuwrap.core import hyprland
-from matuwrap.core.hyprland import TRANSFORMS, swap_if_rotated
-from matuwrap.core.theme import console, print_header, print_kv, print_error, fmt
+from matuwrap.core.hyprland import TRANSFORMS
+from matuwrap.core.theme import console, print_header, print_kv, print_error, print_info, print_success, fmt
+
+try:
+    from matuwrap.wrp_native import HyprEventStream, LayoutWatcher, MonitorConfig, list_layout_profiles
+    _USE_NATIVE = True
+except ImportError:
+    _USE_NATIVE = False
+    HyprEventStream = None  # type: ignore[assignment,misc]
+    LayoutWatcher = None  # type: ignore[assignment,misc]
+    MonitorConfig = None  # type: ignore[assignment,misc]
+    list_layout_profiles = None  # type: ignore[assignment]
 
 COMMAND = {
     "description": "Show monitor information",
+    "subcommands": [
+        ("watch", "", "Re-display monitors on hotplug"),
+        ("set", "<name> <mode|off> [XxY] [scale] [transform]", "Apply a monitor layout"),
+        ("preview", "<name> <mode|off> [XxY] [scale] [transform]", "Print monitor= lines without applying"),
+        ("save", "<profile>", "Save current layout as a named profile"),
+        ("restore", "<profile> [preview]", "Restore a saved layout profile"),
+        ("profiles", "", "List saved layout profiles"),
+        ("auto", "[preview]", "Apply matching profiles on hotplug"),
+    ],
 }
 
+HOTPLUG_EVENTS = ["monitoradded", "monitorremoved"]
 
-def run(*args: str) -> int:
+
+def _show() -> int:
     """Display information about all connected monitors."""
     try:
         monitors = hyprland.get_monitors()
@@ -22,22 +45,21 @@ def run(*args: str) -> int:
         return 1
 
     for monitor in monitors:
-        name = monitor.get("name", "unknown")
-        monitor_id = monitor.get("id", "?")
-        make = monitor.get("make", "")
-        model = monitor.get("model", "")
-        width = monitor.get("width", 0)
-        height = monitor.get("height", 0)
-        refresh = round(monitor.get("refreshRate", 0))
-        x = monitor.get("x", 0)
-        y = monitor.get("y", 0)
-        scale = monitor.get("scale", 1)
-        workspace = monitor.get("activeWorkspace", {}).get("name", "?")
-        dpms = "on" if monitor.get("dpmsStatus", True) else "off"
-        transform = monitor.get("transform", 0)
-
-        # Swap dimensions for 90° or 270° rotation
-        width, height = swap_if_rotated(width, height, transform)
+        name = monitor.name or "unknown"
+        monitor_id = monitor.id
+        make = monitor.make
+        model = monitor.model
+        # Dimensions as displayed (swapped for 90° or 270° rotation)
+        width = monitor.transformed_width
+        height = monitor.transformed_height
+        refresh = round(monitor.refresh_rate)
+        x = monitor.x
+        y = monitor.y
+        scale = monitor.scale
+        workspace = monitor.active_workspace.name or "?"
+        dpms = "on" if monitor.dpms_status else "off"
+        transform = monitor.transform
+
         transform_label = TRANSFORMS.get(transform)
 
         print_header(f"{name} [muted](ID {fmt(monitor_id)})[/muted]")
@@ -56,3 +78,169 @@ def run(*args: str) -> int:
 
     console.print()
     return 0
+
+
+def _watch() -> int:
+    """Show monitors, then re-display whenever one is added or removed."""
+    if not _USE_NATIVE or HyprEventStream is None:
+        print_error("Native module not available")
+        return 1
+
+    _show()
+    try:
+        with HyprEventStream(HOTPLUG_EVENTS) as events:
+            for event in events:
+                print_info(f"{fmt(event.event)} [muted]{event.name}[/muted]")  # type: ignore[attr-defined]
+                _show()
+    except KeyboardInterrupt:
+        pass
+    except (ConnectionError, RuntimeError) as e:
+        print_error(f"Failed to watch monitors: {e}")
+        return 1
+    return 0
+
+
+def _parse_config(args: tuple[str, ...]) -> MonitorConfig | None:
+    """Build a MonitorConfig from `<name> <mode|off> [XxY] [scale] [transform]`."""
+    if len(args) < 2:
+        print_error("Usage: <name> <mode|off> [XxY] [scale] [transform]")
+        return None
+    name, mode = args[0], args[1]
+    if mode == "off":
+        return MonitorConfig(name, enabled=False)
+
+    try:
+        position = None
+        if len(args) > 2 and args[2] != "auto":
+            x, y = args[2].split("x", 1)
+            position = (int(x), int(y))
+        scale = float(args[3]) if len(args) > 3 else 1.0
+        transform = int(args[4]) if len(args) > 4 else 0
+    except ValueError:
+        print_error(f"Invalid layout arguments: {' '.join(args[2:])}")
+        return None
+    return MonitorConfig(name, mode, position, scale, transform)
+
+
+def _apply(args: tuple[str, ...], dry_run: bool) -> int:
+    """Apply (or preview) a layout for one monitor."""
+    if not _USE_NATIVE or MonitorConfig is None:
+        print_error("Native module not available")
+        return 1
+
+    config = _parse_config(args)
+    if config is None:
+        return 1
+
+    try:
+        lines = hyprland.apply_layout([config], dry_run=dry_run)
+    except hyprland.HyprlandError as e:
+        print_error(f"Failed to apply layout: {e}")
+        return 1
+
+    for line in lines:
+        console.print(line, markup=False, highlight=False)
+    if not dry_run:
+        print_success(f"Applied layout for {config.name}")
+    return 0
+
+
+def _save(name: str) -> int:
+    """Save the current layout as a named profile."""
+    if not _USE_NATIVE:
+        print_error("Native module not available")
+        return 1
+
+    try:
+        profile = hyprland.save_layout_profile(name)
+    except hyprland.HyprlandError as e:
+        print_error(f"Failed to save profile: {e}")
+        return 1
+
+    print_success(f"Saved profile {name} ({len(profile.monitors)} monitors)")
+    return 0
+
+
+def _restore(name: str, dry_run: bool) -> int:
+    """Restore (or preview) a saved layout profile."""
+    if not _USE_NATIVE:
+        print_error("Native module not available")
+        return 1
+
+    try:
+        lines = hyprland.restore_layout_profile(name, dry_run=dry_run)
+    except hyprland.HyprlandError as e:
+        print_error(f"Failed to restore profile: {e}")
+        return 1
+
+    for line in lines:
+        console.print(line, markup=False, highlight=False)
+    if not dry_run:
+        print_success(f"Restored profile {name}")
+    return 0
+
+
+def _profiles() -> int:
+    """List saved layout profiles."""
+    if not _USE_NATIVE or list_layout_profiles is None:
+        print_error("Native module not available")
+        return 1
+
+    names = list_layout_profiles()
+    if not names:
+        print_info("No saved layout profiles")
+        return 0
+    for name in names:
+        console.print(f"  [value]{name}[/value]")
+    return 0
+
+
+def _auto(dry_run: bool) -> int:
+    """Restore the matching layout profile whenever monitors change."""
+    if not _USE_NATIVE or LayoutWatcher is None:
+        print_error("Native module not available")
+        return 1
+
+    try:
+        with LayoutWatcher(dry_run=dry_run) as watcher:
+            for switch in watcher:
+                connected = ", ".join(switch.monitors)
+                if switch.profile is None:
+                    print_info(f"No profile for [muted]{connected}[/muted]")
+                    continue
+                if switch.error:
+                    print_error(f"Failed to restore {switch.profile}: {switch.error}")
+                    continue
+                for line in switch.lines:
+                    console.print(line, markup=False, highlight=False)
+                if dry_run:
+                    print_info(f"Would restore profile {fmt(switch.profile)}")
+                else:
+                    print_success(f"Restored profile {switch.profile}")
+                    _show()
+    except KeyboardInterrupt:
+        pass
+    except (ConnectionError, RuntimeError) as e:
+        print_error(f"Failed to watch monitors: {e}")
+        return 1
+    return 0
+
+
+def run(*args: str) -> int:
+    """Dispatch monitors subcommands."""
+    if args and args[0] == "watch":
+        return _watch()
+    if args and args[0] in ("set", "preview"):
+        return _apply(args[1:], dry_run=args[0] == "preview")
+    if args and args[0] in ("save", "restore"):
+        if len(args) < 2:
+            print_error("Missing profile name")
+            return 1
+        if args[0] == "save":
+            return _save(args[1])
+        return _restore(args[1], dry_run=args[2:3] == ("preview",))
+    if args and args[0] == "profiles":
+        return _profiles()
+    if args and args[0] == "auto":
+        return _auto(dry_run=args[1:2] == ("preview",))
+    return _show()
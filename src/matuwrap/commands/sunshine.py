@@ -3,7 +3,7 @@
 from pathlib import Path
 
 from matuwrap.core import hyprland, systemd
-from matuwrap.core.hyprland import TRANSFORMS, swap_if_rotated
+from matuwrap.core.hyprland import TRANSFORMS
 from matuwrap.core.notify import notify
 from matuwrap.core.theme import (
     console,
@@ -175,16 +175,13 @@ def list_monitors() -> int:
     table = create_table("", "Name", "Resolution", "Position")
 
     for m in monitors:
-        name = m.get("name", "unknown")
-        width = m.get("width", 0)
-        height = m.get("height", 0)
-        x = m.get("x", 0)
-        y = m.get("y", 0)
-        transform = m.get("transform", 0)
-
-        # Swap dimensions for 90°/270° rotations
-        width, height = swap_if_rotated(width, height, transform)
-        transform_label = TRANSFORMS.get(transform)
+        name = m.name or "unknown"
+        # Dimensions as displayed (swapped for 90°/270° rotations)
+        width = m.transformed_width
+        height = m.transformed_height
+        x = m.x
+        y = m.y
+        transform_label = TRANSFORMS.get(m.transform)
 
         is_current = name == current
         indicator = "[bool_on]●[/bool_on]" if is_current else "[muted]○[/muted]"
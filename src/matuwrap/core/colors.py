@@ -6,7 +6,7 @@ from pathlib import Path
 
 WALLPAPER_PATH = Path.home() / ".current.wall"
 
-_native_get_colors: Callable[[str], dict[str, str]] | None = None
+_native_get_colors: Callable[..., dict[str, str] | None] | None = None
 _USE_NATIVE = False
 
 # Try native implementation
@@ -79,11 +79,27 @@ class Colors:
         )
 
 
-def get_colors(wallpaper: Path | None = None) -> Colors:
+def get_colors(
+    wallpaper: Path | None = None,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+) -> Colors:
     """Get color scheme from wallpaper using matugen.
 
-    Uses native Rust implementation with mtime-based caching.
-    Falls back to default colors if matugen unavailable or fails.
+    Uses the native color cache, keyed by a hash of the image content;
+    the image is only re-hashed when its file stamp (mtime, size, inode)
+    changes. Without matugen, the palette is extracted natively; default
+    colors are used only if that fails too.
+
+    Args:
+        wallpaper: Wallpaper path (default: ~/.current.wall).
+        scheme_type: matugen scheme type; None uses the saved one.
+        mode: "dark" or "light"; None uses the saved mode.
+        contrast: Contrast level from -1.0 to 1.0; None uses the saved one.
+        source_index: Which ranked source color to use (0 = best); None
+            uses the saved one.
     """
     path = wallpaper or WALLPAPER_PATH
 
@@ -95,7 +111,10 @@ def get_colors(wallpaper: Path | None = None) -> Colors:
     if _USE_NATIVE:
         if _native_get_colors is None:
             return Colors.default()
-        colors = _native_get_colors(resolved)
+        try:
+            colors = _native_get_colors(resolved, scheme_type, mode, contrast, source_index)
+        except ValueError:
+            return Colors.default()
         if colors is not None:
             return Colors.from_dict(colors)  # type: ignore
         return Colors.default()
@@ -1,22 +1,44 @@
-"""Hyprland IPC wrapper using native socket communication."""
+"""Hyprland IPC wrapper using native socket communication.
+
+Typed queries (monitors, workspaces, clients, layouts) return the native
+module's classes and need it; without it they raise HyprlandError. Only
+dispatchers fall back to the `hyprctl` binary.
+"""
+
+from __future__ import annotations
 
 from collections.abc import Callable
-from typing import Any
-import json
+from typing import TYPE_CHECKING, TypeVar
 import subprocess
 import logging
 
+if TYPE_CHECKING:
+    from matuwrap.wrp_native import (
+        Client,
+        HyprlandClient,
+        LayoutProfile,
+        Monitor,
+        MonitorConfig,
+        Workspace,
+    )
+
 logger = logging.getLogger(__name__)
 
-_native_hyprctl: Callable[[str], str] | None = None
-_native_hyprctl_json: Callable[[str], str] | None = None
 _USE_NATIVE = False
 
 try:
-    from matuwrap.wrp_native import hyprctl as _native_hyprctl, hyprctl_json as _native_hyprctl_json
+    from matuwrap import wrp_native as _native
     _USE_NATIVE = True
 except ImportError:
-    logger.warning("Native module unavailable, using subprocess fallback (slower)")
+    _native = None  # type: ignore[assignment]
+    logger.warning(
+        "Native module unavailable: dispatchers fall back to hyprctl, typed queries are unsupported"
+    )
+
+T = TypeVar("T")
+
+# Shared native client; resolves the Hyprland socket once per process
+_client: HyprlandClient | None = None
 
 class HyprlandError(Exception):
     """Raised when hyprctl command fails."""
@@ -60,12 +82,37 @@ def swap_if_rotated(width: int, height: int, transform: int) -> tuple[int, int]:
     return width, height
 
 
+def _get_client() -> HyprlandClient:
+    """Return the shared native client, creating it on first use."""
+    global _client
+    if _native is None:
+        raise HyprlandError("Native module unavailable (needed for typed Hyprland queries)")
+    if _client is None:
+        try:
+            _client = _native.HyprlandClient()
+        except RuntimeError as e:
+            raise HyprlandError(str(e)) from e
+    return _client
+
+
+def _query_typed(func: Callable[[HyprlandClient], T]) -> T:
+    """Run a query on the shared client, mapping native errors to HyprlandError.
+
+    Timeouts (HyprlandTimeoutError) are OSErrors and are mapped too.
+    """
+    client = _get_client()
+    try:
+        return func(client)
+    except (OSError, RuntimeError, ValueError) as e:
+        raise HyprlandError(str(e)) from e
+
+
 def _run_hyprctl(*args: str) -> str:
     """Run hyprctl with arguments and return output."""
-    if _USE_NATIVE and _native_hyprctl is not None:
+    if _USE_NATIVE:
         command = " ".join(args)
-        return _native_hyprctl(command)
-    
+        return _query_typed(lambda c: c.hyprctl(command))
+
     # Fallback: use subprocess
     result = subprocess.run(
         ["hyprctl", *args],
@@ -77,43 +124,75 @@ def _run_hyprctl(*args: str) -> str:
     return result.stdout
 
 
-def _query_json(*args: str) -> Any:
-    """Run hyprctl with -j flag and parse JSON output."""
-    if _USE_NATIVE and _native_hyprctl_json is not None:
-        command = " ".join(args)
-        try:
-            return json.loads(_native_hyprctl_json(command))
-        except json.JSONDecodeError as e:
-            raise HyprlandError(f"Invalid JSON from hyprctl: {e}")
-
-    # Fallback: use subprocess
-    output = _run_hyprctl("-j", *args)
-    try:
-        return json.loads(output)
-    except json.JSONDecodeError as e:
-        raise HyprlandError(f"Invalid JSON from hyprctl: {e}")
+def get_monitors() -> list[Monitor]:
+    """Get list of all monitors with their properties."""
+    return _query_typed(lambda c: c.get_monitors())
 
 
-def get_monitors() -> list[dict]:
-    """Get list of all monitors with their properties."""
-    return _query_json("monitors")
+def get_workspaces() -> list[Workspace]:
+    """Get list of all workspaces."""
+    return _query_typed(lambda c: c.get_workspaces())
 
 
-def get_active_workspace() -> dict:
+def get_active_workspace() -> Workspace:
     """Get the currently active workspace."""
-    return _query_json("activeworkspace")
+    return _query_typed(lambda c: c.get_active_workspace())
 
 
-def get_clients() -> list[dict]:
+def get_clients() -> list[Client]:
     """Get list of all windows/clients."""
-    return _query_json("clients")
+    return _query_typed(lambda c: c.get_clients())
 
 
-def get_active_window() -> dict:
-    """Get the currently focused window."""
-    return _query_json("activewindow")
+def get_active_window() -> Client | None:
+    """Get the currently focused window, or None if nothing is focused."""
+    return _query_typed(lambda c: c.get_active_window())
 
 
 def dispatch(command: str, *args: str) -> None:
-    """Execute a Hyprland dispatcher command."""
-    _run_hyprctl("dispatch", command, *args)
+    """Execute a Hyprland dispatcher command.
+
+    Raises:
+        HyprlandError: If Hyprland rejects the dispatcher or its arguments.
+    """
+    if _USE_NATIVE:
+        _query_typed(lambda c: c.dispatch(command, list(args)))
+        return
+
+    reply = _run_hyprctl("dispatch", command, *args).strip()
+    if reply != "ok":
+        raise HyprlandError(reply or f"dispatch {command} failed")
+
+
+def apply_layout(configs: list[MonitorConfig], dry_run: bool = False) -> list[str]:
+    """Validate and apply a monitor layout via `keyword monitor`.
+
+    Returns:
+        The equivalent `monitor=` config lines.
+
+    Raises:
+        HyprlandError: If the layout is invalid or Hyprland rejects a rule.
+    """
+    return _query_typed(lambda c: c.apply_layout(configs, dry_run))
+
+
+def save_layout_profile(name: str) -> LayoutProfile:
+    """Snapshot the current monitor arrangement as a named profile.
+
+    Raises:
+        HyprlandError: If the monitors cannot be queried or the name is invalid.
+    """
+    return _query_typed(lambda c: c.save_layout_profile(name))
+
+
+def restore_layout_profile(name: str, dry_run: bool = False) -> list[str]:
+    """Restore a saved layout profile, matching monitors by make/model/serial.
+
+    Returns:
+        The equivalent `monitor=` config lines.
+
+    Raises:
+        HyprlandError: If the profile is missing, doesn't match the connected
+            monitors, or Hyprland rejects a rule.
+    """
+    return _query_typed(lambda c: c.restore_layout_profile(name, dry_run))
@@ -31,6 +31,14 @@ from PySide6.QtWidgets import (
 from matuwrap.commands.hue import HueController, _check_config
 from matuwrap.core.colors import get_colors, WALLPAPER_PATH
 
+# Try native implementation
+try:
+    from matuwrap.wrp_native import color
+    _USE_NATIVE = True
+except ImportError:
+    _USE_NATIVE = False
+    color = None  # type: ignore[assignment]
+
 # Glaze imports
 from glaze import (
     generate_theme,
@@ -63,10 +71,15 @@ def hue_sat_to_qcolor(hue: int, sat: int, bri: int = 254) -> QColor:
 
 
 def contrast_text(bg: QColor) -> QColor:
-    # simple luminance heuristic
-    r, g, b, _ = bg.getRgb() # type: ignore
-    lum = (0.299 * r + 0.587 * g + 0.114 * b)
-    return QColor("#111111") if lum > 160 else QColor("#f2f2f2")
+    # whichever of near-black/near-white reads better (WCAG contrast)
+    dark, light = "#111111", "#f2f2f2"
+    if not _USE_NATIVE or color is None:
+        # simple luminance heuristic
+        r, g, b, _ = bg.getRgb() # type: ignore
+        return QColor(dark) if 0.299 * r + 0.587 * g + 0.114 * b > 160 else QColor(light)
+    if color.contrast_ratio(dark, bg.name()) >= color.contrast_ratio(light, bg.name()):
+        return QColor(dark)
+    return QColor(light)
 
 
 class NumericTableItem(QTableWidgetItem):
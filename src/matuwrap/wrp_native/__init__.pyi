@@ -0,0 +1,1641 @@
+"""Type stubs for wrp_native Rust extension module."""
+
+from collections.abc import Callable
+from os import PathLike
+from typing import Final
+
+from . import color as color
+
+# Command execution
+
+def run_command(program: str, args: list[str]) -> str:
+    """Run a command and return stdout as string.
+
+    Raises:
+        OSError: If command fails to execute.
+        RuntimeError: If command returns non-zero exit code.
+    """
+    ...
+
+# Hyprland IPC
+
+def hyprctl(command: str, instance: str | None = None) -> str:
+    """Query Hyprland IPC directly via Unix socket.
+
+    Args:
+        command: Hyprland IPC command (e.g., "monitors", "dispatch workspace 1").
+        instance: Instance signature (see `list_instances`). Defaults to
+            HYPRLAND_INSTANCE_SIGNATURE, else the single running instance.
+
+    Returns:
+        Raw response string from Hyprland.
+
+    Raises:
+        RuntimeError: If no Hyprland instance can be resolved.
+        ConnectionError: If socket connection fails.
+        HyprlandTimeoutError: If Hyprland does not answer in time
+            (1s connect, 5s read, 1s write).
+        IOError: If read/write fails.
+    """
+    ...
+
+def hyprctl_json(command: str, instance: str | None = None) -> str:
+    """Query Hyprland IPC with JSON output.
+
+    Args:
+        command: Hyprland IPC command (e.g., "monitors", "clients").
+        instance: Instance signature; see `hyprctl`.
+
+    Returns:
+        JSON string response from Hyprland.
+
+    Raises:
+        RuntimeError: If no Hyprland instance can be resolved.
+        ConnectionError: If socket connection fails.
+        IOError: If read/write fails.
+    """
+    ...
+
+class BatchReply:
+    """Reply to one command of a batched Hyprland request."""
+
+    command: Final[str]
+    """Command as passed to `hyprctl_batch`."""
+
+    output: Final[str]
+    """Raw reply text for this command."""
+
+    ok: Final[bool]
+    """Whether the reply looks successful."""
+
+    error: Final[str | None]
+    """Hyprland's error message, or None if ok."""
+
+    def __repr__(self) -> str: ...
+
+def hyprctl_batch(
+    commands: list[str], json: bool = False, instance: str | None = None
+) -> list[BatchReply]:
+    """Send several commands in one `[[BATCH]]` socket request.
+
+    Args:
+        commands: Hyprland IPC commands. A ';' is only allowed inside `[...]`,
+            as in `exec [workspace 2 silent; float] kitty`; elsewhere
+            Hyprland would split the command there.
+        json: Request JSON output for every command.
+        instance: Instance signature; see `hyprctl`.
+
+    Returns:
+        One BatchReply per command, in order. Dispatch/keyword commands
+        are ok only if Hyprland replied "ok"; JSON queries only if the
+        reply parses as JSON.
+
+    Raises:
+        ValueError: If a command contains ';' outside `[...]`.
+        RuntimeError: If no Hyprland instance can be resolved, or the
+            response cannot be split into one reply per command.
+        ConnectionError: If socket connection fails.
+        IOError: If read/write fails.
+    """
+    ...
+
+class HyprlandTimeoutError(TimeoutError):
+    """Raised when Hyprland does not answer within the configured timeout."""
+
+class HyprlandClient:
+    """Hyprland IPC client bound to one instance.
+
+    Resolves the instance signature and socket path once, and applies
+    timeouts to every request. Each request still uses its own connection
+    (Hyprland closes the socket after replying). If the compositor refuses
+    a connection (e.g. mid-restart), the request is retried once.
+    """
+
+    signature: Final[str]
+    """Instance signature this client talks to."""
+
+    socket_path: Final[str]
+    """Resolved `.socket.sock` path."""
+
+    def __init__(
+        self,
+        connect_timeout: float | None = 1.0,
+        read_timeout: float | None = 5.0,
+        write_timeout: float | None = 1.0,
+        instance: str | None = None,
+    ) -> None:
+        """Resolve the instance to talk to.
+
+        Args:
+            connect_timeout: Seconds to wait for the socket connection.
+            read_timeout: Seconds to wait for Hyprland's reply.
+            write_timeout: Seconds to wait while sending the request.
+                None disables the respective timeout.
+            instance: Instance signature; see `hyprctl`.
+
+        Raises:
+            RuntimeError: If no Hyprland instance can be resolved.
+            ValueError: If a timeout is zero or negative.
+        """
+        ...
+
+    def hyprctl(self, command: str) -> str:
+        """Like `hyprctl()`, raising HyprlandTimeoutError on timeout."""
+        ...
+
+    def hyprctl_json(self, command: str) -> str:
+        """Like `hyprctl_json()`, raising HyprlandTimeoutError on timeout."""
+        ...
+
+    def hyprctl_batch(self, commands: list[str], json: bool = False) -> list[BatchReply]:
+        """Like `hyprctl_batch()`, raising HyprlandTimeoutError on timeout."""
+        ...
+
+    def dispatch(self, name: str, args: list[str] = []) -> None:
+        """Like `dispatch()`, on this client's instance."""
+        ...
+
+    def apply_layout(
+        self, configs: list[MonitorConfig], dry_run: bool = False
+    ) -> list[str]:
+        """Like `apply_layout()`, on this client's instance."""
+        ...
+
+    def save_layout_profile(self, name: str) -> LayoutProfile:
+        """Like `save_layout_profile()`, on this client's instance."""
+        ...
+
+    def restore_layout_profile(self, name: str, dry_run: bool = False) -> list[str]:
+        """Like `restore_layout_profile()`, on this client's instance."""
+        ...
+
+    def get_monitors(self, all: bool = False) -> list[Monitor]: ...
+    def get_workspaces(self) -> list[Workspace]: ...
+    def get_active_workspace(self) -> Workspace: ...
+    def get_clients(self) -> list[Client]: ...
+    def get_active_window(self) -> Client | None: ...
+    def get_layers(self) -> list[Layer]: ...
+    def __repr__(self) -> str: ...
+
+# Hyprland dispatchers
+
+class HyprlandDispatchError(RuntimeError):
+    """Raised when Hyprland rejects a dispatcher or its arguments.
+
+    The message is "<dispatcher>: <Hyprland's reply>".
+    """
+
+def dispatch(name: str, args: list[str] = [], instance: str | None = None) -> None:
+    """Run a Hyprland dispatcher and check that Hyprland replied "ok".
+
+    Args:
+        name: Dispatcher name (e.g. "workspace", "movewindow").
+        args: Arguments, joined with spaces.
+        instance: Instance signature; see `hyprctl`.
+
+    Raises:
+        HyprlandDispatchError: If Hyprland replies with an error.
+        ValueError: If `name` is empty or contains whitespace.
+        RuntimeError: If no Hyprland instance can be resolved.
+        ConnectionError: If socket connection fails.
+        HyprlandTimeoutError: If Hyprland does not answer in time.
+    """
+    ...
+
+def dispatch_workspace(workspace: int | str, instance: str | None = None) -> None:
+    """Switch to a workspace by ID or selector ("name:web", "+1", "special:x")."""
+    ...
+
+def dispatch_move_to_workspace(
+    workspace: int | str,
+    window: str | None = None,
+    silent: bool = False,
+    instance: str | None = None,
+) -> None:
+    """Move a window to a workspace.
+
+    Args:
+        workspace: Workspace ID or selector.
+        window: Window selector (e.g. "address:0x..."); default is focused.
+        silent: Keep focus on the current workspace (movetoworkspacesilent).
+    """
+    ...
+
+def dispatch_focus_monitor(monitor: str, instance: str | None = None) -> None:
+    """Focus a monitor by name, ID or direction ("l", "r", "+1")."""
+    ...
+
+def dispatch_dpms(
+    enabled: bool | None = None, monitor: str | None = None, instance: str | None = None
+) -> None:
+    """Turn DPMS on (True), off (False) or toggle it (None); all monitors if none given."""
+    ...
+
+def dispatch_exec(command: str, rules: str | None = None, instance: str | None = None) -> None:
+    """Launch a command through Hyprland, optionally with window rules
+    (e.g. rules="workspace 3 silent")."""
+    ...
+
+def dispatch_toggle_floating(window: str | None = None, instance: str | None = None) -> None:
+    """Toggle floating for a window (default: the focused one)."""
+    ...
+
+class HyprInstance:
+    """A Hyprland instance directory found on disk."""
+
+    signature: Final[str]
+    """Instance signature (HYPRLAND_INSTANCE_SIGNATURE)."""
+
+    path: Final[str]
+    """Instance directory containing the sockets."""
+
+    pid: Final[int | None]
+    """Compositor PID from `hyprland.lock`."""
+
+    wayland_display: Final[str | None]
+    """Wayland display from `hyprland.lock` (e.g. "wayland-1")."""
+
+    alive: Final[bool]
+    """Whether the instance's socket accepted a connection."""
+
+    def __repr__(self) -> str: ...
+
+def list_instances(alive_only: bool = False) -> list[HyprInstance]:
+    """List Hyprland instances under $XDG_RUNTIME_DIR/hypr and /tmp/hypr.
+
+    Works without HYPRLAND_INSTANCE_SIGNATURE (cron, SSH, systemd units).
+    If XDG_RUNTIME_DIR is unset, /run/user/<uid> is scanned instead.
+
+    Args:
+        alive_only: Only return instances whose socket accepts connections.
+    """
+    ...
+
+# Hyprland data model
+
+class WorkspaceRef:
+    """Workspace reference embedded in monitors and clients."""
+
+    id: Final[int]
+    name: Final[str]
+
+class Monitor:
+    """A monitor as reported by Hyprland (`j/monitors`)."""
+
+    id: Final[int]
+    name: Final[str]
+    """Connector name (e.g. "DP-1")."""
+    description: Final[str]
+    make: Final[str]
+    model: Final[str]
+    serial: Final[str]
+    width: Final[int]
+    """Mode width in pixels, before transform."""
+    height: Final[int]
+    """Mode height in pixels, before transform."""
+    physical_width: Final[int]
+    """Physical width in millimetres."""
+    physical_height: Final[int]
+    """Physical height in millimetres."""
+    refresh_rate: Final[float]
+    x: Final[int]
+    y: Final[int]
+    active_workspace: Final[WorkspaceRef]
+    special_workspace: Final[WorkspaceRef]
+    reserved: Final[list[int]]
+    scale: Final[float]
+    transform: Final[int]
+    """Hyprland transform (0-7)."""
+    focused: Final[bool]
+    dpms_status: Final[bool]
+    vrr: Final[bool]
+    disabled: Final[bool]
+    current_format: Final[str]
+    mirror_of: Final[str]
+    available_modes: Final[list[str]]
+    """Supported modes (e.g. "2560x1440@165.00Hz")."""
+    is_rotated: Final[bool]
+    """Whether the transform swaps width and height (90°/270°)."""
+    transformed_width: Final[int]
+    """Pixel width after applying the transform."""
+    transformed_height: Final[int]
+    """Pixel height after applying the transform."""
+    logical_width: Final[int]
+    """Layout width in logical pixels (transformed, divided by scale)."""
+    logical_height: Final[int]
+    """Layout height in logical pixels (transformed, divided by scale)."""
+
+    def __repr__(self) -> str: ...
+
+class Workspace:
+    """A workspace as reported by Hyprland (`j/workspaces`)."""
+
+    id: Final[int]
+    name: Final[str]
+    monitor: Final[str]
+    monitor_id: Final[int]
+    windows: Final[int]
+    has_fullscreen: Final[bool]
+    last_window: Final[str]
+    last_window_title: Final[str]
+    is_persistent: Final[bool]
+
+    def __repr__(self) -> str: ...
+
+class Client:
+    """A window as reported by Hyprland (`j/clients`)."""
+
+    address: Final[str]
+    mapped: Final[bool]
+    hidden: Final[bool]
+    at: Final[tuple[int, int]]
+    size: Final[tuple[int, int]]
+    workspace: Final[WorkspaceRef]
+    floating: Final[bool]
+    pseudo: Final[bool]
+    monitor: Final[int]
+    window_class: Final[str]
+    """Window class (`class` in Hyprland's JSON)."""
+    title: Final[str]
+    initial_class: Final[str]
+    initial_title: Final[str]
+    pid: Final[int]
+    xwayland: Final[bool]
+    pinned: Final[bool]
+    fullscreen: Final[int]
+    """Fullscreen mode (0 = none)."""
+    grouped: Final[list[str]]
+    tags: Final[list[str]]
+    focus_history_id: Final[int]
+
+    def __repr__(self) -> str: ...
+
+class Layer:
+    """A layer-shell surface as reported by Hyprland (`j/layers`)."""
+
+    address: Final[str]
+    x: Final[int]
+    y: Final[int]
+    w: Final[int]
+    h: Final[int]
+    namespace: Final[str]
+    pid: Final[int]
+    monitor: Final[str]
+    """Monitor name the surface is on."""
+    level: Final[int]
+    """Layer level 0-3 (background, bottom, top, overlay)."""
+
+    def __repr__(self) -> str: ...
+
+def get_monitors(all: bool = False, instance: str | None = None) -> list[Monitor]:
+    """Get all monitors.
+
+    Args:
+        all: Include disabled monitors.
+        instance: Instance signature; see `hyprctl`.
+
+    Raises:
+        RuntimeError: If no Hyprland instance can be resolved.
+        ConnectionError: If socket connection fails.
+        ValueError: If Hyprland returns malformed JSON.
+    """
+    ...
+
+def get_workspaces(instance: str | None = None) -> list[Workspace]:
+    """Get all workspaces. Raises like `get_monitors`."""
+    ...
+
+def get_active_workspace(instance: str | None = None) -> Workspace:
+    """Get the currently active workspace. Raises like `get_monitors`."""
+    ...
+
+def get_clients(instance: str | None = None) -> list[Client]:
+    """Get all windows. Raises like `get_monitors`."""
+    ...
+
+def get_active_window(instance: str | None = None) -> Client | None:
+    """Get the focused window, or None. Raises like `get_monitors`."""
+    ...
+
+def get_layers(instance: str | None = None) -> list[Layer]:
+    """Get all layer-shell surfaces. Raises like `get_monitors`."""
+    ...
+
+# Monitor layout
+
+class MonitorConfig:
+    """Desired configuration for one monitor, applied via `keyword monitor`."""
+
+    name: str
+    """Connector name (e.g. "DP-1") or "desc:" selector."""
+    mode: str
+    """"WxH@Hz", "WxH", or "preferred" / "highres" / "highrr" / "maxwidth"."""
+    position: tuple[int, int] | None
+    """Top-left position in layout pixels; None places it automatically."""
+    scale: float
+    transform: int
+    """Hyprland transform (0-7)."""
+    enabled: bool
+
+    def __init__(
+        self,
+        name: str,
+        mode: str = "preferred",
+        position: tuple[int, int] | None = None,
+        scale: float = 1.0,
+        transform: int = 0,
+        enabled: bool = True,
+    ) -> None: ...
+    def to_rule(self) -> str:
+        """Rule text for `keyword monitor` (e.g. "DP-1,2560x1440@165,0x0,1")."""
+        ...
+
+    def to_config_line(self) -> str:
+        """Equivalent hyprland.conf line (e.g. "monitor=DP-1,2560x1440@165,0x0,1")."""
+        ...
+
+    def __repr__(self) -> str: ...
+
+def current_layout(instance: str | None = None) -> list[MonitorConfig]:
+    """Snapshot the current configuration of all monitors, including disabled ones.
+
+    Raises:
+        RuntimeError: If no Hyprland instance can be resolved.
+        ConnectionError: If the Hyprland socket cannot be reached.
+    """
+    ...
+
+def validate_layout(
+    configs: list[MonitorConfig], instance: str | None = None
+) -> list[str]:
+    """Check a layout against the monitors Hyprland reports.
+
+    Checks that each monitor exists, that its mode is in `available_modes`
+    (refresh within 0.5Hz), and that scale and transform are in range.
+
+    Returns:
+        List of problems; empty if the layout is valid.
+    """
+    ...
+
+def apply_layout(
+    configs: list[MonitorConfig], dry_run: bool = False, instance: str | None = None
+) -> list[str]:
+    """Validate a layout and apply it in one batched `keyword monitor` request.
+
+    Args:
+        configs: One config per monitor to change.
+        dry_run: Only validate; don't apply anything.
+        instance: Instance signature; see `hyprctl`.
+
+    Returns:
+        The equivalent `monitor=` config lines.
+
+    Raises:
+        ValueError: If validation fails (all problems are listed).
+        RuntimeError: If Hyprland rejects a rule, or no instance can be resolved.
+    """
+    ...
+
+# Monitor layout profiles
+
+class ProfileMonitor:
+    """One monitor in a saved layout profile, identified by make/model/serial."""
+
+    make: Final[str]
+    model: Final[str]
+    serial: Final[str]
+    name: Final[str]
+    """Connector name when the profile was saved; not used for matching."""
+    mode: Final[str]
+    position: Final[tuple[int, int]]
+    scale: Final[float]
+    transform: Final[int]
+    enabled: Final[bool]
+    dpms: Final[bool]
+
+    def __repr__(self) -> str: ...
+
+class LayoutProfile:
+    """A named monitor layout stored under ~/.config/matuwrap/layouts/."""
+
+    name: Final[str]
+    monitors: Final[list[ProfileMonitor]]
+
+    def __repr__(self) -> str: ...
+
+def save_layout_profile(name: str, instance: str | None = None) -> LayoutProfile:
+    """Snapshot the current monitor arrangement as a named profile.
+
+    Replaces any existing profile with the same name.
+
+    Raises:
+        ValueError: If `name` is empty, starts with "." or contains "/".
+        OSError: If the profile cannot be written.
+        RuntimeError: If no Hyprland instance can be resolved.
+    """
+    ...
+
+def load_layout_profile(name: str) -> LayoutProfile:
+    """Load a saved profile without applying it.
+
+    Raises:
+        FileNotFoundError: If no profile with that name exists.
+        ValueError: If the name or the profile file is invalid.
+    """
+    ...
+
+def list_layout_profiles() -> list[str]:
+    """Names of all saved layout profiles, sorted."""
+    ...
+
+def delete_layout_profile(name: str) -> bool:
+    """Delete a saved profile. Returns False if it didn't exist."""
+    ...
+
+def restore_layout_profile(
+    name: str, dry_run: bool = False, instance: str | None = None
+) -> list[str]:
+    """Restore a saved profile.
+
+    Each profile monitor is matched to a connected one by make/model/serial,
+    so connector renumbering (DP-1/DP-2) doesn't matter. The layout is
+    applied as in `apply_layout()`, then DPMS is set per monitor.
+
+    Args:
+        name: Profile name.
+        dry_run: Only match and validate; don't apply anything.
+        instance: Instance signature; see `hyprctl`.
+
+    Returns:
+        The equivalent `monitor=` config lines.
+
+    Raises:
+        FileNotFoundError: If no profile with that name exists.
+        ValueError: If a profile monitor is not connected, or validation fails.
+        RuntimeError: If Hyprland rejects a rule, or no instance can be resolved.
+    """
+    ...
+
+# Monitor hotplug
+
+class LayoutSwitch:
+    """Outcome of one change in the set of connected monitors."""
+
+    monitors: Final[list[str]]
+    """Connected monitors as "make model serial", sorted."""
+    profile: Final[str | None]
+    """Profile that matched, or None if no saved profile fits."""
+    lines: Final[list[str]]
+    """`monitor=` lines applied (or that would be, with dry_run)."""
+    error: Final[str | None]
+    """Why restoring the matched profile failed, if it did."""
+
+    def __repr__(self) -> str: ...
+
+class LayoutWatcher:
+    """Restore the matching layout profile whenever monitors are plugged in or out.
+
+    Listens for `monitoradded`/`monitorremoved`, waits `debounce` seconds for
+    the event burst to settle, then fingerprints the connected monitors by
+    make/model/serial. If the set changed, the saved profile with exactly
+    those monitors is restored. Yields a `LayoutSwitch` per change; iteration
+    ends when Hyprland exits. Errors restoring a profile are reported in
+    `LayoutSwitch.error` rather than raised.
+
+    Example:
+        with LayoutWatcher() as watcher:
+            for switch in watcher:
+                print(switch.profile, switch.lines)
+    """
+
+    def __init__(
+        self,
+        debounce: float = 0.5,
+        dry_run: bool = False,
+        apply_on_start: bool = True,
+        instance: str | None = None,
+    ) -> None:
+        """Connect to the event socket.
+
+        Args:
+            debounce: Seconds without hotplug events before re-checking.
+            dry_run: Match and validate profiles but don't apply them.
+            apply_on_start: Check and apply once before the first event.
+            instance: Instance signature; see `hyprctl`.
+
+        Raises:
+            ValueError: If `debounce` is negative.
+            ConnectionError: If the event socket cannot be reached.
+            RuntimeError: If no Hyprland instance can be resolved.
+        """
+        ...
+
+    def __iter__(self) -> LayoutWatcher: ...
+    def __next__(self) -> LayoutSwitch: ...
+    def __enter__(self) -> LayoutWatcher: ...
+    def __exit__(self, *args: object) -> None: ...
+    def close(self) -> None:
+        """Stop watching. Further iteration stops immediately."""
+        ...
+
+def find_matching_profile(instance: str | None = None) -> str | None:
+    """Name of the saved profile whose monitors exactly match the connected ones."""
+    ...
+
+# Hyprland events
+
+class HyprEvent:
+    """Event from Hyprland's event socket (`.socket2.sock`).
+
+    Each known event is a subclass (e.g. `HyprEvent.MonitorAdded`) with typed
+    fields. Unknown events arrive as `HyprEvent.Other`.
+    """
+
+    @property
+    def event(self) -> str:
+        """Raw Hyprland event name (e.g. "monitoradded")."""
+        ...
+
+    class Workspace(HyprEvent):
+        name: Final[str]
+    class WorkspaceV2(HyprEvent):
+        id: Final[int]
+        name: Final[str]
+    class FocusedMonitor(HyprEvent):
+        monitor: Final[str]
+        workspace: Final[str]
+    class ActiveWindow(HyprEvent):
+        window_class: Final[str]
+        title: Final[str]
+    class ActiveWindowV2(HyprEvent):
+        address: Final[str]
+    class Fullscreen(HyprEvent):
+        enabled: Final[bool]
+    class MonitorAdded(HyprEvent):
+        name: Final[str]
+    class MonitorAddedV2(HyprEvent):
+        id: Final[int]
+        name: Final[str]
+        description: Final[str]
+    class MonitorRemoved(HyprEvent):
+        name: Final[str]
+    class MonitorRemovedV2(HyprEvent):
+        id: Final[int]
+        name: Final[str]
+        description: Final[str]
+    class CreateWorkspace(HyprEvent):
+        name: Final[str]
+    class DestroyWorkspace(HyprEvent):
+        name: Final[str]
+    class MoveWorkspace(HyprEvent):
+        workspace: Final[str]
+        monitor: Final[str]
+    class RenameWorkspace(HyprEvent):
+        id: Final[int]
+        name: Final[str]
+    class ActiveSpecial(HyprEvent):
+        workspace: Final[str]
+        monitor: Final[str]
+    class ActiveLayout(HyprEvent):
+        keyboard: Final[str]
+        layout: Final[str]
+    class OpenWindow(HyprEvent):
+        address: Final[str]
+        workspace: Final[str]
+        window_class: Final[str]
+        title: Final[str]
+    class CloseWindow(HyprEvent):
+        address: Final[str]
+    class MoveWindow(HyprEvent):
+        address: Final[str]
+        workspace: Final[str]
+    class WindowTitle(HyprEvent):
+        address: Final[str]
+    class ChangeFloatingMode(HyprEvent):
+        address: Final[str]
+        floating: Final[bool]
+    class Urgent(HyprEvent):
+        address: Final[str]
+    class OpenLayer(HyprEvent):
+        namespace: Final[str]
+    class CloseLayer(HyprEvent):
+        namespace: Final[str]
+    class Submap(HyprEvent):
+        name: Final[str]
+    class ConfigReloaded(HyprEvent): ...
+    class Other(HyprEvent):
+        name: Final[str]
+        data: Final[str]
+
+class HyprEventStream:
+    """Blocking iterator over Hyprland events.
+
+    The GIL is released while waiting for the next event. Iteration stops
+    when Hyprland closes the socket or `close()` is called, which also
+    works from another thread while one is waiting.
+
+    Example:
+        for event in HyprEventStream(["monitoradded", "monitorremoved"]):
+            ...
+    """
+
+    connected: Final[bool]
+    """Whether the socket is still open."""
+
+    def __init__(self, events: list[str] | None = None, instance: str | None = None) -> None:
+        """Connect to `.socket2.sock`.
+
+        Args:
+            events: Raw event names to keep (e.g. ["monitoradded"]).
+                None yields every event.
+            instance: Instance signature; see `hyprctl`.
+
+        Raises:
+            RuntimeError: If no Hyprland instance can be resolved.
+            ConnectionError: If socket connection fails.
+        """
+        ...
+
+    def __iter__(self) -> HyprEventStream: ...
+    def __next__(self) -> HyprEvent:
+        """Block until the next event.
+
+        Raises:
+            IOError: If reading from the socket fails.
+            RuntimeError: If another thread is already waiting on this stream.
+        """
+        ...
+    def __enter__(self) -> HyprEventStream: ...
+    def __exit__(self, *args: object) -> None: ...
+    def close(self) -> None:
+        """Close the socket; further iteration stops, and a waiting
+        iteration returns within 200ms."""
+        ...
+
+# Matugen color caching
+
+class ColorCacheCorruptWarning(UserWarning):
+    """Warned when the color cache file can't be parsed.
+
+    The file is moved to colors.json.corrupt and the cache starts empty.
+    """
+
+class ColorScheme:
+    """One scheme (dark or light) of a `Palette`.
+
+    Roles read as attributes or items: `scheme.primary`,
+    `scheme["on_surface"]`.
+    """
+
+    mode: Final[str]
+    """"dark" or "light"."""
+
+    def __getattr__(self, role: str) -> str:
+        """Color of `role` as "#rrggbb".
+
+        Raises:
+            AttributeError: If the scheme has no such role.
+        """
+        ...
+    def __getitem__(self, role: str) -> str:
+        """Color of `role` as "#rrggbb".
+
+        Raises:
+            KeyError: If the scheme has no such role.
+        """
+        ...
+    def __contains__(self, role: str) -> bool: ...
+    def __len__(self) -> int: ...
+    def get(self, role: str, default: str | None = None) -> str | None:
+        """Color of `role`, or `default` if missing."""
+        ...
+    def keys(self) -> list[str]:
+        """Role names, sorted."""
+        ...
+    def to_dict(self) -> dict[str, str]:
+        """Dict of role -> "#rrggbb"."""
+        ...
+    def __repr__(self) -> str: ...
+
+class Palette:
+    """Dark and light schemes generated from the same source color."""
+
+    dark: Final[ColorScheme]
+    light: Final[ColorScheme]
+
+    def scheme(self, mode: str | None = None) -> ColorScheme:
+        """Scheme for "dark" or "light"; None uses the saved mode.
+
+        Raises:
+            ValueError: If `mode` is not "dark" or "light".
+        """
+        ...
+    def get(self, role: str, mode: str | None = None) -> str | None:
+        """Color of `role` in `mode` (None: the saved mode), or None if missing.
+
+        Raises:
+            ValueError: If `mode` is not "dark" or "light".
+        """
+        ...
+    def roles(self) -> list[str]:
+        """Role names across both schemes, sorted."""
+        ...
+    def __repr__(self) -> str: ...
+
+def get_cached_colors(
+    wallpaper_path: str,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+    verify: bool = False,
+) -> dict[str, str] | None:
+    """Get matugen colors with caching.
+
+    Colors are cached to ~/.cache/matuwrap/colors.json, keyed by a hash
+    of the image content, so the same image hits at any path or mtime. The
+    hash is only recomputed when the file's nanosecond mtime, size or inode
+    changes (or always, with `verify`). The 32 most recently used
+    wallpapers are kept (see `prune_cache`). Writes are atomic, and a miss
+    holds a lock on the cache while generating, so concurrent callers wait
+    for one matugen run instead of each starting their own. Each
+    combination of scheme type, contrast and source index is cached
+    separately for the same wallpaper, with both its dark and light scheme
+    from one matugen run, so switching modes never regenerates. If matugen
+    is not installed or fails, the palette is generated natively (see
+    `extract_colors`).
+
+    Args:
+        wallpaper_path: Path to wallpaper image file.
+        scheme_type: matugen scheme type (e.g. "scheme-tonal-spot",
+            "scheme-vibrant", "scheme-expressive").
+        mode: "dark" or "light"; None uses the saved mode (see
+            `get_color_mode`).
+        contrast: Contrast level from -1.0 (reduced) to 1.0 (high).
+        source_index: Which ranked source color to use (0 = best).
+
+        None for `scheme_type`, `contrast` or `source_index` uses the
+        saved value (see `get_scheme_options`).
+        verify: Hash the image even if its stamp is unchanged, catching
+            in-place rewrites that preserve mtime and size.
+
+    Returns:
+        Dict of color_name -> hex_value, or None if both matugen and
+        native extraction fail.
+
+    Warns:
+        ColorCacheCorruptWarning: If the cache file was unreadable and
+            had to be discarded.
+
+    Raises:
+        ValueError: If the scheme type, mode or contrast is invalid.
+    """
+    ...
+
+def get_cached_palette(
+    wallpaper_path: str,
+    scheme_type: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+    verify: bool = False,
+) -> Palette | None:
+    """Like `get_cached_colors`, but returns both the dark and light scheme.
+
+    Shares the cache with `get_cached_colors`.
+
+    Returns:
+        The palette, or None if both matugen and native extraction fail.
+
+    Raises:
+        ValueError: If the scheme type or contrast is invalid.
+    """
+    ...
+
+def invalidate_color_cache() -> None:
+    """Invalidate the color cache, forcing regeneration on next call."""
+    ...
+
+class CacheStatus:
+    """Whether a palette is cached for a wallpaper and scheme options."""
+
+    Hit: Final[CacheStatus]
+    """Cached for this image content."""
+    Stale: Final[CacheStatus]
+    """Cached for what this path held before, but the file has changed."""
+    Miss: Final[CacheStatus]
+    """Nothing cached for this image."""
+    Corrupt: Final[CacheStatus]
+    """The cache file exists but can't be parsed."""
+
+class CacheLookup:
+    """Result of `cache_lookup`."""
+
+    status: Final[CacheStatus]
+    hash: Final[str | None]
+    """Content hash of the wallpaper (None if the cache is corrupt)."""
+    reason: Final[str]
+    """Human-readable explanation of the status."""
+
+    def __repr__(self) -> str: ...
+
+def cache_lookup(
+    wallpaper_path: str,
+    scheme_type: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+    verify: bool = False,
+) -> CacheLookup:
+    """Check whether a palette is cached for a wallpaper, for diagnostics.
+
+    Doesn't generate or write anything. Arguments are as in
+    `get_cached_colors`.
+
+    Raises:
+        OSError: If the wallpaper cannot be read.
+        ValueError: If the scheme type or contrast is invalid.
+    """
+    ...
+
+class CacheStats:
+    """What the color cache currently holds."""
+
+    path: Final[str | None]
+    """Path of the cache file."""
+    size_bytes: Final[int]
+    """Size of the cache file in bytes (0 if it doesn't exist)."""
+    entries: Final[int]
+    """Number of cached wallpapers."""
+    max_entries: Final[int]
+    """Wallpapers kept before the least recently used is dropped."""
+    palettes: Final[int]
+    """Number of cached palettes across all wallpapers."""
+    wallpapers: Final[list[str]]
+    """Last used path of each cached wallpaper, most recent first."""
+    corrupt: Final[str | None]
+    """Why the cache file can't be parsed, if it can't."""
+
+    def __repr__(self) -> str: ...
+
+def cache_stats() -> CacheStats:
+    """Summarize the color cache."""
+    ...
+
+def prune_cache(max_entries: int) -> int:
+    """Keep only the `max_entries` most recently used wallpapers.
+
+    Returns:
+        Number of wallpapers dropped.
+
+    Raises:
+        OSError: If the pruned cache cannot be written.
+        ValueError: If the cache file is corrupt.
+    """
+    ...
+
+def get_color_mode() -> str:
+    """Saved color mode ("dark" or "light"); "dark" if never set.
+
+    Stored in ~/.config/matuwrap/color_mode.
+    """
+    ...
+
+def set_color_mode(mode: str) -> None:
+    """Save the color mode used when `mode` isn't passed explicitly.
+
+    Raises:
+        ValueError: If `mode` is not "dark" or "light".
+    """
+    ...
+
+def toggle_color_mode() -> str:
+    """Switch the saved color mode between dark and light. Returns the new mode."""
+    ...
+
+def get_scheme_options() -> dict[str, str | float | int]:
+    """Saved scheme options, as a dict with "scheme_type", "contrast" and
+    "source_index"; tonal spot, 0.0 and 0 if never set.
+
+    Every function taking these options uses the saved ones for any passed
+    as None. Stored in ~/.config/matuwrap/scheme.json.
+    """
+    ...
+
+def set_scheme_options(
+    scheme_type: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+) -> None:
+    """Save the scheme options used when they aren't passed explicitly.
+
+    Options passed as None keep their saved value.
+
+    Raises:
+        ValueError: If the scheme type or contrast is invalid.
+    """
+    ...
+
+def extract_colors(
+    image_path: str,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+) -> dict[str, str]:
+    """Generate a Material You palette from an image without matugen.
+
+    Decodes the image (PNG, JPEG or WebP), quantizes it (Celebi: Wu +
+    WSMeans), ranks clusters with Material's score and builds the scheme
+    from the chosen source color. Keys match matugen's JSON output, plus
+    "source_color". Not cached.
+
+    Args:
+        image_path: Path to the image file.
+        scheme_type: Scheme type, as in `get_cached_colors`.
+        mode: "dark" or "light"; None uses the saved mode.
+        contrast: Contrast level from -1.0 to 1.0.
+        source_index: Which ranked source color to use (0 = best).
+            None for any of the scheme options uses the saved value.
+
+    Returns:
+        Dict of color_name -> "#rrggbb".
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If the options are invalid, the image cannot be decoded,
+            or it has no source color at `source_index`.
+    """
+    ...
+
+def extract_palette(
+    image_path: str,
+    scheme_type: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+) -> Palette:
+    """Like `extract_colors`, but returns both the dark and light scheme.
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If the options are invalid, the image cannot be decoded,
+            or it has no source color at `source_index`.
+    """
+    ...
+
+# Contrast audit
+
+class ContrastPair:
+    """WCAG contrast of one foreground/background role pair."""
+
+    foreground: Final[str]
+    """Foreground role, e.g. "on_surface"."""
+    background: Final[str]
+    """Background role, e.g. "surface"."""
+    foreground_color: Final[str]
+    background_color: Final[str]
+    ratio: Final[float]
+    """Contrast ratio, 1.0 to 21.0."""
+    aa: Final[bool]
+    """At least 4.5:1."""
+    aaa: Final[bool]
+    """At least 7:1."""
+    passes: Final[bool]
+    """Meets the level the audit asked for."""
+    adjusted_ratio: Final[float | None]
+    """Ratio after `fix` changed either role, otherwise None."""
+
+    def __repr__(self) -> str: ...
+
+class ContrastReport:
+    """Result of `audit_palette` or `audit_contrast`."""
+
+    mode: Final[str]
+    """"dark" or "light"."""
+    level: Final[str]
+    """"AA" or "AAA"."""
+    required: Final[float]
+    """Minimum ratio for `level` (4.5 or 7.0)."""
+    pairs: Final[list[ContrastPair]]
+    """Every checked pair whose roles are in the palette."""
+    failures: Final[int]
+    """Pairs below `level`."""
+    adjusted: Final[dict[str, str]]
+    """Role -> new "#rrggbb" for roles `fix` changed (empty without `fix`)."""
+    unresolved: Final[int]
+    """Pairs still below `level` after `fix` (equal to `failures` without it)."""
+
+    def failing(self) -> list[ContrastPair]:
+        """Pairs below the audited level."""
+        ...
+
+    def __repr__(self) -> str: ...
+
+def audit_palette(
+    palette: Palette,
+    mode: str | None = None,
+    level: str = "aa",
+    fix: bool = False,
+) -> ContrastReport:
+    """Check WCAG contrast of the palette's foreground/background role pairs.
+
+    Covers the Material "on_*" pairs plus the roles the CLI theme prints on
+    `surface` (primary, secondary, tertiary, error, outline, outline_variant).
+    Pairs are judged as text: AA is 4.5:1, AAA 7:1.
+
+    Args:
+        palette: Palette to audit (see `get_cached_palette`).
+        mode: Scheme to audit; None uses the saved mode.
+        level: "aa" or "aaa".
+        fix: Move failing foregrounds to the nearest HCT tone (same hue and
+            chroma) that passes against every role they're paired with.
+            The new colors are in `ContrastReport.adjusted`; the palette
+            itself is not changed.
+
+    Raises:
+        ValueError: If `mode` or `level` is invalid.
+    """
+    ...
+
+def audit_contrast(
+    wallpaper_path: str,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+    level: str = "aa",
+    fix: bool = False,
+) -> ContrastReport:
+    """Like `audit_palette`, with the wallpaper's cached palette.
+
+    Raises:
+        ValueError: If an option is invalid.
+        RuntimeError: If no palette could be generated for the wallpaper.
+    """
+    ...
+
+# Palette export
+
+class ExportResult:
+    """Outcome of writing one export file."""
+
+    format: Final[str]
+    """Format name, e.g. "kitty"."""
+    path: Final[str]
+    """File written (empty if the format is unknown)."""
+    changed: Final[bool]
+    """The file's contents changed."""
+    error: Final[str | None]
+    """Why exporting or writing failed, if it did."""
+
+    def __repr__(self) -> str: ...
+
+def export_formats() -> list[str]:
+    """Names of the supported export formats.
+
+    "xresources", "kitty", "alacritty", "foot", "gtk" (`@define-color`,
+    including libadwaita's named colors), "css" (custom properties on
+    `:root`) and "json" (W3C design tokens).
+    """
+    ...
+
+def export_palette(palette: Palette, format: str, mode: str | None = None) -> str:
+    """Render a palette's scheme as a color file.
+
+    Terminal formats use the scheme's surface/on_surface as background and
+    foreground, primary as cursor, and the 16 colors of `ansi_palette`.
+
+    Args:
+        palette: Palette to export (see `get_cached_palette`).
+        format: One of `export_formats()`.
+        mode: Scheme to export; None uses the saved mode.
+
+    Raises:
+        ValueError: If the format or mode is unknown, or the scheme lacks
+            primary, surface or on_surface.
+    """
+    ...
+
+def ansi_palette(palette: Palette, mode: str | None = None) -> list[str]:
+    """The 16 ANSI terminal colors derived from a palette's scheme.
+
+    Grays come from the surface, outline and on_surface roles; red, green,
+    yellow, blue, magenta and cyan are fixed hues harmonized toward primary
+    (tone 70/80 on dark schemes, 40/30 on light ones).
+
+    Raises:
+        ValueError: If the mode is unknown, or the scheme lacks primary,
+            surface or on_surface.
+    """
+    ...
+
+def export_files(
+    wallpaper_path: str,
+    directory: str | None = None,
+    formats: list[str] | None = None,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+) -> list[ExportResult]:
+    """Write the wallpaper's cached palette as color files.
+
+    Files are named after their format (kitty-colors.conf,
+    alacritty-colors.toml, colors.Xresources, ...) and only rewritten when
+    their contents change. Errors in one format are reported in its result.
+
+    Args:
+        wallpaper_path: Wallpaper whose palette to export.
+        directory: Output directory; defaults to ~/.cache/matuwrap/export.
+        formats: Formats to write; defaults to all of `export_formats()`.
+        scheme_type, mode, contrast, source_index: As for `get_cached_colors`.
+
+    Raises:
+        ValueError: If an option is invalid.
+        RuntimeError: If no palette could be generated for the wallpaper.
+        OSError: If the directory can't be created.
+    """
+    ...
+
+# Templates
+
+class HookResult:
+    """Outcome of one post-render hook."""
+
+    kind: Final[str]
+    """"signal", "command" or "keyword"."""
+    hook: Final[str]
+    """The hook as configured, e.g. "signal SIGUSR1 kitty"."""
+    ok: Final[bool]
+    output: Final[str]
+    """Command stdout, Hyprland's reply, or how many processes were signalled."""
+    error: Final[str | None]
+
+    def __repr__(self) -> str: ...
+
+class TemplateResult:
+    """Outcome of rendering one template."""
+
+    name: Final[str]
+    """Name in templates.json."""
+    output: Final[str]
+    """Resolved output path."""
+    changed: Final[bool]
+    """The output's contents changed (or would, with dry_run)."""
+    error: Final[str | None]
+    """Why reading, rendering or writing failed, if it did."""
+    hooks: Final[list[HookResult]]
+    """Hooks that ran because the output changed (none with dry_run)."""
+
+    def __repr__(self) -> str: ...
+
+def render_template(
+    template: str,
+    palette: Palette,
+    mode: str | None = None,
+    image: str | None = None,
+) -> str:
+    """Fill in the `{{ ... }}` placeholders of a template string.
+
+    Placeholders follow matugen: `{{colors.<role>.<variant>.<format>}}`
+    with variant "default" (the current mode), "dark" or "light", and
+    format "hex" (the default if omitted), "hex_stripped", "rgb", "rgba",
+    "hsl" or "hsla". `{{mode}}` and `{{image}}` give the mode and wallpaper
+    path. Filters chain after `|`: `lighten: N` and `darken: N` (HSL
+    lightness points), `alpha: A` (0 to 1), or any format name.
+
+    Example:
+        `{{colors.surface.default.hex | darken: 10 | alpha: 0.8 | rgba}}`
+
+    Args:
+        template: Template source.
+        palette: Palette to take colors from (see `get_cached_palette`).
+        mode: Mode for the "default" variant; None uses the saved mode.
+        image: Value of `{{image}}`.
+
+    Raises:
+        ValueError: If a placeholder or filter is invalid (the message
+            includes the line number) or `mode` is unknown.
+    """
+    ...
+
+def render_templates(
+    wallpaper_path: str,
+    scheme_type: str | None = None,
+    mode: str | None = None,
+    contrast: float | None = None,
+    source_index: int | None = None,
+    dry_run: bool = False,
+) -> list[TemplateResult]:
+    """Render every template in ~/.config/matuwrap/templates/templates.json.
+
+    The manifest maps names to an input template and an output path
+    (`~/` is expanded; relative paths are resolved against the templates
+    directory):
+
+        {"kitty": {"input": "kitty.conf", "output": "~/.config/kitty/colors.conf"}}
+
+    Colors come from the wallpaper's cached palette (see
+    `get_cached_colors`), so matugen runs at most once. Outputs are
+    written atomically, only when their contents change; symlinked
+    outputs are written through. A failing template doesn't stop the
+    others: its error is reported in its result.
+
+    Each template may list `hooks` to run after its output changes:
+
+        {"type": "signal", "process": "kitty", "signal": "SIGUSR1"}
+        {"type": "command", "command": ["swaync-client", "-rs"], "timeout": 5}
+        {"type": "keyword", "keyword": "general:col.active_border",
+         "value": "rgb({{colors.primary.default.hex_stripped}})"}
+
+    Signals go to the current user's processes with that name (default
+    SIGUSR1). Commands run without a shell; a command is done when it
+    exits, even if something it started in the background is still
+    running. If it outlives `timeout` seconds (default 10), it is killed
+    along with everything it started. Command arguments and keyword values
+    are rendered like templates. Outcomes are reported in `hooks`.
+
+    Args:
+        wallpaper_path: Path to wallpaper image file.
+        scheme_type: Scheme type, as in `get_cached_colors`.
+        mode: "dark" or "light" for the "default" variant; None uses the
+            saved mode.
+        contrast: Contrast level from -1.0 to 1.0.
+        source_index: Which ranked source color to use (0 = best).
+        dry_run: Render and compare, but don't write.
+
+    Returns:
+        One result per template, in name order. Empty if there is no
+        manifest.
+
+    Raises:
+        ValueError: If the options or the manifest are invalid.
+        RuntimeError: If no palette can be generated for the wallpaper.
+    """
+    ...
+
+# Terminal colors
+
+class TerminalColor:
+    """A color with the nearest xterm-256 and base-16 colors (by OKLab
+    distance)."""
+
+    hex: Final[str]
+    ansi256: Final[int]
+    """xterm-256 index from 16 to 255 (the cube and grays)."""
+    ansi16: Final[int]
+    """Base color index from 0 to 15, as xterm shows them."""
+
+    def escape(self, depth: str | None = None) -> str:
+        """Foreground escape sequence at `depth` ("truecolor", "256", "16"
+        or "none"; None detects it). Empty for "none"."""
+        ...
+    def __repr__(self) -> str: ...
+
+def detect_color_depth() -> str:
+    """Color depth of the current terminal.
+
+    "none" if `NO_COLOR` is set or `TERM=dumb`; "truecolor" if `COLORTERM`
+    says so or `TERM` is a known 24-bit terminal; "256" for
+    `TERM=*256color` (e.g. tmux); "16" otherwise (e.g. the Linux console).
+    """
+    ...
+
+def ansi_escape(color: str, depth: str | None = None) -> str:
+    """Foreground escape sequence for a hex color, quantized to `depth`.
+
+    Args:
+        color: "#rrggbb" or "#rgb".
+        depth: "truecolor", "256", "16" or "none"; None uses
+            `detect_color_depth()`.
+
+    Returns:
+        e.g. "\\x1b[38;5;217m", or "" for "none".
+
+    Raises:
+        ValueError: If the color or depth is invalid.
+    """
+    ...
+
+def quantize_palette(palette: Palette, mode: str | None = None) -> dict[str, TerminalColor]:
+    """Map every role of a palette's scheme to the nearest terminal colors.
+
+    Args:
+        palette: Palette to quantize.
+        mode: "dark" or "light"; None uses the saved mode.
+
+    Raises:
+        ValueError: If mode is invalid.
+    """
+    ...
+
+# Shell prompts
+
+def render_prompt(
+    palette: Palette | None,
+    shell: str = "bash",
+    format: str | None = None,
+    mode: str | None = None,
+    depth: str | None = None,
+) -> str:
+    """Render a shell prompt colored from a palette.
+
+    The format mixes literal text with segments the shell expands each
+    time it draws the prompt, and Rich-style color tags:
+
+        [primary]{user}[/]@[primary]{host}[/]:[tertiary]{cwd}[/]{git| (%s)}
+
+    Segments are `{user}`, `{host}`, `{cwd}` and `{git}` (current branch);
+    `{name|template}` puts the value at `%s` and drops the segment when
+    it's empty. Tags are palette roles, `[#rrggbb]` or `[bold]`, closed by
+    `[/]`. `{{`, `}}` and `[[` are literal.
+
+    Output is what goes into the shell's config: a `PS1` string (bash), a
+    `PROMPT` string (zsh, with `setopt prompt_subst`), a `fish_prompt`
+    function (fish) or a `$env.PROMPT_COMMAND` closure (nu).
+
+    Args:
+        palette: Palette to take colors from; None renders without colors.
+        shell: "bash", "zsh", "fish" or "nu".
+        format: Prompt format; None is the `user@host:cwd` default above,
+            without git.
+        mode: "dark" or "light"; None uses the saved mode.
+        depth: "truecolor", "256", "16" or "none"; None detects it like
+            `detect_color_depth`.
+
+    Raises:
+        ValueError: If the format, shell, mode or depth is invalid.
+    """
+    ...
+
+# Wallpaper watching
+
+class WallpaperChange:
+    """What the watcher did for one wallpaper change."""
+
+    wallpaper: Final[str]
+    """The image the wallpaper link now points to."""
+    palette: Final[Palette | None]
+    """The regenerated palette, None if generation failed."""
+    templates: Final[list[TemplateResult]]
+    """Templates rendered with the new palette (empty if disabled)."""
+    shell_cache: Final[list[str]]
+    """Shell cache files that were rewritten."""
+    error: Final[str | None]
+    """Why generating colors, rendering or writing failed, if it did."""
+
+    def __repr__(self) -> str: ...
+
+class WallpaperWatcher:
+    """Regenerate colors whenever the wallpaper changes.
+
+    Watches the wallpaper symlink and its target with inotify, so both
+    re-pointing the link and rewriting the image are seen. After `debounce`
+    seconds without events, a change whose target path or file stamp
+    differs from the last one regenerates and caches the palette, renders
+    templates (see `render_templates`, using the saved mode) and rewrites
+    `~/.cache/matuwrap/ps1` and `color`, the files the bash integration
+    loads. Yields a `WallpaperChange` per change; errors are reported in
+    `WallpaperChange.error` rather than raised.
+
+    Example:
+        with WallpaperWatcher() as watcher:
+            watcher.run(lambda change: print(change.wallpaper))
+    """
+
+    def __init__(
+        self,
+        wallpaper_path: str | PathLike[str] | None = None,
+        debounce: float = 0.3,
+        scheme_type: str | None = None,
+        contrast: float | None = None,
+        source_index: int | None = None,
+        render: bool = True,
+        shell_cache: bool = True,
+        apply_on_start: bool = True,
+    ) -> None:
+        """Start watching.
+
+        Args:
+            wallpaper_path: Wallpaper symlink (default ~/.current.wall).
+            debounce: Seconds without events before checking the wallpaper.
+            scheme_type: Scheme type, as in `get_cached_colors`.
+            contrast: Contrast level from -1.0 to 1.0.
+            source_index: Which ranked source color to use (0 = best).
+                Options left as None are read from the saved ones on each
+                change, like the mode.
+            render: Render templates after each change.
+            shell_cache: Rewrite the bash integration's cache files.
+            apply_on_start: Apply the current wallpaper before the first
+                event, unless it was already seen.
+
+        Raises:
+            ValueError: If `debounce` or the scheme options are invalid.
+            OSError: If inotify is unavailable or the link's directory
+                cannot be watched.
+        """
+        ...
+
+    def __iter__(self) -> WallpaperWatcher: ...
+    def __next__(self) -> WallpaperChange: ...
+    def __enter__(self) -> WallpaperWatcher: ...
+    def __exit__(self, *args: object) -> None: ...
+    def run(self, callback: Callable[[WallpaperChange], object]) -> None:
+        """Call `callback` with each change until closed (or the callback raises).
+
+        Raises:
+            RuntimeError: If another thread is already waiting on this
+                watcher.
+        """
+        ...
+
+    def close(self) -> None:
+        """Stop watching. Further iteration stops immediately.
+
+        Safe to call from the `run` callback or another thread: a blocked
+        `run` or `next()` returns within a fraction of a second.
+        """
+        ...
+
+# PipeWire audio
+
+class AudioSink:
+    """A PipeWire audio output node ("Audio/Sink" or "Audio/Duplex")."""
+
+    id: Final[int]
+    """Node ID, as `set_default_sink` and wpctl take it."""
+
+    name: Final[str]
+    """`node.name`, e.g. "alsa_output.pci-0000_01_00.1.hdmi-stereo"."""
+
+    description: Final[str]
+    """`node.description` (or `node.nick`, or the name), e.g. "Built-in Audio
+    Analog Stereo"."""
+
+    media_class: Final[str]
+    """`media.class`, e.g. "Audio/Sink"."""
+
+    volume: Final[float | None]
+    """Average channel volume on the cubic scale wpctl shows (1.0 is 100%),
+    or None if the node has no volume."""
+
+    channel_volumes: Final[list[float]]
+    """Per-channel volumes on the same scale."""
+
+    channels: Final[list[str]]
+    """Channel positions matching `channel_volumes`, e.g. ["FL", "FR"]."""
+
+    muted: Final[bool]
+
+    is_default: Final[bool]
+    """Whether this is the default sink."""
+
+    def __repr__(self) -> str: ...
+
+def get_audio_sinks() -> list[AudioSink]:
+    """Get audio sinks from PipeWire via pw-dump.
+
+    The default sink is the one in use (`default.audio.sink` metadata), or
+    else the one configured.
+
+    Returns:
+        List of AudioSink objects, in graph order.
+
+    Raises:
+        OSError: If pw-dump fails to execute.
+        RuntimeError: If pw-dump returns an error or invalid JSON.
+    """
+    ...
+
+def set_default_sink(sink_id: int) -> bool:
+    """Set the default audio sink by ID.
+
+    Args:
+        sink_id: PipeWire sink ID.
+
+    Returns:
+        True if successful, False otherwise.
+
+    Raises:
+        OSError: If wpctl fails to execute.
+    """
+    ...
+
+# System info
+
+def memory_info() -> tuple[int, int, int]:
+    """Get system memory info.
+
+    Returns:
+        Tuple of (total_bytes, used_bytes, available_bytes).
+    """
+    ...
+
+def cpu_usage() -> float:
+    """Get CPU usage as percentage.
+
+    Note: This function blocks for ~100ms to get accurate reading.
+
+    Returns:
+        CPU usage percentage (0.0 - 100.0).
+    """
+    ...
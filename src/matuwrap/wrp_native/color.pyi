@@ -0,0 +1,152 @@
+"""Type stubs for the wrp_native.color submodule.
+
+Colors are "#rrggbb" strings (the `#` is optional, "#rgb" is accepted).
+Functions named `to_*` return components; `from_*` return "#rrggbb",
+clamping anything outside sRGB.
+"""
+
+def parse_hex(color: str) -> tuple[int, int, int]:
+    """Parse a hex color into (r, g, b), each 0-255.
+
+    Raises:
+        ValueError: If the color is not "#rrggbb" or "#rgb".
+    """
+    ...
+
+def to_hex(r: int, g: int, b: int) -> str:
+    """Format (r, g, b), each 0-255, as "#rrggbb"."""
+    ...
+
+def to_hsv(color: str) -> tuple[float, float, float]:
+    """Hue in degrees (0-360), saturation and value (0.0-1.0).
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_hsv(h: float, s: float, v: float) -> str:
+    """Inverse of `to_hsv`."""
+    ...
+
+def to_hsl(color: str) -> tuple[float, float, float]:
+    """Hue in degrees (0-360), saturation and lightness (0.0-1.0).
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_hsl(h: float, s: float, l: float) -> str:
+    """Inverse of `to_hsl`."""
+    ...
+
+def to_xyz(color: str) -> tuple[float, float, float]:
+    """CIE XYZ (D65 white point), Y from 0.0 to 1.0.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_xyz(x: float, y: float, z: float) -> str:
+    """Inverse of `to_xyz`."""
+    ...
+
+def to_xy(color: str) -> tuple[float, float]:
+    """CIE xy chromaticity, as used by Philips Hue. Black gives the white point.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_xy(x: float, y: float, brightness: float = 1.0) -> str:
+    """The color at chromaticity (x, y) with luminance `brightness` (0.0-1.0).
+
+    Colors outside sRGB are scaled down rather than clipped, keeping their hue.
+    """
+    ...
+
+def to_lab(color: str) -> tuple[float, float, float]:
+    """CIE L*a*b* (D65), L from 0 to 100.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_lab(l: float, a: float, b: float) -> str:
+    """Inverse of `to_lab`."""
+    ...
+
+def to_oklch(color: str) -> tuple[float, float, float]:
+    """OKLCH: lightness (0.0-1.0), chroma (about 0.0-0.4), hue in degrees.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_oklch(l: float, c: float, h: float) -> str:
+    """Inverse of `to_oklch`."""
+    ...
+
+def to_hct(color: str) -> tuple[float, float, float]:
+    """Material HCT: hue in degrees, chroma, tone (0-100).
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def from_hct(hue: float, chroma: float, tone: float) -> str:
+    """Inverse of `to_hct`. Chroma is reduced if the color doesn't fit in sRGB."""
+    ...
+
+def from_kelvin(kelvin: float) -> str:
+    """Approximate color of a black body, clamped to 1000-40000 K.
+
+    Hue's `ct` is in mireds: `from_kelvin(1_000_000 / ct)`.
+    """
+    ...
+
+def relative_luminance(color: str) -> float:
+    """WCAG 2 relative luminance, 0.0 (black) to 1.0 (white).
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def contrast_ratio(foreground: str, background: str) -> float:
+    """WCAG 2 contrast ratio, 1.0 to 21.0 (the order doesn't matter).
+
+    Raises:
+        ValueError: If either color is not valid hex.
+    """
+    ...
+
+def with_tone(color: str, tone: float) -> str:
+    """The same HCT hue and chroma at another tone (0-100).
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def lighten(color: str, amount: float) -> str:
+    """Raise the HCT tone by `amount`, keeping hue and chroma.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
+
+def darken(color: str, amount: float) -> str:
+    """Lower the HCT tone by `amount`, keeping hue and chroma.
+
+    Raises:
+        ValueError: If the color is not valid hex.
+    """
+    ...
@@ -0,0 +1,166 @@
+"""Shared helpers for tests of the native module."""
+
+import json
+import os
+import socket
+import struct
+import tempfile
+import threading
+import time
+import zlib
+from contextlib import contextmanager
+from pathlib import Path
+from unittest import mock
+
+
+def write_png(path: Path, *colors: tuple[int, int, int], size: int = 8) -> Path:
+    """Write a PNG of vertical stripes in `colors` (one solid color by default)."""
+    colors = colors or ((0x40, 0x60, 0xA0),)
+    row = b"".join(bytes(colors[x * len(colors) // size]) for x in range(size))
+    raw = b"".join(b"\x00" + row for _ in range(size))
+
+    def chunk(kind: bytes, data: bytes) -> bytes:
+        body = kind + data
+        return struct.pack(">I", len(data)) + body + struct.pack(">I", zlib.crc32(body))
+
+    path.write_bytes(
+        b"\x89PNG\r\n\x1a\n"
+        + chunk(b"IHDR", struct.pack(">IIBBBBB", size, size, 8, 2, 0, 0, 0))
+        + chunk(b"IDAT", zlib.compress(raw))
+        + chunk(b"IEND", b"")
+    )
+    return path
+
+
+@contextmanager
+def isolated_home():
+    """Point HOME and the XDG config/cache dirs at a fresh temporary directory.
+
+    Yields the directory. matugen is hidden from PATH so colors always come
+    from native extraction.
+    """
+    with tempfile.TemporaryDirectory() as home:
+        env = {
+            "HOME": home,
+            "XDG_CONFIG_HOME": os.path.join(home, ".config"),
+            "XDG_CACHE_HOME": os.path.join(home, ".cache"),
+            "PATH": "/usr/bin:/bin",
+        }
+        with mock.patch.dict(os.environ, env):
+            yield Path(home)
+
+
+def monitor(name: str, **fields) -> dict:
+    """A `j/monitors` entry for a 1920x1080@60 output named `name`."""
+    entry = {
+        "name": name,
+        "width": 1920,
+        "height": 1080,
+        "refreshRate": 60.0,
+        "scale": 1.0,
+        "dpmsStatus": True,
+        "availableModes": ["1920x1080@60.00Hz", "1280x720@59.94Hz"],
+    }
+    entry.update(fields)
+    return entry
+
+
+def monitors_reply(monitors: list[dict], reject: str | None = None):
+    """A FakeHyprland `reply` that reports `monitors` for `j/monitors all`
+    and answers "ok" to everything else, batches included, except
+    commands containing `reject`.
+    """
+
+    def answer(command: str) -> str:
+        if command == "j/monitors all":
+            return json.dumps(monitors)
+        return f"invalid: {command}" if reject and reject in command else "ok"
+
+    def reply(request: str) -> str:
+        if request.startswith("[[BATCH]]"):
+            return "\n\n\n".join(answer(c) for c in request.removeprefix("[[BATCH]]").split(";"))
+        return answer(request)
+
+    return reply
+
+
+class FakeHyprland:
+    """A Hyprland instance served from threads.
+
+    `.socket.sock` answers each request with `reply(request)` and records
+    it in `requests`. With `events`, `.socket2.sock` sends each client
+    those chunks (bytes, or a float to pause for that many seconds) and
+    then closes. HYPRLAND_INSTANCE_SIGNATURE and XDG_RUNTIME_DIR point at
+    the instance while the context is active.
+    """
+
+    signature = "fake_1700000000_test"
+
+    def __init__(self, reply=lambda request: "ok", events=None):
+        self.reply = reply
+        self.events = events
+        self.requests: list[str] = []
+
+    def __enter__(self):
+        self.runtime = tempfile.TemporaryDirectory()
+        self.dir = Path(self.runtime.name) / "hypr" / self.signature
+        self.dir.mkdir(parents=True)
+        self.stopped = threading.Event()
+        self.servers = []
+        self.threads = []
+        self._listen(".socket.sock", self._answer)
+        if self.events is not None:
+            self._listen(".socket2.sock", self._broadcast)
+        env = {"HYPRLAND_INSTANCE_SIGNATURE": self.signature, "XDG_RUNTIME_DIR": self.runtime.name}
+        self.env = mock.patch.dict(os.environ, env)
+        self.env.start()
+        return self
+
+    def __exit__(self, *exc):
+        self.env.stop()
+        self.stopped.set()
+        for thread in self.threads:
+            thread.join()
+        for server in self.servers:
+            server.close()
+        self.runtime.cleanup()
+
+    def _listen(self, name, handle):
+        server = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        server.bind(str(self.dir / name))
+        server.listen()
+        # accept() polls so the thread notices `stopped`
+        server.settimeout(0.05)
+        thread = threading.Thread(target=self._serve, args=(server, handle), daemon=True)
+        thread.start()
+        self.servers.append(server)
+        self.threads.append(thread)
+
+    def _serve(self, server, handle):
+        while not self.stopped.is_set():
+            try:
+                conn, _ = server.accept()
+            except TimeoutError:
+                continue
+            with conn:
+                conn.settimeout(None)
+                handle(conn)
+
+    def _answer(self, conn):
+        request = conn.recv(65536).decode()
+        if not request:
+            # A liveness probe: connects and closes without a request
+            return
+        self.requests.append(request)
+        try:
+            conn.sendall(self.reply(request).encode())
+        except (BrokenPipeError, ConnectionResetError):
+            # The client gave up waiting (read timeout tests)
+            pass
+
+    def _broadcast(self, conn):
+        for chunk in self.events:
+            if isinstance(chunk, float):
+                time.sleep(chunk)
+            else:
+                conn.sendall(chunk)
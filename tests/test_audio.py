@@ -0,0 +1,95 @@
+"""Tests for wrp_native.get_audio_sinks against recorded pw-dump output."""
+
+import os
+import shutil
+import stat
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+from matuwrap.wrp_native import get_audio_sinks
+
+FIXTURES = Path(__file__).parent / "fixtures"
+
+
+class FakePwDump:
+    """Put a `pw-dump` printing `output` (exiting with `status`) first on PATH."""
+
+    def __init__(self, output: str, status: int = 0):
+        self.script = f"#!/bin/sh\ncat <<'EOF'\n{output}\nEOF\nexit {status}\n"
+
+    def __enter__(self):
+        self.dir = tempfile.mkdtemp()
+        path = Path(self.dir) / "pw-dump"
+        path.write_text(self.script)
+        path.chmod(path.stat().st_mode | stat.S_IEXEC)
+        self.env = mock.patch.dict(os.environ, {"PATH": f"{self.dir}:{os.environ['PATH']}"})
+        self.env.start()
+
+    def __exit__(self, *exc):
+        self.env.stop()
+        shutil.rmtree(self.dir)
+
+
+def sinks_from_fixture():
+    with FakePwDump((FIXTURES / "pw-dump.json").read_text()):
+        return get_audio_sinks()
+
+
+class TestSinks(unittest.TestCase):
+    """Tests for sinks parsed from pw-dump JSON."""
+
+    def test_only_sinks(self):
+        """Sources, streams and devices are left out, graph order is kept."""
+        self.assertEqual([s.id for s in sinks_from_fixture()], [34, 50, 80])
+
+    def test_properties(self):
+        """Names with '.' and '*' come through as they are."""
+        headset = sinks_from_fixture()[0]
+        self.assertEqual(headset.name, "alsa_output.usb-HP_HyperX_Cloud_Alpha_S-00.analog-stereo")
+        self.assertEqual(headset.description, "HyperX Cloud Alpha S *Gaming* v1.2")
+        self.assertEqual(headset.media_class, "Audio/Sink")
+        self.assertEqual(headset.channels, ["FL", "FR"])
+
+    def test_default_prefers_current_over_configured(self):
+        self.assertEqual([s.is_default for s in sinks_from_fixture()], [True, False, False])
+
+    def test_volumes_on_cubic_scale(self):
+        headset, hdmi, eq = sinks_from_fixture()
+        self.assertAlmostEqual(headset.volume, 0.6)
+        self.assertEqual([round(v, 2) for v in hdmi.channel_volumes], [1.0, 0.9])
+        self.assertAlmostEqual(hdmi.volume, 0.95)
+        self.assertTrue(hdmi.muted)
+        self.assertIsNone(eq.volume)
+        self.assertEqual(eq.channel_volumes, [])
+
+    def test_description_falls_back_to_nick(self):
+        self.assertEqual(sinks_from_fixture()[2].description, "Equalizer")
+
+    def test_string_metadata_value(self):
+        """Older pw-dump prints metadata values as JSON strings."""
+        dump = """[
+          {"id": 31, "type": "PipeWire:Interface:Metadata", "props": {"metadata.name": "default"},
+           "metadata": [{"subject": 0, "key": "default.audio.sink", "value": "{\\"name\\":\\"b\\"}"}]},
+          {"id": 1, "type": "PipeWire:Interface:Node", "info": {"props": {"node.name": "a", "media.class": "Audio/Sink"}}},
+          {"id": 2, "type": "PipeWire:Interface:Node", "info": {"props": {"node.name": "b", "media.class": "Audio/Sink"}}}
+        ]"""
+        with FakePwDump(dump):
+            self.assertEqual([s.is_default for s in get_audio_sinks()], [False, True])
+
+
+class TestErrors(unittest.TestCase):
+    """Tests for pw-dump failures."""
+
+    def test_failure(self):
+        with FakePwDump("", status=1), self.assertRaises(RuntimeError):
+            get_audio_sinks()
+
+    def test_invalid_json(self):
+        with FakePwDump("Sinks:"), self.assertRaises(RuntimeError):
+            get_audio_sinks()
+
+
+if __name__ == "__main__":
+    unittest.main()
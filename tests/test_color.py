@@ -0,0 +1,167 @@
+"""Tests for the wrp_native.color submodule."""
+
+import unittest
+
+from matuwrap.wrp_native import color
+
+
+SAMPLES = ["#000000", "#ffffff", "#3366cc", "#ff0000", "#7f7f7f", "#d0bcff", "#1b5e20"]
+
+
+class TestHex(unittest.TestCase):
+    """Tests for hex parsing and formatting."""
+
+    def test_parse_long_and_short(self):
+        """Both #rrggbb and #rgb should parse, with or without '#'."""
+        self.assertEqual(color.parse_hex("#3366cc"), (0x33, 0x66, 0xCC))
+        self.assertEqual(color.parse_hex("3366CC"), (0x33, 0x66, 0xCC))
+        self.assertEqual(color.parse_hex("#abc"), (0xAA, 0xBB, 0xCC))
+
+    def test_parse_invalid(self):
+        """Malformed colors should raise ValueError."""
+        for bad in ["", "#12345", "#gggggg", "#3366cc80", "#éé"]:
+            with self.assertRaises(ValueError):
+                color.parse_hex(bad)
+
+    def test_to_hex(self):
+        """to_hex should format lowercase #rrggbb."""
+        self.assertEqual(color.to_hex(0x33, 0x66, 0xCC), "#3366cc")
+        self.assertEqual(color.to_hex(0, 0, 0), "#000000")
+
+
+class TestRoundTrips(unittest.TestCase):
+    """Every to_* conversion should come back through its from_*."""
+
+    def _round_trip(self, name):
+        to, back = getattr(color, f"to_{name}"), getattr(color, f"from_{name}")
+        for sample in SAMPLES:
+            with self.subTest(space=name, color=sample):
+                self.assertEqual(back(*to(sample)), sample)
+
+    def test_hsv(self):
+        self._round_trip("hsv")
+
+    def test_hsl(self):
+        self._round_trip("hsl")
+
+    def test_xyz(self):
+        self._round_trip("xyz")
+
+    def test_lab(self):
+        self._round_trip("lab")
+
+    def test_oklch(self):
+        self._round_trip("oklch")
+
+    def test_hct(self):
+        self._round_trip("hct")
+
+
+class TestConversions(unittest.TestCase):
+    """Spot checks against reference values."""
+
+    def test_hsv(self):
+        """Pure red is hue 0, fully saturated."""
+        self.assertEqual(color.to_hsv("#ff0000"), (0.0, 1.0, 1.0))
+        h, s, v = color.to_hsv("#3366cc")
+        self.assertAlmostEqual(h, 220.0)
+        self.assertAlmostEqual(s, 0.75)
+        self.assertAlmostEqual(v, 0.8)
+
+    def test_hsl(self):
+        h, s, l = color.to_hsl("#3366cc")
+        self.assertAlmostEqual(h, 220.0)
+        self.assertAlmostEqual(s, 0.6)
+        self.assertAlmostEqual(l, 0.5)
+
+    def test_lab_white(self):
+        """White is L=100 with no a/b."""
+        l, a, b = color.to_lab("#ffffff")
+        self.assertAlmostEqual(l, 100.0, places=2)
+        self.assertAlmostEqual(a, 0.0, places=2)
+        self.assertAlmostEqual(b, 0.0, places=2)
+
+    def test_oklch_red(self):
+        l, c, h = color.to_oklch("#ff0000")
+        self.assertAlmostEqual(l, 0.628, places=3)
+        self.assertAlmostEqual(c, 0.2577, places=3)
+        self.assertAlmostEqual(h, 29.23, places=1)
+
+    def test_xy_white_point(self):
+        """White sits at the D65 white point, and black reports it too."""
+        for sample in ("#ffffff", "#000000"):
+            x, y = color.to_xy(sample)
+            self.assertAlmostEqual(x, 0.3127, places=3)
+            self.assertAlmostEqual(y, 0.3290, places=3)
+
+    def test_from_xy(self):
+        self.assertEqual(color.from_xy(0.3127, 0.3290), "#ffffff")
+        self.assertEqual(color.from_xy(0.3127, 0.3290, 0.0), "#000000")
+        x, y = color.to_xy("#3366cc")
+        # Same chromaticity at full brightness: scaled up, same hue
+        h, _, _ = color.to_hsv(color.from_xy(x, y))
+        self.assertAlmostEqual(h, 220.0, delta=1.0)
+
+    def test_from_xy_out_of_gamut_keeps_hue(self):
+        """A saturated red beyond sRGB should stay red, not turn orange/pink."""
+        self.assertEqual(color.from_xy(0.7, 0.29), "#ff0000")
+
+    def test_from_kelvin(self):
+        """Warm light leans orange, daylight is near white."""
+        r, g, b = color.parse_hex(color.from_kelvin(2700))
+        self.assertEqual(r, 255)
+        self.assertLess(b, g)
+        r, g, b = color.parse_hex(color.from_kelvin(6500))
+        self.assertTrue(all(c > 240 for c in (r, g, b)))
+
+
+class TestContrast(unittest.TestCase):
+    """Tests for WCAG luminance and contrast."""
+
+    def test_luminance_extremes(self):
+        self.assertEqual(color.relative_luminance("#000000"), 0.0)
+        self.assertAlmostEqual(color.relative_luminance("#ffffff"), 1.0)
+
+    def test_black_on_white(self):
+        self.assertAlmostEqual(color.contrast_ratio("#000000", "#ffffff"), 21.0)
+
+    def test_symmetric(self):
+        self.assertEqual(
+            color.contrast_ratio("#3366cc", "#ffffff"),
+            color.contrast_ratio("#ffffff", "#3366cc"),
+        )
+
+    def test_same_color(self):
+        self.assertAlmostEqual(color.contrast_ratio("#3366cc", "#3366cc"), 1.0)
+
+    def test_reference_value(self):
+        """#777777 on white is the classic just-under-AA gray (4.48:1)."""
+        self.assertAlmostEqual(color.contrast_ratio("#777777", "#ffffff"), 4.48, places=2)
+
+
+class TestTone(unittest.TestCase):
+    """Tests for HCT tone adjustments."""
+
+    def test_with_tone(self):
+        _, _, tone = color.to_hct(color.with_tone("#3366cc", 80))
+        self.assertAlmostEqual(tone, 80.0, delta=0.5)
+
+    def test_lighten_darken(self):
+        _, _, base = color.to_hct("#3366cc")
+        _, _, lighter = color.to_hct(color.lighten("#3366cc", 20))
+        _, _, darker = color.to_hct(color.darken("#3366cc", 20))
+        self.assertAlmostEqual(lighter, base + 20, delta=0.5)
+        self.assertAlmostEqual(darker, base - 20, delta=0.5)
+
+    def test_keeps_hue(self):
+        hue, _, _ = color.to_hct("#3366cc")
+        lighter_hue, _, _ = color.to_hct(color.lighten("#3366cc", 20))
+        self.assertAlmostEqual(lighter_hue, hue, delta=2.0)
+
+    def test_clamps(self):
+        self.assertEqual(color.lighten("#3366cc", 200), "#ffffff")
+        self.assertEqual(color.darken("#3366cc", 200), "#000000")
+
+
+if __name__ == "__main__":
+    unittest.main()
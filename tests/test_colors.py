@@ -0,0 +1,256 @@
+"""Tests for the color cache and saved color settings."""
+
+import json
+import os
+import shutil
+import subprocess
+import sys
+import unittest
+import warnings
+
+from matuwrap.wrp_native import (
+    CacheStatus,
+    ColorCacheCorruptWarning,
+    cache_lookup,
+    cache_stats,
+    get_cached_colors,
+    get_cached_palette,
+    get_scheme_options,
+    prune_cache,
+    set_scheme_options,
+)
+
+from matuwrap.core.colors import Colors, get_colors
+from tests.support import isolated_home, write_png
+
+DEFAULT_OPTIONS = {"scheme_type": "scheme-tonal-spot", "contrast": 0.0, "source_index": 0}
+
+
+class TestSchemeOptions(unittest.TestCase):
+    """Tests for saved scheme options."""
+
+    def test_defaults(self):
+        with isolated_home():
+            self.assertEqual(get_scheme_options(), DEFAULT_OPTIONS)
+
+    def test_partial_update_keeps_the_rest(self):
+        with isolated_home():
+            set_scheme_options("scheme-vibrant", 0.5)
+            set_scheme_options(source_index=1)
+            self.assertEqual(
+                get_scheme_options(),
+                {"scheme_type": "scheme-vibrant", "contrast": 0.5, "source_index": 1},
+            )
+
+    def test_invalid_options_are_not_saved(self):
+        with isolated_home():
+            for kwargs in [{"scheme_type": "scheme-nope"}, {"contrast": 2.0}]:
+                with self.subTest(**kwargs), self.assertRaises(ValueError):
+                    set_scheme_options(**kwargs)
+            self.assertEqual(get_scheme_options(), DEFAULT_OPTIONS)
+
+    def test_unreadable_file_uses_defaults(self):
+        with isolated_home() as home:
+            path = home / ".config" / "matuwrap" / "scheme.json"
+            path.parent.mkdir(parents=True)
+            path.write_text("{not json")
+            self.assertEqual(get_scheme_options(), DEFAULT_OPTIONS)
+
+    def test_saved_options_are_the_default(self):
+        with isolated_home() as home:
+            wall = str(write_png(home / "wall.png", (0xC0, 0x30, 0x30), (0x30, 0x90, 0x40)))
+            standard = get_cached_palette(wall)
+            set_scheme_options("scheme-monochrome", 0.5)
+            saved = get_cached_palette(wall)
+            self.assertEqual(saved.dark.to_dict(), get_cached_palette(wall, "scheme-monochrome", 0.5).dark.to_dict())
+            self.assertNotEqual(saved.dark.to_dict(), standard.dark.to_dict())
+            # Explicit arguments still win
+            self.assertEqual(
+                get_cached_colors(wall, "scheme-tonal-spot", "dark", 0.0),
+                standard.dark.to_dict(),
+            )
+
+
+class CacheTest(unittest.TestCase):
+    """Runs each test with an empty home holding a red and a green wallpaper."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        self.red = str(write_png(self.home / "red.png", (0xC0, 0x30, 0x30)))
+        self.green = str(write_png(self.home / "green.png", (0x30, 0x90, 0x40)))
+
+
+class TestContentHashCache(CacheTest):
+    """Tests for the multi-entry cache keyed by image content."""
+
+    def test_entries_per_wallpaper(self):
+        red = get_cached_colors(self.red)
+        green = get_cached_colors(self.green)
+        self.assertNotEqual(red, green)
+        # Switching back is a hit, not a regeneration of the other entry
+        self.assertEqual(get_cached_colors(self.red), red)
+        stats = cache_stats()
+        self.assertEqual(stats.entries, 2)
+        self.assertEqual(stats.wallpapers, [self.red, self.green])
+
+    def test_same_image_at_another_path(self):
+        get_cached_colors(self.red)
+        copy = shutil.copy(self.red, self.home / "copy.png")
+        get_cached_colors(str(copy))
+        stats = cache_stats()
+        self.assertEqual(stats.entries, 1)
+        self.assertEqual(stats.wallpapers, [str(copy)])
+
+    def test_palettes_per_options(self):
+        get_cached_colors(self.red)
+        get_cached_colors(self.red, "scheme-vibrant")
+        get_cached_colors(self.red, mode="light")
+        stats = cache_stats()
+        self.assertEqual((stats.entries, stats.palettes), (1, 2))
+
+    def test_wrapper_passes_options_through(self):
+        wall = write_png(self.home / "stripes.png", (0xC0, 0x30, 0x30), (0x30, 0x90, 0x40))
+        for index in (0, 1):
+            with self.subTest(source_index=index):
+                expected = Colors.from_dict(get_cached_colors(str(wall), source_index=index))
+                self.assertEqual(get_colors(wall, source_index=index), expected)
+        self.assertNotEqual(get_colors(wall, source_index=0), get_colors(wall, source_index=1))
+
+    def test_least_recently_used_is_dropped(self):
+        limit = cache_stats().max_entries
+        walls = [str(write_png(self.home / f"{i}.png", (i, 0x40, 0x80))) for i in range(limit + 1)]
+        for wall in walls:
+            get_cached_colors(wall)
+        stats = cache_stats()
+        self.assertEqual(stats.entries, limit)
+        self.assertEqual(stats.wallpapers, walls[:0:-1])
+
+    def test_prune(self):
+        get_cached_colors(self.red)
+        get_cached_colors(self.green)
+        self.assertEqual(prune_cache(5), 0)
+        self.assertEqual(prune_cache(1), 1)
+        self.assertEqual(cache_stats().wallpapers, [self.green])
+
+    def test_empty_stats(self):
+        stats = cache_stats()
+        self.assertEqual((stats.entries, stats.size_bytes, stats.corrupt), (0, 0, None))
+        self.assertEqual(stats.path, str(self.home / ".cache" / "matuwrap" / "colors.json"))
+        self.assertEqual(prune_cache(0), 0)
+
+
+class TestCacheLookup(CacheTest):
+    """Tests for cache_lookup."""
+
+    def assertLookup(self, path, status, reason, **kwargs):
+        lookup = cache_lookup(path, **kwargs)
+        self.assertEqual(lookup.status, status)
+        self.assertTrue(lookup.reason.startswith(reason), lookup.reason)
+        return lookup
+
+    def test_miss_then_hit(self):
+        miss = self.assertLookup(self.red, CacheStatus.Miss, "Nothing cached for this image")
+        self.assertEqual(cache_stats().entries, 0)
+        get_cached_colors(self.red)
+        hit = self.assertLookup(self.red, CacheStatus.Hit, "Cached for this image")
+        self.assertEqual(hit.hash, miss.hash)
+        self.assertLookup(self.red, CacheStatus.Miss, "Nothing cached for these scheme options", contrast=0.5)
+
+    def test_changed_file_is_stale(self):
+        get_cached_colors(self.red)
+        shutil.copy(self.green, self.red)
+        lookup = self.assertLookup(self.red, CacheStatus.Stale, "File changed since it was cached")
+        self.assertIn(f"now {lookup.hash}", lookup.reason)
+        get_cached_colors(self.red)
+        self.assertLookup(self.red, CacheStatus.Hit, "Cached for this image")
+
+    def test_changed_to_cached_content(self):
+        get_cached_colors(self.red)
+        get_cached_colors(self.green)
+        shutil.copy(self.green, self.red)
+        self.assertLookup(self.red, CacheStatus.Hit, "File changed, but its new content is cached")
+
+    def test_relinked_symlink(self):
+        link = self.home / ".current.wall"
+        link.symlink_to(self.red)
+        get_cached_colors(str(link))
+        link.unlink()
+        link.symlink_to(self.green)
+        self.assertLookup(str(link), CacheStatus.Stale, "File changed since it was cached")
+
+    def test_touched_file_is_still_a_hit(self):
+        get_cached_colors(self.red)
+        os.utime(self.red, ns=(1, 1))
+        self.assertLookup(self.red, CacheStatus.Hit, "Cached for this image")
+
+    def test_verify(self):
+        """A change that keeps size and mtime is only caught with `verify`."""
+        get_cached_colors(self.red)
+        stat = os.stat(self.red)
+        with open(self.red, "r+b") as f:
+            f.seek(-1, os.SEEK_END)
+            f.write(b"\xff")
+        os.utime(self.red, ns=(stat.st_atime_ns, stat.st_mtime_ns))
+        self.assertLookup(self.red, CacheStatus.Hit, "Cached for this image")
+        self.assertLookup(self.red, CacheStatus.Stale, "File changed since it was cached", verify=True)
+
+    def test_missing_file(self):
+        with self.assertRaises(OSError):
+            cache_lookup(str(self.home / "missing.png"))
+
+
+class TestCacheWrites(CacheTest):
+    """Tests for corrupt cache files and concurrent writers."""
+
+    def setUp(self):
+        super().setUp()
+        self.cache = self.home / ".cache" / "matuwrap" / "colors.json"
+
+    def corrupt(self):
+        self.cache.parent.mkdir(parents=True, exist_ok=True)
+        self.cache.write_text('{"entries": [')
+
+    def test_corrupt_cache_is_moved_aside(self):
+        self.corrupt()
+        with self.assertWarnsRegex(ColorCacheCorruptWarning, "Discarded corrupt color cache"):
+            colors = get_cached_colors(self.red)
+        self.assertEqual(colors, get_cached_colors(self.red))
+        self.assertEqual(self.cache.with_suffix(".json.corrupt").read_text(), '{"entries": [')
+        self.assertEqual(cache_stats().entries, 1)
+
+    def test_palette_warns_too(self):
+        self.corrupt()
+        with self.assertWarns(ColorCacheCorruptWarning):
+            get_cached_palette(self.red)
+
+    def test_no_warning_for_a_valid_cache(self):
+        get_cached_colors(self.red)
+        with warnings.catch_warnings():
+            warnings.simplefilter("error")
+            get_cached_colors(self.green)
+
+    def test_corrupt_status(self):
+        self.corrupt()
+        lookup = cache_lookup(self.red)
+        self.assertEqual(lookup.status, CacheStatus.Corrupt)
+        self.assertIsNone(lookup.hash)
+        self.assertIsNotNone(cache_stats().corrupt)
+        with self.assertRaisesRegex(ValueError, "Color cache is corrupt"):
+            prune_cache(1)
+        # Looking up doesn't discard it
+        self.assertTrue(self.cache.exists())
+
+    def test_concurrent_writers_keep_every_entry(self):
+        walls = [str(write_png(self.home / f"{i}.png", (0x20 * i, 0x40, 0x80))) for i in range(6)]
+        script = "import sys; from matuwrap.wrp_native import get_cached_colors; get_cached_colors(sys.argv[1])"
+        processes = [subprocess.Popen([sys.executable, "-c", script, wall]) for wall in walls]
+        self.assertEqual([p.wait(60) for p in processes], [0] * len(walls))
+        cache = json.loads(self.cache.read_text())
+        self.assertEqual(sorted(e["wallpaper_path"] for e in cache["entries"]), sorted(walls))
+        self.assertEqual([p.name for p in self.cache.parent.glob("*.tmp*")], [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,105 @@
+"""Tests for the WCAG contrast audit and its tone fixes."""
+
+import unittest
+
+from matuwrap.wrp_native import audit_contrast, audit_palette, extract_palette, get_cached_palette
+from matuwrap.wrp_native.color import contrast_ratio, to_hct
+
+from tests.support import isolated_home, write_png
+
+
+class ContrastTest(unittest.TestCase):
+    """Runs each test with an empty home and a red wallpaper."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        self.wall = str(write_png(self.home / "wall.png", (0xC0, 0x30, 0x30)))
+        self.palette = extract_palette(self.wall)
+        # Minimum contrast fails far more pairs
+        self.low = extract_palette(self.wall, contrast=-1.0)
+
+
+class TestAudit(ContrastTest):
+    """Tests for auditing without fixes."""
+
+    def test_ratios(self):
+        report = audit_palette(self.palette, "dark")
+        self.assertEqual((report.mode, report.level, report.required), ("dark", "AA", 4.5))
+        for pair in report.pairs:
+            with self.subTest(f"{pair.foreground} on {pair.background}"):
+                self.assertEqual(pair.foreground_color, self.palette.dark.to_dict()[pair.foreground])
+                self.assertAlmostEqual(pair.ratio, contrast_ratio(pair.foreground_color, pair.background_color))
+                self.assertEqual((pair.aa, pair.aaa, pair.passes), (pair.ratio >= 4.5, pair.ratio >= 7, pair.aa))
+                self.assertIsNone(pair.adjusted_ratio)
+
+    def test_failures(self):
+        report = audit_palette(self.low, "light", "aaa")
+        self.assertEqual(report.required, 7.0)
+        self.assertEqual(report.failures, len(report.failing()))
+        self.assertTrue(all(not p.passes and p.ratio < 7 for p in report.failing()))
+        self.assertEqual((report.adjusted, report.unresolved), ({}, report.failures))
+
+    def test_pairs_cover_theme_roles(self):
+        pairs = {(p.foreground, p.background) for p in audit_palette(self.palette).pairs}
+        self.assertIn(("on_surface", "surface"), pairs)
+        self.assertIn(("outline", "surface"), pairs)
+
+    def test_invalid_options(self):
+        for kwargs in [{"level": "AAAA"}, {"mode": "dim"}]:
+            with self.subTest(**kwargs), self.assertRaises(ValueError):
+                audit_palette(self.palette, **kwargs)
+
+    def test_wallpaper(self):
+        ratios = [p.ratio for p in audit_contrast(self.wall, mode="dark").pairs]
+        self.assertEqual(ratios, [p.ratio for p in audit_palette(get_cached_palette(self.wall), "dark").pairs])
+
+
+class TestFix(ContrastTest):
+    """Tests for fixing failing pairs by tone."""
+
+    def test_fixes_every_failure(self):
+        for mode in ["dark", "light"]:
+            for level, required in [("aa", 4.5), ("aaa", 7.0)]:
+                with self.subTest(mode=mode, level=level):
+                    report = audit_palette(self.low, mode, level, fix=True)
+                    self.assertGreater(report.failures, 0)
+                    self.assertEqual(report.unresolved, 0)
+                    for pair in report.pairs:
+                        if pair.adjusted_ratio is not None:
+                            self.assertGreaterEqual(pair.adjusted_ratio, required)
+
+    def test_adjusts_foregrounds_only(self):
+        report = audit_palette(self.low, "light", "aa", fix=True)
+        failing = {p.foreground for p in report.failing()}
+        self.assertTrue(set(report.adjusted) <= failing, report.adjusted)
+        # Passing pairs whose roles weren't touched keep no adjusted ratio
+        for pair in report.pairs:
+            if pair.foreground not in report.adjusted and pair.background not in report.adjusted:
+                self.assertIsNone(pair.adjusted_ratio)
+
+    def test_keeps_hue(self):
+        colors = self.low.light.to_dict()
+        for role, new in audit_palette(self.low, "light", "aa", fix=True).adjusted.items():
+            hue, chroma, tone = to_hct(colors[role])
+            new_hue, _, new_tone = to_hct(new)
+            with self.subTest(role):
+                self.assertNotEqual(new_tone, tone)
+                # Near black or white, hue is lost to gamut clipping
+                if chroma > 16 and 10 < new_tone < 90:
+                    self.assertAlmostEqual(new_hue, hue, delta=3)
+
+    def test_palette_is_unchanged(self):
+        before = self.low.dark.to_dict()
+        audit_palette(self.low, "dark", fix=True)
+        self.assertEqual(self.low.dark.to_dict(), before)
+
+    def test_only_failing_roles_change(self):
+        report = audit_palette(self.palette, "dark", fix=True)
+        self.assertEqual(list(report.adjusted), ["outline_variant"])
+        self.assertEqual(report.unresolved, 0)
+
+
+if __name__ == "__main__":
+    unittest.main()
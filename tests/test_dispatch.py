@@ -0,0 +1,73 @@
+"""Tests for Hyprland dispatchers and their reply checks."""
+
+import unittest
+
+from matuwrap.wrp_native import (
+    HyprlandClient,
+    HyprlandDispatchError,
+    dispatch,
+    dispatch_dpms,
+    dispatch_exec,
+    dispatch_move_to_workspace,
+    dispatch_toggle_floating,
+    dispatch_workspace,
+)
+
+from tests.support import FakeHyprland
+
+
+class TestRequests(unittest.TestCase):
+    """Tests for the requests the helpers send."""
+
+    def test_helpers(self):
+        calls = [
+            (lambda: dispatch("movefocus", ["l"]), "dispatch movefocus l"),
+            (lambda: dispatch("killactive"), "dispatch killactive"),
+            (lambda: dispatch_workspace(3), "dispatch workspace 3"),
+            (lambda: dispatch_workspace("name:web"), "dispatch workspace name:web"),
+            (
+                lambda: dispatch_move_to_workspace(2, "address:0x1", silent=True),
+                "dispatch movetoworkspacesilent 2,address:0x1",
+            ),
+            (lambda: dispatch_dpms(), "dispatch dpms toggle"),
+            (lambda: dispatch_dpms(False, "DP-1"), "dispatch dpms off DP-1"),
+            (lambda: dispatch_exec("kitty", rules="workspace 3 silent"), "dispatch exec [workspace 3 silent] kitty"),
+            (lambda: dispatch_toggle_floating(), "dispatch togglefloating"),
+        ]
+        for call, expected in calls:
+            with self.subTest(expected), FakeHyprland() as hypr:
+                self.assertIsNone(call())
+                self.assertEqual(hypr.requests, [expected])
+
+    def test_client_method(self):
+        with FakeHyprland() as hypr:
+            HyprlandClient().dispatch("workspace", ["1"])
+        self.assertEqual(hypr.requests, ["dispatch workspace 1"])
+
+    def test_invalid_name(self):
+        with FakeHyprland() as hypr:
+            for name in ["", "move focus", "a;b"]:
+                with self.subTest(name=name), self.assertRaises(ValueError):
+                    dispatch(name)
+        self.assertEqual(hypr.requests, [])
+
+
+class TestReplies(unittest.TestCase):
+    """Tests for checking Hyprland's reply."""
+
+    def test_error_reply(self):
+        with FakeHyprland(reply=lambda request: "Invalid dispatcher"):
+            with self.assertRaisesRegex(HyprlandDispatchError, "^nope: Invalid dispatcher$"):
+                dispatch("nope")
+
+    def test_empty_reply(self):
+        with FakeHyprland(reply=lambda request: ""):
+            with self.assertRaisesRegex(HyprlandDispatchError, "empty reply"):
+                dispatch_workspace(1)
+
+    def test_is_a_runtime_error(self):
+        self.assertTrue(issubclass(HyprlandDispatchError, RuntimeError))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,95 @@
+"""Tests for wrp_native.HyprEventStream against a fake event socket."""
+
+import threading
+import time
+import unittest
+
+from matuwrap.wrp_native import HyprEvent, HyprEventStream
+
+from tests.support import FakeHyprland
+
+
+def stream_events(*chunks, **kwargs):
+    """Events a stream yields for `chunks` sent on `.socket2.sock`."""
+    with FakeHyprland(events=list(chunks)), HyprEventStream(**kwargs) as stream:
+        return list(stream)
+
+
+class TestParsing(unittest.TestCase):
+    """Tests for parsing `EVENT>>DATA` lines."""
+
+    def test_typed_fields(self):
+        [added, workspace, floating] = stream_events(
+            b"monitoraddedv2>>2,DP-1,Dell Inc. U2720Q ABC123\n"
+            b"workspacev2>>3,coding\n"
+            b"changefloatingmode>>5612a8e0,1\n"
+        )
+        self.assertIsInstance(added, HyprEvent.MonitorAddedV2)
+        self.assertEqual((added.id, added.name, added.description), (2, "DP-1", "Dell Inc. U2720Q ABC123"))
+        self.assertEqual((workspace.id, workspace.name), (3, "coding"))
+        self.assertTrue(floating.floating)
+        self.assertEqual(floating.event, "changefloatingmode")
+
+    def test_last_field_keeps_commas(self):
+        [window] = stream_events(b"activewindow>>kitty,vim a.py, b.py\n")
+        self.assertEqual((window.window_class, window.title), ("kitty", "vim a.py, b.py"))
+
+    def test_unknown_and_malformed_events(self):
+        """Both come through as Other rather than being dropped."""
+        [unknown, malformed] = stream_events(b"newevent>>a,b\nworkspacev2>>notanumber,x\n")
+        self.assertIsInstance(unknown, HyprEvent.Other)
+        self.assertEqual((unknown.name, unknown.data), ("newevent", "a,b"))
+        self.assertEqual(malformed.event, "workspacev2")
+
+    def test_lines_without_separator_are_skipped(self):
+        events = stream_events(b"garbage\nsubmap>>resize\n")
+        self.assertEqual([e.event for e in events], ["submap"])
+
+
+class TestStream(unittest.TestCase):
+    """Tests for reading the event socket."""
+
+    def test_partial_line_across_reads(self):
+        """A line split across a read timeout is joined, not parsed in halves."""
+        [event] = stream_events(b"monitoradded>>DP", 0.5, b"-1\n")
+        self.assertIsInstance(event, HyprEvent.MonitorAdded)
+        self.assertEqual(event.name, "DP-1")
+
+    def test_filter(self):
+        events = stream_events(b"workspace>>1\nsubmap>>resize\nworkspace>>2\n", events=["workspace"])
+        self.assertEqual([e.name for e in events], ["1", "2"])
+
+    def test_eof_disconnects(self):
+        with FakeHyprland(events=[b"workspace>>1\n"]), HyprEventStream() as stream:
+            self.assertEqual(next(stream).name, "1")
+            with self.assertRaises(StopIteration):
+                next(stream)
+            self.assertFalse(stream.connected)
+
+
+class TestClose(unittest.TestCase):
+    """Tests for closing a stream while another thread waits on it."""
+
+    def test_close_from_another_thread(self):
+        # The fake instance stays connected but silent
+        with FakeHyprland(events=[1.0]), HyprEventStream() as stream:
+            events = []
+            thread = threading.Thread(target=lambda: events.extend(stream))
+            thread.start()
+            time.sleep(0.1)
+            self.assertTrue(stream.connected)
+            with self.assertRaisesRegex(RuntimeError, "already waiting"):
+                next(stream)
+            start = time.monotonic()
+            stream.close()
+            thread.join(2)
+            self.assertFalse(thread.is_alive())
+            self.assertLess(time.monotonic() - start, 0.5)
+            self.assertEqual(events, [])
+            self.assertFalse(stream.connected)
+            with self.assertRaises(StopIteration):
+                next(stream)
+
+
+if __name__ == "__main__":
+    unittest.main()
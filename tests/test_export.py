@@ -0,0 +1,170 @@
+"""Tests for exporting palettes as terminal and app color files."""
+
+import json
+import re
+import threading
+import tomllib
+import unittest
+
+from matuwrap.wrp_native import ansi_palette, export_files, export_formats, export_palette, extract_palette
+from matuwrap.wrp_native.color import to_hct
+
+from tests.support import isolated_home, write_png
+
+ANSI_NAMES = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"]
+
+
+def key_values(text: str, pattern: str) -> dict[str, str]:
+    """Pairs matched by `pattern` (two groups) in each line of `text`."""
+    return dict(m.groups() for m in map(re.compile(pattern).fullmatch, text.splitlines()) if m)
+
+
+class ExportTest(unittest.TestCase):
+    """Runs each test with an empty home and a red wallpaper's palette."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        self.wall = str(write_png(self.home / "wall.png", (0xC0, 0x30, 0x30)))
+        self.palette = extract_palette(self.wall)
+        self.dark = self.palette.dark.to_dict()
+        self.ansi = ansi_palette(self.palette, "dark")
+
+
+class TestAnsiPalette(ExportTest):
+    """Tests for the 16 ANSI colors."""
+
+    def test_grays_from_scheme(self):
+        self.assertEqual(len(self.ansi), 16)
+        self.assertEqual(self.ansi[8], self.dark["outline"])
+        self.assertEqual(self.ansi[15], self.dark["on_surface"])
+
+    def test_hues_are_distinct(self):
+        hues = [to_hct(color)[0] for color in self.ansi[1:7]]
+        for a, b in zip(hues, hues[1:]):
+            self.assertGreater(abs(a - b), 20, hues)
+
+    def test_tones_by_mode(self):
+        light = ansi_palette(self.palette, "light")
+        for dark, lite in zip(self.ansi[1:7], light[1:7]):
+            self.assertGreater(to_hct(dark)[2], to_hct(lite)[2])
+
+
+class TestFormats(ExportTest):
+    """Tests for each export format's contents."""
+
+    def export(self, format):
+        return export_palette(self.palette, format, "dark")
+
+    def test_header(self):
+        for format in export_formats():
+            if format != "json":
+                with self.subTest(format):
+                    self.assertIn("Generated by matuwrap (dark scheme)", self.export(format).splitlines()[0])
+
+    def test_xresources(self):
+        values = key_values(self.export("xresources"), r"\*\.(\w+): (#\w{6})")
+        self.assertEqual(values["background"], self.dark["surface"])
+        self.assertEqual(values["cursorColor"], self.dark["primary"])
+        self.assertEqual([values[f"color{i}"] for i in range(16)], self.ansi)
+
+    def test_kitty(self):
+        values = key_values(self.export("kitty"), r"(\w+) (#\w{6})")
+        self.assertEqual((values["foreground"], values["background"]), (self.dark["on_surface"], self.dark["surface"]))
+        self.assertEqual([values[f"color{i}"] for i in range(16)], self.ansi)
+
+    def test_alacritty(self):
+        colors = tomllib.loads(self.export("alacritty"))["colors"]
+        self.assertEqual(colors["primary"]["background"], self.dark["surface"])
+        self.assertEqual(colors["cursor"]["cursor"], self.dark["primary"])
+        ansi = [colors[group][name] for group in ["normal", "bright"] for name in ANSI_NAMES]
+        self.assertEqual(ansi, self.ansi)
+
+    def test_foot(self):
+        values = key_values(self.export("foot"), r"([\w-]+)=(\w{6}(?: \w{6})?)")
+        self.assertEqual(values["background"], self.dark["surface"][1:])
+        self.assertEqual(values["color"], f"{self.dark['on_primary'][1:]} {self.dark['primary'][1:]}")
+        ansi = [values[f"{group}{i}"] for group in ["regular", "bright"] for i in range(8)]
+        self.assertEqual(ansi, [c[1:] for c in self.ansi])
+
+    def test_gtk(self):
+        values = key_values(self.export("gtk"), r"@define-color (\w+) (#\w{6});")
+        self.assertEqual(values["accent_bg_color"], self.dark["primary"])
+        self.assertEqual(values["window_bg_color"], self.dark["surface"])
+        self.assertEqual(values["on_surface"], self.dark["on_surface"])
+
+    def test_css(self):
+        text = self.export("css")
+        self.assertIn("color-scheme: dark;", text)
+        values = key_values(text, r"  --([\w-]+): (#\w{6});")
+        self.assertEqual([values.pop(f"ansi-{i}") for i in range(16)], self.ansi)
+        self.assertEqual({k.replace("-", "_"): v for k, v in values.items()}, self.dark)
+
+    def test_json(self):
+        tokens = json.loads(self.export("json"))
+        roles = {k: v["$value"] for k, v in tokens["color"].items() if not k.startswith("$")}
+        self.assertEqual(roles, self.dark)
+        self.assertEqual([tokens["ansi"][name]["$value"] for name in ANSI_NAMES], self.ansi[:8])
+
+    def test_light_mode(self):
+        light = self.palette.light.to_dict()
+        values = key_values(export_palette(self.palette, "kitty", "light"), r"(\w+) (#\w{6})")
+        self.assertEqual(values["background"], light["surface"])
+
+    def test_invalid(self):
+        with self.assertRaisesRegex(ValueError, "Unknown export format"):
+            export_palette(self.palette, "iterm")
+        with self.assertRaises(ValueError):
+            export_palette(self.palette, "kitty", "dim")
+
+
+class TestExportFiles(ExportTest):
+    """Tests for export_files."""
+
+    def test_writes_every_format(self):
+        results = export_files(self.wall, mode="dark")
+        self.assertEqual([r.format for r in results], export_formats())
+        directory = self.home / ".cache" / "matuwrap" / "export"
+        for result in results:
+            with self.subTest(result.format):
+                self.assertTrue(result.changed)
+                self.assertIsNone(result.error)
+                self.assertEqual(result.path.rsplit("/", 1)[0], str(directory))
+                with open(result.path) as f:
+                    self.assertEqual(f.read(), export_palette(self.palette, result.format, "dark"))
+
+    def test_unchanged_files(self):
+        export_files(self.wall, formats=["kitty"])
+        [result] = export_files(self.wall, formats=["kitty"])
+        self.assertFalse(result.changed)
+
+    def test_directory_and_unknown_format(self):
+        kitty, unknown = export_files(self.wall, str(self.home / "out"), ["kitty", "iterm"])
+        self.assertEqual(kitty.path, str(self.home / "out" / "kitty-colors.conf"))
+        self.assertEqual((unknown.path, unknown.changed), ("", False))
+        self.assertIn("Unknown export format", unknown.error)
+
+    def test_threads_writing_the_same_file(self):
+        out = self.home / "out"
+        export_files(self.wall, str(out), ["kitty"])
+        errors = []
+
+        def write(modes):
+            for mode in modes * 25:
+                [result] = export_files(self.wall, str(out), ["kitty"], mode=mode)
+                if result.error:
+                    errors.append(result.error)
+
+        orders = (["light", "dark"], ["dark", "light"]) * 2
+        threads = [threading.Thread(target=write, args=(modes,)) for modes in orders]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        self.assertEqual(errors, [])
+        self.assertEqual([p.name for p in out.iterdir()], ["kitty-colors.conf"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,92 @@
+"""Tests for native color extraction (used when matugen is missing)."""
+
+import unittest
+
+from matuwrap.wrp_native import extract_colors, extract_palette, get_cached_colors
+
+from tests.support import isolated_home, write_png
+
+RED = (0xC0, 0x30, 0x30)
+GREEN = (0x30, 0x90, 0x40)
+SCHEME_TYPES = [
+    "scheme-content",
+    "scheme-expressive",
+    "scheme-fidelity",
+    "scheme-fruit-salad",
+    "scheme-monochrome",
+    "scheme-neutral",
+    "scheme-rainbow",
+    "scheme-tonal-spot",
+    "scheme-vibrant",
+]
+
+
+class ExtractTest(unittest.TestCase):
+    """Runs each test with an empty home and a mostly red wallpaper."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        # Five red stripes to three green, so red ranks first
+        self.wall = str(write_png(self.home / "wall.png", *[RED] * 5, *[GREEN] * 3))
+
+
+class TestExtractColors(ExtractTest):
+    """Tests for extract_colors."""
+
+    def test_source_colors(self):
+        self.assertEqual(extract_colors(self.wall)["source_color"], "#c03030")
+        self.assertEqual(extract_colors(self.wall, source_index=1)["source_color"], "#309040")
+
+    def test_scheme_types(self):
+        for scheme_type in SCHEME_TYPES:
+            with self.subTest(scheme_type):
+                colors = extract_colors(self.wall, scheme_type)
+                self.assertEqual(len(colors), 50)
+                self.assertTrue(all(len(c) == 7 and c.startswith("#") for c in colors.values()))
+
+    def test_monochrome_is_grey(self):
+        primary = extract_colors(self.wall, "scheme-monochrome")["primary"]
+        self.assertEqual(len({primary[1:3], primary[3:5], primary[5:7]}), 1, primary)
+
+    def test_modes_differ(self):
+        dark = extract_colors(self.wall, mode="dark")
+        light = extract_colors(self.wall, mode="light")
+        self.assertEqual(dark["source_color"], light["source_color"])
+        self.assertNotEqual(dark["surface"], light["surface"])
+
+    def test_is_the_cache_fallback(self):
+        """Without matugen on PATH, the cache holds the native colors."""
+        self.assertEqual(get_cached_colors(self.wall), extract_colors(self.wall))
+
+    def test_invalid_options(self):
+        cases = [
+            ({"scheme_type": "scheme-nope"}, "Unknown scheme type"),
+            ({"mode": "dim"}, "Unknown mode"),
+            ({"contrast": 3.0}, "Contrast must be between"),
+            ({"source_index": 5}, "no source color at index 5"),
+        ]
+        for kwargs, message in cases:
+            with self.subTest(**kwargs), self.assertRaisesRegex(ValueError, message):
+                extract_colors(self.wall, **kwargs)
+
+    def test_unreadable_images(self):
+        with self.assertRaises(OSError):
+            extract_colors(str(self.home / "missing.png"))
+        (self.home / "bad.png").write_text("not an image")
+        with self.assertRaisesRegex(ValueError, "Failed to decode image"):
+            extract_colors(str(self.home / "bad.png"))
+
+
+class TestExtractPalette(ExtractTest):
+    """Tests for extract_palette."""
+
+    def test_matches_extract_colors(self):
+        palette = extract_palette(self.wall, "scheme-vibrant", 0.5, 1)
+        self.assertEqual(palette.dark.to_dict(), extract_colors(self.wall, "scheme-vibrant", "dark", 0.5, 1))
+        self.assertEqual(palette.light.to_dict(), extract_colors(self.wall, "scheme-vibrant", "light", 0.5, 1))
+
+
+if __name__ == "__main__":
+    unittest.main()
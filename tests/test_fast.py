@@ -0,0 +1,70 @@
+"""Tests for the wrp-fast binary (built by `cargo build` in rust/)."""
+
+import os
+import subprocess
+import unittest
+from pathlib import Path
+
+from matuwrap.wrp_native import get_cached_colors
+
+from tests.support import isolated_home, write_png
+
+WRP_FAST = Path(os.environ.get("WRP_FAST", Path(__file__).parents[1] / "rust" / "target" / "debug" / "wrp-fast"))
+
+
+def wrp_fast(*args: str, **kwargs) -> subprocess.CompletedProcess:
+    return subprocess.run([str(WRP_FAST), *args], capture_output=True, text=True, **kwargs)
+
+
+@unittest.skipUnless(WRP_FAST.exists(), f"{WRP_FAST} not built")
+class TestWrpFast(unittest.TestCase):
+    """Tests for wrp-fast output and errors."""
+
+    def test_colors_match_the_module(self):
+        with isolated_home() as home:
+            (home / ".current.wall").symlink_to(write_png(home / "wall.png"))
+            result = wrp_fast("colors", "get", "tertiary")
+            expected = get_cached_colors(str(home / ".current.wall"))["tertiary"]
+        self.assertEqual(result.returncode, 0, result.stderr)
+        self.assertEqual(result.stdout, f"{expected}\n")
+
+    def test_ps1_without_color(self):
+        with isolated_home() as home:
+            (home / ".current.wall").symlink_to(write_png(home / "wall.png"))
+            result = wrp_fast("colors", "ps1", "--depth", "none")
+        self.assertEqual(result.stdout, "\\u@\\h:\\w\n")
+
+    def test_unknown_depth(self):
+        with isolated_home():
+            result = wrp_fast("colors", "ps1", "--depth", "88")
+        self.assertEqual(result.returncode, 1)
+        self.assertIn("Unknown color depth", result.stderr)
+
+    def test_usage_before_loading_colors(self):
+        """Bad commands and options are reported without a wallpaper."""
+        cases = [
+            ((), "Usage"),
+            (("typo",), 'Unknown colors command "typo"'),
+            (("get", "--mode", "dim"), 'Unknown mode "dim"'),
+        ]
+        for args, message in cases:
+            with self.subTest(args), isolated_home():
+                result = wrp_fast("colors", *args)
+            self.assertEqual(result.returncode, 1)
+            self.assertIn(message, result.stderr)
+            self.assertNotIn("Could not generate colors", result.stderr)
+
+    def test_closed_pipe(self):
+        """A reader that went away (`wrp-fast ... | head -0`) is not an error."""
+        read, write = os.pipe()
+        os.close(read)
+        try:
+            result = subprocess.run([str(WRP_FAST), "help"], stdout=write, stderr=subprocess.PIPE, text=True)
+        finally:
+            os.close(write)
+        self.assertEqual(result.returncode, 0)
+        self.assertEqual(result.stderr, "")
+
+
+if __name__ == "__main__":
+    unittest.main()
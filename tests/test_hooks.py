@@ -0,0 +1,152 @@
+"""Tests for post-render template hooks."""
+
+import json
+import shutil
+import subprocess
+import time
+import unittest
+
+from matuwrap.wrp_native import extract_palette, render_templates
+
+from tests.support import FakeHyprland, isolated_home, write_png
+
+
+def render_with_hooks(home, *hooks):
+    """Render one template with `hooks` and return its hook results."""
+    templates = home / ".config" / "matuwrap" / "templates"
+    templates.mkdir(parents=True)
+    (templates / "colors.txt").write_text("{{colors.primary.default.hex}}\n")
+    manifest = {"test": {"input": "colors.txt", "output": str(home / "out.txt"), "hooks": list(hooks)}}
+    (templates / "templates.json").write_text(json.dumps(manifest))
+    [result] = render_templates(str(write_png(home / "wall.png")))
+    return result.hooks
+
+
+class TestCommandHooks(unittest.TestCase):
+    """Tests for `command` hooks."""
+
+    def test_output(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "command", "command": ["echo", "{{mode}}"]})
+        self.assertTrue(hook.ok)
+        self.assertEqual(hook.output, "dark")
+
+    def test_failure_reports_stderr(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "command", "command": ["sh", "-c", "echo bad >&2; exit 3"]})
+        self.assertFalse(hook.ok)
+        self.assertEqual(hook.error, "bad")
+
+    def test_background_process_does_not_block(self):
+        """A process left running in the background keeps the pipes open;
+        the hook still finishes when the command itself exits."""
+        with isolated_home() as home:
+            start = time.monotonic()
+            [hook] = render_with_hooks(
+                home, {"type": "command", "command": ["sh", "-c", "sleep 3 & echo started"], "timeout": 10}
+            )
+            elapsed = time.monotonic() - start
+        self.assertTrue(hook.ok, hook.error)
+        self.assertEqual(hook.output, "started")
+        self.assertLess(elapsed, 2)
+
+    def test_placeholders_in_arguments(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "command", "command": ["echo", "{{colors.primary.dark.hex}}"]})
+            primary = extract_palette(str(home / "wall.png")).dark.primary
+        self.assertEqual(hook.output, primary)
+
+    def test_invalid_placeholder(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "command", "command": ["echo", "{{nope}}"]})
+        self.assertFalse(hook.ok)
+        self.assertIn("Unknown placeholder", hook.error)
+
+    def test_timeout(self):
+        with isolated_home() as home:
+            start = time.monotonic()
+            [hook] = render_with_hooks(home, {"type": "command", "command": ["sleep", "5"], "timeout": 0.2})
+            elapsed = time.monotonic() - start
+        self.assertFalse(hook.ok)
+        self.assertIn("timed out", hook.error)
+        self.assertLess(elapsed, 2)
+
+
+class TestSignalHooks(unittest.TestCase):
+    """Tests for `signal` hooks."""
+
+    def test_signals_process_by_name(self):
+        with isolated_home() as home:
+            # A copy of sleep with a name nothing else is running under
+            sleeper = shutil.copy("/bin/sleep", home / "mw-hook-sleeper")
+            process = subprocess.Popen([sleeper, "30"])
+            try:
+                [hook] = render_with_hooks(home, {"type": "signal", "process": "mw-hook-sleeper", "signal": "TERM"})
+                self.assertEqual(process.wait(5), -15)
+            finally:
+                process.kill()
+                process.wait()
+        self.assertTrue(hook.ok, hook.error)
+        self.assertEqual(hook.output, "1 process(es)")
+
+    def test_unknown_signal(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "signal", "process": "nope", "signal": "SIGFOO"})
+        self.assertFalse(hook.ok)
+        self.assertIn("Unknown signal", hook.error)
+
+    def test_no_process(self):
+        with isolated_home() as home:
+            [hook] = render_with_hooks(home, {"type": "signal", "process": "no-such-process-here"})
+        self.assertFalse(hook.ok)
+        self.assertIn("No running process", hook.error)
+
+
+class TestKeywordHooks(unittest.TestCase):
+    """Tests for `keyword` hooks."""
+
+    def test_sends_rendered_keyword(self):
+        hook = {"type": "keyword", "keyword": "general:col.active_border", "value": "rgb({{colors.primary.dark.rgb}})"}
+        with isolated_home() as home, FakeHyprland() as hypr:
+            [result] = render_with_hooks(home, hook)
+            primary = extract_palette(str(home / "wall.png")).dark.primary
+        self.assertTrue(result.ok, result.error)
+        self.assertEqual(result.kind, "keyword")
+        r, g, b = (int(primary[i : i + 2], 16) for i in (1, 3, 5))
+        self.assertEqual(hypr.requests, [f"keyword general:col.active_border rgb(rgb({r}, {g}, {b}))"])
+
+    def test_rejected_keyword(self):
+        hook = {"type": "keyword", "keyword": "general:nope", "value": "1"}
+        with isolated_home() as home, FakeHyprland(reply=lambda request: "no such field"):
+            [result] = render_with_hooks(home, hook)
+        self.assertFalse(result.ok)
+        self.assertEqual(result.error, "no such field")
+
+
+class TestWhenHooksRun(unittest.TestCase):
+    """Tests for which renders run hooks."""
+
+    def test_only_when_output_changes(self):
+        with isolated_home() as home:
+            [first] = render_with_hooks(home, {"type": "command", "command": ["true"]})
+            [second] = render_templates(str(home / "wall.png"))
+        self.assertTrue(first.ok, first.error)
+        self.assertFalse(second.changed)
+        self.assertEqual(second.hooks, [])
+
+    def test_not_in_dry_run(self):
+        with isolated_home() as home:
+            templates = home / ".config" / "matuwrap" / "templates"
+            templates.mkdir(parents=True)
+            (templates / "colors.txt").write_text("{{mode}}\n")
+            hooks = [{"type": "command", "command": ["touch", str(home / "ran")]}]
+            manifest = {"test": {"input": "colors.txt", "output": str(home / "out.txt"), "hooks": hooks}}
+            (templates / "templates.json").write_text(json.dumps(manifest))
+            [result] = render_templates(str(write_png(home / "wall.png")), dry_run=True)
+            self.assertTrue(result.changed)
+            self.assertEqual(result.hooks, [])
+            self.assertFalse((home / "ran").exists())
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,89 @@
+"""Tests for restoring layout profiles on monitor hotplug."""
+
+import unittest
+
+from matuwrap.wrp_native import LayoutWatcher, find_matching_profile, save_layout_profile
+
+from tests.support import FakeHyprland, isolated_home, monitor, monitors_reply
+
+LAPTOP = monitor("eDP-1", make="BOE", model="0x095F", serial="")
+DELL = monitor("DP-1", make="Dell Inc.", model="U2720Q", serial="ABC123", x=1920)
+
+
+class HotplugTest(unittest.TestCase):
+    """Saves a "laptop" and a "docked" profile in an empty config dir."""
+
+    def setUp(self):
+        home = isolated_home()
+        home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        with FakeHyprland(reply=monitors_reply([LAPTOP])):
+            save_layout_profile("laptop")
+        with FakeHyprland(reply=monitors_reply([LAPTOP, DELL])):
+            save_layout_profile("docked")
+
+
+class TestFindMatchingProfile(HotplugTest):
+    """Tests for find_matching_profile."""
+
+    def test_exact_set(self):
+        renumbered = {**DELL, "name": "DP-7"}
+        for monitors, expected in [([LAPTOP], "laptop"), ([renumbered, LAPTOP], "docked"), ([DELL], None)]:
+            with self.subTest(expected), FakeHyprland(reply=monitors_reply(monitors)):
+                self.assertEqual(find_matching_profile(), expected)
+
+
+class TestLayoutWatcher(HotplugTest):
+    """Tests for LayoutWatcher."""
+
+    def test_switches_on_hotplug(self):
+        connected = [LAPTOP]
+        # Stay connected past the debounce; an exit during it ends iteration
+        events = [0.3, b"monitoradded>>DP-1\n", b"monitoraddedv2>>1,DP-1,Dell\n", 0.3]
+        with FakeHyprland(reply=monitors_reply(connected), events=events) as hypr:
+            with LayoutWatcher(debounce=0.05) as watcher:
+                start = next(watcher)
+                connected.append(DELL)
+                switches = list(watcher)
+        self.assertEqual((start.profile, start.monitors), ("laptop", ["BOE 0x095F"]))
+        [docked] = switches
+        self.assertEqual(docked.profile, "docked")
+        self.assertIsNone(docked.error)
+        self.assertEqual(docked.lines[1], "monitor=DP-1,1920x1080@60.00,1920x0,1")
+        self.assertIn("dispatch dpms on DP-1", hypr.requests)
+
+    def test_unchanged_set_is_not_reported(self):
+        events = [b"monitorremoved>>DP-1\n", b"monitoradded>>DP-1\n"]
+        with FakeHyprland(reply=monitors_reply([LAPTOP]), events=events):
+            with LayoutWatcher(debounce=0.05, apply_on_start=False) as watcher:
+                self.assertEqual(list(watcher), [])
+
+    def test_no_matching_profile(self):
+        with FakeHyprland(reply=monitors_reply([DELL]), events=[]):
+            with LayoutWatcher(debounce=0.05) as watcher:
+                switch = next(watcher)
+        self.assertEqual((switch.profile, switch.lines, switch.error), (None, [], None))
+        self.assertEqual(switch.monitors, ["Dell Inc. U2720Q ABC123"])
+
+    def test_restore_error_is_reported(self):
+        no_modes = {**LAPTOP, "availableModes": ["800x600@60.00Hz"]}
+        with FakeHyprland(reply=monitors_reply([no_modes]), events=[]):
+            with LayoutWatcher(debounce=0.05) as watcher:
+                switch = next(watcher)
+        self.assertEqual(switch.profile, "laptop")
+        self.assertIn("not supported", switch.error)
+
+    def test_dry_run(self):
+        with FakeHyprland(reply=monitors_reply([LAPTOP]), events=[]) as hypr:
+            with LayoutWatcher(dry_run=True) as watcher:
+                switch = next(watcher)
+        self.assertEqual(switch.lines, ["monitor=eDP-1,1920x1080@60.00,0x0,1"])
+        self.assertTrue(all(r.startswith("j/") for r in hypr.requests), hypr.requests)
+
+    def test_invalid_debounce(self):
+        with FakeHyprland(events=[]), self.assertRaises(ValueError):
+            LayoutWatcher(debounce=-1)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,39 @@
+"""Tests for Hue light state colors in matuwrap.commands.hue."""
+
+import unittest
+from unittest import mock
+
+from matuwrap.commands import hue
+
+STATES = [
+    {"colormode": "xy", "xy": [0.6915, 0.3083], "bri": 254},
+    {"colormode": "xy", "xy": [0.17, 0.7], "bri": 120},
+    {"colormode": "xy", "xy": [0.3127, 0.329], "bri": 1},
+    {"colormode": "hs", "hue": 46920, "sat": 254, "bri": 200},
+    {"colormode": "hs", "hue": 0, "sat": 0, "bri": 254},
+    {"colormode": "ct", "ct": 153},
+    {"colormode": "ct", "ct": 500},
+    {"hue": 10000, "sat": 100},
+]
+
+
+class TestStateToHex(unittest.TestCase):
+    """Tests for _hue_state_to_hex."""
+
+    def test_fallback_matches_native(self):
+        for state in STATES:
+            with self.subTest(state):
+                native = hue._hue_state_to_hex(state)
+                with mock.patch.object(hue, "_USE_NATIVE", False):
+                    self.assertEqual(hue._hue_state_to_hex(state), native)
+
+    def test_fallback_colors(self):
+        with mock.patch.object(hue, "_USE_NATIVE", False):
+            self.assertEqual(hue._hue_state_to_hex({"colormode": "hs", "hue": 0, "sat": 254, "bri": 254}), "#ff0000")
+            self.assertEqual(hue._hue_state_to_hex({"colormode": "xy", "xy": [0.3, 0.0]}), "#000000")
+            self.assertIsNone(hue._hue_state_to_hex({"colormode": "xy"}))
+            self.assertIsNone(hue._hue_state_to_hex({}))
+
+
+if __name__ == "__main__":
+    unittest.main()
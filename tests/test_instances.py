@@ -0,0 +1,87 @@
+"""Tests for finding Hyprland instances without HYPRLAND_INSTANCE_SIGNATURE."""
+
+import os
+import socket
+import unittest
+from unittest import mock
+
+from matuwrap.wrp_native import HyprlandClient, list_instances
+
+from tests.support import FakeHyprland
+
+
+class InstanceTest(unittest.TestCase):
+    """Runs each test in a fake instance's runtime dir, without the env var."""
+
+    def setUp(self):
+        self.hypr = FakeHyprland()
+        self.hypr.__enter__()
+        self.addCleanup(self.hypr.__exit__, None, None, None)
+        env = mock.patch.dict(os.environ)
+        env.start()
+        self.addCleanup(env.stop)
+        del os.environ["HYPRLAND_INSTANCE_SIGNATURE"]
+        self.root = self.hypr.dir.parent
+
+    def add_instance(self, signature, lock=None, listening=False):
+        directory = self.root / signature
+        directory.mkdir()
+        if lock is not None:
+            (directory / "hyprland.lock").write_text(lock)
+        if listening:
+            server = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+            server.bind(str(directory / ".socket.sock"))
+            server.listen()
+            self.addCleanup(server.close)
+        return directory
+
+    def instances(self, **kwargs):
+        """Instances found under this test's runtime dir."""
+        return {i.signature: i for i in list_instances(**kwargs) if i.path.startswith(str(self.root))}
+
+
+class TestListInstances(InstanceTest):
+    """Tests for list_instances."""
+
+    def test_lock_file(self):
+        self.add_instance("dead_1", lock="4242\nwayland-1\n")
+        dead = self.instances()["dead_1"]
+        self.assertEqual((dead.pid, dead.wayland_display), (4242, "wayland-1"))
+        self.assertFalse(dead.alive)
+
+    def test_alive(self):
+        self.add_instance("dead_1")
+        self.assertEqual(sorted(self.instances()), ["dead_1", FakeHyprland.signature])
+        self.assertEqual(list(self.instances(alive_only=True)), [FakeHyprland.signature])
+        self.assertIsNone(self.instances()[FakeHyprland.signature].pid)
+
+
+class TestResolve(InstanceTest):
+    """Tests for picking an instance."""
+
+    def test_single_live_instance(self):
+        self.add_instance("dead_1")
+        client = HyprlandClient()
+        self.assertEqual(client.signature, FakeHyprland.signature)
+        self.assertEqual(client.socket_path, str(self.hypr.dir / ".socket.sock"))
+        self.assertEqual(client.hyprctl("version"), "ok")
+
+    def test_ambiguous(self):
+        self.add_instance("other_2", listening=True)
+        with self.assertRaisesRegex(RuntimeError, "Multiple Hyprland instances"):
+            HyprlandClient()
+        # Naming one resolves it
+        self.assertEqual(HyprlandClient(instance="other_2").signature, "other_2")
+
+    def test_env_var_wins(self):
+        self.add_instance("other_2", listening=True)
+        with mock.patch.dict(os.environ, {"HYPRLAND_INSTANCE_SIGNATURE": "other_2"}):
+            self.assertEqual(HyprlandClient().signature, "other_2")
+
+    def test_unknown_instance(self):
+        with self.assertRaisesRegex(RuntimeError, "not found"):
+            HyprlandClient(instance="missing_3")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,147 @@
+"""Tests for wrp_native.HyprlandClient against a fake Hyprland socket."""
+
+import socket
+import threading
+import time
+import unittest
+
+from matuwrap.wrp_native import HyprlandClient, HyprlandTimeoutError, hyprctl_batch
+
+from tests.support import FakeHyprland
+
+
+class TestTimeouts(unittest.TestCase):
+    """Tests for client timeouts."""
+
+    def test_invalid_timeouts(self):
+        for kwargs in [{"read_timeout": 0}, {"connect_timeout": 0.0}, {"write_timeout": -1}]:
+            with self.subTest(**kwargs), self.assertRaises(ValueError):
+                HyprlandClient(**kwargs)
+
+    def test_read_timeout(self):
+        def slow(request):
+            time.sleep(0.5)
+            return "ok"
+
+        with FakeHyprland(slow):
+            client = HyprlandClient(read_timeout=0.1)
+            start = time.monotonic()
+            with self.assertRaises(HyprlandTimeoutError) as raised:
+                client.hyprctl("version")
+            self.assertLess(time.monotonic() - start, 0.4)
+        self.assertIsInstance(raised.exception, TimeoutError)
+
+    def test_no_timeout(self):
+        with FakeHyprland(lambda request: "pong") as hypr:
+            client = HyprlandClient(connect_timeout=None, read_timeout=None, write_timeout=None)
+            self.assertEqual(client.hyprctl("version"), "pong")
+        self.assertEqual(hypr.requests, ["version"])
+
+
+class TestRetry(unittest.TestCase):
+    """Tests for retrying refused connections."""
+
+    def refusing_socket(self, hypr):
+        """Replace the fake's socket with one that refuses connections."""
+        path = hypr.dir / ".socket.sock"
+        path.unlink()
+        server = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        server.bind(str(path))
+        self.addCleanup(server.close)
+        return server
+
+    def test_retries_once(self):
+        """A compositor that starts listening shortly after is reached."""
+        with FakeHyprland() as hypr:
+            server = self.refusing_socket(hypr)
+
+            def listen_late():
+                time.sleep(0.03)
+                server.listen()
+                conn, _ = server.accept()
+                with conn:
+                    conn.recv(1024)
+                    conn.sendall(b"pong")
+
+            thread = threading.Thread(target=listen_late)
+            thread.start()
+            self.assertEqual(HyprlandClient().hyprctl("version"), "pong")
+            thread.join()
+
+    def test_refused_twice(self):
+        with FakeHyprland() as hypr:
+            self.refusing_socket(hypr)
+            with self.assertRaises(ConnectionError):
+                HyprlandClient().hyprctl("version")
+
+
+class TestBatchReplies(unittest.TestCase):
+    """Tests for splitting a batch response into per-command replies."""
+
+    def batch(self, response, commands, json=False):
+        with FakeHyprland(lambda request: response):
+            return hyprctl_batch(commands, json=json)
+
+    def test_separated_replies(self):
+        """Hyprland 0.40+ separates replies with blank lines."""
+        replies = self.batch("ok\n\n\nInvalid dispatcher", ["dispatch workspace 2", "dispatch nope"])
+        self.assertEqual([r.output for r in replies], ["ok", "Invalid dispatcher"])
+        self.assertEqual([r.ok for r in replies], [True, False])
+        self.assertEqual(replies[1].error, "Invalid dispatcher")
+
+    def test_concatenated_json(self):
+        """Older releases concatenate replies; JSON values delimit themselves."""
+        replies = self.batch('[{"id": 1}]{"id": 2}', ["monitors", "activeworkspace"], json=True)
+        self.assertEqual([r.output for r in replies], ['[{"id": 1}]', '{"id": 2}'])
+        self.assertTrue(all(r.ok for r in replies))
+
+    def test_concatenated_ok(self):
+        replies = self.batch("okok", ["dispatch workspace 1", "keyword general:gaps_in 4"])
+        self.assertEqual([r.output for r in replies], ["ok", "ok"])
+
+    def test_reply_errors(self):
+        replies = self.batch(
+            "unknown request\n\n\nnot json\n\n\n[]",
+            ["nope", "monitors", "workspaces"],
+            json=True,
+        )
+        self.assertEqual([r.error for r in replies], ["unknown request", "not json", None])
+        self.assertEqual([r.command for r in replies], ["nope", "monitors", "workspaces"])
+
+    def test_unsplittable(self):
+        with self.assertRaises(RuntimeError):
+            self.batch("ok", ["dispatch workspace 1", "dispatch workspace 2"])
+
+    def test_json_prefix(self):
+        with FakeHyprland(lambda request: "[]\n\n\n[]") as hypr:
+            hyprctl_batch(["monitors", " clients "], json=True)
+        self.assertEqual(hypr.requests, ["[[BATCH]]j/monitors;j/clients"])
+
+    def test_empty(self):
+        with FakeHyprland() as hypr:
+            self.assertEqual(hyprctl_batch([]), [])
+        self.assertEqual(hypr.requests, [])
+
+
+class TestBatchCommands(unittest.TestCase):
+    """Tests for the commands a batch accepts."""
+
+    def test_semicolon_inside_rules(self):
+        with FakeHyprland(lambda request: "ok\n\n\nok") as hypr:
+            replies = hyprctl_batch(["dispatch exec [workspace 2 silent; float] kitty", "dispatch workspace 2"])
+        self.assertEqual(
+            hypr.requests,
+            ["[[BATCH]]dispatch exec [workspace 2 silent; float] kitty;dispatch workspace 2"],
+        )
+        self.assertTrue(all(r.ok for r in replies))
+
+    def test_semicolon_outside_rules(self):
+        for command in ["dispatch workspace 2; dispatch exec kitty", "dispatch exec [float] kitty; ls"]:
+            with self.subTest(command=command), FakeHyprland() as hypr:
+                with self.assertRaises(ValueError):
+                    hyprctl_batch([command])
+                self.assertEqual(hypr.requests, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,115 @@
+"""Tests for monitor layout validation and `keyword monitor` rules."""
+
+import unittest
+
+from matuwrap.wrp_native import MonitorConfig, apply_layout, current_layout, validate_layout
+
+from tests.support import FakeHyprland, monitor, monitors_reply
+
+MONITORS = [
+    monitor("DP-1", description="Dell Inc. U2720Q ABC123", x=0, y=0),
+    monitor("HDMI-A-1", x=1920, y=0, transform=1, scale=1.5),
+]
+
+
+class TestRules(unittest.TestCase):
+    """Tests for MonitorConfig rule text."""
+
+    def test_rule(self):
+        config = MonitorConfig("DP-1", "2560x1440@165", (0, 0), 1.25, transform=1)
+        self.assertEqual(config.to_rule(), "DP-1,2560x1440@165,0x0,1.25,transform,1")
+        self.assertEqual(config.to_config_line(), "monitor=DP-1,2560x1440@165,0x0,1.25,transform,1")
+
+    def test_defaults(self):
+        self.assertEqual(MonitorConfig("DP-1").to_rule(), "DP-1,preferred,auto,1")
+
+    def test_disabled(self):
+        self.assertEqual(MonitorConfig("DP-1", "1920x1080", enabled=False).to_rule(), "DP-1,disable")
+
+
+class TestValidate(unittest.TestCase):
+    """Tests for validate_layout."""
+
+    def problems(self, *configs):
+        with FakeHyprland(reply=monitors_reply(MONITORS)):
+            return validate_layout(list(configs))
+
+    def test_valid(self):
+        self.assertEqual(
+            self.problems(
+                MonitorConfig("DP-1", "1920x1080@60"),
+                MonitorConfig("HDMI-A-1", "1280x720@60Hz", (1920, 0)),
+                MonitorConfig("desc:Dell Inc. U2720Q", "highres"),
+            ),
+            [],
+        )
+
+    def test_refresh_tolerance(self):
+        self.assertEqual(self.problems(MonitorConfig("DP-1", "1280x720@59.5")), [])
+        [problem] = self.problems(MonitorConfig("DP-1", "1280x720@58"))
+        self.assertIn("not supported", problem)
+
+    def test_problems(self):
+        cases = [
+            (MonitorConfig("DP-9"), "DP-9: no such monitor"),
+            (MonitorConfig("desc:Acer"), "desc:Acer: no such monitor"),
+            (MonitorConfig("DP-1", "big"), 'DP-1: invalid mode "big"'),
+            (MonitorConfig("DP-1", "3840x2160@60"), "DP-1: mode 3840x2160@60 not supported"),
+            (MonitorConfig("DP-1", scale=0), "DP-1: scale must be positive, got 0"),
+            (MonitorConfig("DP-1", transform=8), "DP-1: transform must be 0-7, got 8"),
+        ]
+        for config, expected in cases:
+            with self.subTest(config):
+                [problem] = self.problems(config)
+                self.assertTrue(problem.startswith(expected), problem)
+
+    def test_disabling_every_monitor(self):
+        self.assertEqual(
+            self.problems(MonitorConfig("DP-1", enabled=False), MonitorConfig("HDMI-A-1", enabled=False)),
+            ["layout disables every monitor"],
+        )
+        # Another enabled monitor stays on
+        self.assertEqual(self.problems(MonitorConfig("DP-1", enabled=False)), [])
+
+
+class TestApply(unittest.TestCase):
+    """Tests for apply_layout and current_layout."""
+
+    def test_apply_batches_rules(self):
+        configs = [MonitorConfig("DP-1", "1920x1080@60", (0, 0)), MonitorConfig("HDMI-A-1", enabled=False)]
+        with FakeHyprland(reply=monitors_reply(MONITORS)) as hypr:
+            lines = apply_layout(configs)
+        self.assertEqual(lines, ["monitor=DP-1,1920x1080@60,0x0,1", "monitor=HDMI-A-1,disable"])
+        self.assertEqual(
+            hypr.requests[-1],
+            "[[BATCH]]keyword monitor DP-1,1920x1080@60,0x0,1;keyword monitor HDMI-A-1,disable",
+        )
+
+    def test_dry_run(self):
+        with FakeHyprland(reply=monitors_reply(MONITORS)) as hypr:
+            lines = apply_layout([MonitorConfig("DP-1", "1280x720")], dry_run=True)
+        self.assertEqual(lines, ["monitor=DP-1,1280x720,auto,1"])
+        self.assertEqual(hypr.requests, ["j/monitors all"])
+
+    def test_invalid_layout_is_not_applied(self):
+        with FakeHyprland(reply=monitors_reply(MONITORS)) as hypr:
+            with self.assertRaisesRegex(ValueError, "DP-9: no such monitor; DP-1: invalid mode"):
+                apply_layout([MonitorConfig("DP-9"), MonitorConfig("DP-1", "big")])
+        self.assertEqual(hypr.requests, ["j/monitors all"])
+
+    def test_rejected_rule(self):
+        with FakeHyprland(reply=monitors_reply(MONITORS, reject="HDMI-A-1")):
+            with self.assertRaisesRegex(RuntimeError, "^keyword monitor HDMI-A-1,"):
+                apply_layout([MonitorConfig("DP-1"), MonitorConfig("HDMI-A-1")])
+
+    def test_current_layout(self):
+        with FakeHyprland(reply=monitors_reply(MONITORS)):
+            layout = current_layout()
+        self.assertEqual(
+            [c.to_rule() for c in layout],
+            ["DP-1,1920x1080@60.00,0x0,1", "HDMI-A-1,1920x1080@60.00,1920x0,1.5,transform,1"],
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
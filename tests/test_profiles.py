@@ -0,0 +1,108 @@
+"""Tests for saved monitor layout profiles."""
+
+import unittest
+
+from matuwrap.wrp_native import (
+    delete_layout_profile,
+    list_layout_profiles,
+    load_layout_profile,
+    restore_layout_profile,
+    save_layout_profile,
+)
+
+from tests.support import FakeHyprland, isolated_home, monitor, monitors_reply
+
+DELL = {"make": "Dell Inc.", "model": "U2720Q", "serial": "ABC123"}
+LG = {"make": "LG Electronics", "model": "27GL850", "serial": ""}
+
+
+class ProfileTest(unittest.TestCase):
+    """Runs each test with an empty config dir."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+
+    def save(self, name, monitors):
+        with FakeHyprland(reply=monitors_reply(monitors)):
+            return save_layout_profile(name)
+
+    def restore(self, name, monitors, **kwargs):
+        """Restore `name` against `monitors`; returns (lines, requests)."""
+        with FakeHyprland(reply=monitors_reply(monitors)) as hypr:
+            lines = restore_layout_profile(name, **kwargs)
+        return lines, hypr.requests
+
+
+class TestStorage(ProfileTest):
+    """Tests for saving, listing and deleting profiles."""
+
+    def test_save_and_load(self):
+        saved = self.save("desk", [monitor("DP-1", x=0, y=0, **DELL), monitor("DP-2", x=1920, dpmsStatus=False, **LG)])
+        loaded = load_layout_profile("desk")
+        self.assertEqual(loaded.name, "desk")
+        self.assertEqual([m.serial for m in loaded.monitors], ["ABC123", ""])
+        self.assertEqual([m.name for m in loaded.monitors], [m.name for m in saved.monitors])
+        lg = loaded.monitors[1]
+        self.assertEqual((lg.mode, lg.position, lg.dpms), ("1920x1080@60.00", (1920, 0), False))
+        self.assertTrue((self.home / ".config" / "matuwrap" / "layouts" / "desk.json").exists())
+
+    def test_list_and_delete(self):
+        self.assertEqual(list_layout_profiles(), [])
+        self.save("tv", [monitor("HDMI-A-1")])
+        self.save("desk", [monitor("DP-1")])
+        self.assertEqual(list_layout_profiles(), ["desk", "tv"])
+        self.assertTrue(delete_layout_profile("tv"))
+        self.assertFalse(delete_layout_profile("tv"))
+        self.assertEqual(list_layout_profiles(), ["desk"])
+
+    def test_missing(self):
+        with self.assertRaises(FileNotFoundError):
+            load_layout_profile("nope")
+
+    def test_invalid_names(self):
+        for name in ["", ".hidden", "a/b"]:
+            with self.subTest(name=name), self.assertRaises(ValueError):
+                load_layout_profile(name)
+
+    def test_invalid_file(self):
+        path = self.home / ".config" / "matuwrap" / "layouts" / "bad.json"
+        path.parent.mkdir(parents=True)
+        path.write_text("{}")
+        with self.assertRaisesRegex(ValueError, "Invalid layout profile"):
+            load_layout_profile("bad")
+
+
+class TestRestore(ProfileTest):
+    """Tests for matching profile monitors to connected ones."""
+
+    def test_renumbered_connectors(self):
+        self.save("desk", [monitor("DP-1", x=0, **DELL), monitor("DP-2", x=1920, dpmsStatus=False, **LG)])
+        lines, requests = self.restore("desk", [monitor("DP-3", **LG), monitor("DP-4", **DELL)])
+        self.assertEqual(lines, ["monitor=DP-4,1920x1080@60.00,0x0,1", "monitor=DP-3,1920x1080@60.00,1920x0,1"])
+        self.assertEqual(requests[-2:], ["dispatch dpms on DP-4", "dispatch dpms off DP-3"])
+
+    def test_identical_monitors_without_serial(self):
+        self.save("twins", [monitor("DP-1", x=0, **LG), monitor("DP-2", x=1920, **LG)])
+        lines, _ = self.restore("twins", [monitor("DP-5", **LG), monitor("DP-6", **LG)], dry_run=True)
+        self.assertEqual(lines, ["monitor=DP-5,1920x1080@60.00,0x0,1", "monitor=DP-6,1920x1080@60.00,1920x0,1"])
+
+    def test_serial_must_match(self):
+        self.save("desk", [monitor("DP-1", **DELL)])
+        with self.assertRaisesRegex(ValueError, "Dell Inc. U2720Q ABC123: not connected"):
+            self.restore("desk", [monitor("DP-1", **{**DELL, "serial": "XYZ789"})])
+
+    def test_each_monitor_used_once(self):
+        self.save("twins", [monitor("DP-1", **LG), monitor("DP-2", **LG)])
+        with self.assertRaisesRegex(ValueError, "LG Electronics 27GL850: not connected"):
+            self.restore("twins", [monitor("DP-1", **LG)])
+
+    def test_dry_run_sends_nothing(self):
+        self.save("desk", [monitor("DP-1", **DELL)])
+        _, requests = self.restore("desk", [monitor("DP-2", **DELL)], dry_run=True)
+        self.assertTrue(all(r.startswith("j/") for r in requests), requests)
+
+
+if __name__ == "__main__":
+    unittest.main()
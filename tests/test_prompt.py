@@ -0,0 +1,86 @@
+"""Tests for wrp_native.render_prompt."""
+
+import shutil
+import subprocess
+import unittest
+
+from matuwrap.wrp_native import render_prompt
+
+
+def render(shell, fmt, depth="truecolor"):
+    return render_prompt(None, shell, fmt, depth=depth)
+
+
+class TestFormat(unittest.TestCase):
+    """Tests for the format language."""
+
+    def test_default_without_palette(self):
+        """Without a palette the default prompt is plain user@host:cwd."""
+        self.assertEqual(render_prompt(None, "bash"), "\\u@\\h:\\w")
+
+    def test_segments(self):
+        self.assertEqual(render("zsh", "{user}@{host}:{cwd}"), "%n@%m:%~")
+
+    def test_literal_brackets(self):
+        self.assertEqual(render("bash", "[[x]] {{y}}"), "[x]] {y}")
+
+    def test_errors(self):
+        for fmt in ["{nope}", "{git|no placeholder}", "[#12345]x[/]", "[/]", "{user"]:
+            with self.subTest(fmt=fmt), self.assertRaises(ValueError):
+                render("bash", fmt)
+
+    def test_unknown_shell(self):
+        with self.assertRaises(ValueError):
+            render("tcsh", "{user}")
+
+
+class TestColors(unittest.TestCase):
+    """Tests for color tags and depths."""
+
+    def test_depths(self):
+        fmt = "[#ff0000]x[/]"
+        self.assertEqual(render("bash", fmt), "\\[\\033[38;2;255;0;0m\\]x\\[\\033[0m\\]")
+        self.assertEqual(render("bash", fmt, "256"), "\\[\\033[38;5;196m\\]x\\[\\033[0m\\]")
+        self.assertEqual(render("bash", fmt, "16"), "\\[\\033[91m\\]x\\[\\033[0m\\]")
+
+    def test_zsh(self):
+        self.assertEqual(render("zsh", "[#ff0000]x[/]"), "%F{#ff0000}x%f%b")
+        self.assertEqual(render("zsh", "[#ff0000]x[/]", "256"), "%F{196}x%f%b")
+
+    def test_nested_tags_reapply(self):
+        """Closing an inner tag resets and reapplies the outer ones."""
+        self.assertEqual(render("zsh", "[bold][#ff0000]x[/]y[/]"), "%B%F{#ff0000}x%f%b%By%f%b")
+
+    def test_unclosed_tags_reset(self):
+        self.assertEqual(render("zsh", "[bold]x"), "%Bx%f%b")
+
+    def test_fish_16_uses_names(self):
+        self.assertIn("set_color brred", render("fish", "[#ff0000]x", "16"))
+
+
+@unittest.skipIf(shutil.which("bash") is None, "bash not installed")
+class TestBashExpansion(unittest.TestCase):
+    """The bash output should expand to exactly the literal text."""
+
+    def expand(self, ps1):
+        script = 'PS1="$1"; printf "%s" "${PS1@P}"'
+        return subprocess.run(
+            ["bash", "-c", script, "bash", ps1], capture_output=True, text=True, check=True
+        ).stdout
+
+    def test_special_characters(self):
+        text = "$HOME \\ \\\\ `echo no` $(echo no) it's 100% \\n"
+        self.assertEqual(self.expand(render("bash", text)), text)
+
+    def test_git_outside_repository(self):
+        """The git segment and its affixes vanish outside a repository."""
+        result = subprocess.run(
+            ["bash", "-c", 'cd / && PS1="$1"; printf "%s" "${PS1@P}"', "bash",
+             render("bash", "a{git| ($HOME %s)}b")],
+            capture_output=True, text=True, check=True,
+        )
+        self.assertEqual(result.stdout, "ab")
+
+
+if __name__ == "__main__":
+    unittest.main()
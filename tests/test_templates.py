@@ -0,0 +1,160 @@
+"""Tests for template placeholders, filters and rendering to outputs."""
+
+import json
+import unittest
+
+from matuwrap.wrp_native import extract_palette, render_template, render_templates
+
+from tests.support import isolated_home, write_png
+
+
+class TemplateTest(unittest.TestCase):
+    """Runs each test with an empty home and a red wallpaper's palette."""
+
+    def setUp(self):
+        home = isolated_home()
+        self.home = home.__enter__()
+        self.addCleanup(home.__exit__, None, None, None)
+        self.wall = str(write_png(self.home / "wall.png", (0xC0, 0x30, 0x30)))
+        self.palette = extract_palette(self.wall)
+
+    def render(self, template, mode="dark", **kwargs):
+        return render_template(template, self.palette, mode, **kwargs)
+
+
+class TestPlaceholders(TemplateTest):
+    """Tests for placeholder lookups and formats."""
+
+    def test_variants(self):
+        dark, light = self.palette.dark.primary, self.palette.light.primary
+        self.assertEqual(self.render("{{colors.primary.default.hex}}"), dark)
+        self.assertEqual(self.render("{{colors.primary.default.hex}}", mode="light"), light)
+        self.assertEqual(self.render("{{ colors.primary.light.hex }} {{colors.primary.dark}}"), f"{light} {dark}")
+
+    def test_formats(self):
+        hex = self.palette.dark.primary
+        r, g, b = (int(hex[i : i + 2], 16) for i in (1, 3, 5))
+        cases = {
+            "hex_stripped": hex[1:],
+            "rgb": f"rgb({r}, {g}, {b})",
+            "rgba": f"rgba({r}, {g}, {b}, 1)",
+        }
+        for format, expected in cases.items():
+            with self.subTest(format):
+                self.assertEqual(self.render(f"{{{{colors.primary.default.{format}}}}}"), expected)
+
+    def test_hsl(self):
+        self.assertEqual(self.render("{{colors.surface.dark.hsl}}")[:4], "hsl(")
+        palette = extract_palette(str(write_png(self.home / "grey.png", (0x80, 0x80, 0x80))))
+        black = render_template("{{colors.surface.dark.hsla | darken: 100}}", palette)
+        self.assertEqual(black, "hsla(0, 0%, 0%, 1)")
+
+    def test_mode_and_image(self):
+        self.assertEqual(self.render("{{mode}} {{image}}", mode="light", image="/w.png"), "light /w.png")
+
+    def test_text_around_placeholders(self):
+        template = "a {\n  color: {{colors.primary.default.hex}};\n}\n"
+        self.assertEqual(self.render(template), f"a {{\n  color: {self.palette.dark.primary};\n}}\n")
+
+
+class TestFilters(TemplateTest):
+    """Tests for `|` filters."""
+
+    def test_lighten_and_darken(self):
+        self.assertEqual(self.render("{{colors.primary.default.hex | lighten: 100}}"), "#ffffff")
+        self.assertEqual(self.render("{{colors.primary.default.hex | darken: 100}}"), "#000000")
+        self.assertEqual(self.render("{{colors.primary.default.hex | lighten: 0}}"), self.palette.dark.primary)
+
+    def test_alpha(self):
+        hex = self.palette.dark.primary
+        self.assertEqual(self.render("{{colors.primary.default.hex | alpha: 0.5}}"), f"{hex}80")
+        self.assertTrue(self.render("{{colors.primary.default | alpha: 0.25 | rgba}}").endswith(", 0.25)"))
+
+    def test_format_filter(self):
+        hex = self.palette.dark.primary
+        self.assertEqual(self.render("{{colors.primary.default.rgb | hex_stripped}}"), hex[1:])
+
+
+class TestErrors(TemplateTest):
+    """Tests for invalid templates."""
+
+    def test_errors(self):
+        cases = [
+            ("{{colors.nope.default.hex}}", 'line 1: Unknown color role "nope"'),
+            ("{{colors.primary.dim.hex}}", 'line 1: Unknown variant "dim"'),
+            ("{{colors.primary.default.cmyk}}", 'line 1: Unknown format "cmyk"'),
+            ("{{colors.primary.default | blur: 3}}", 'line 1: Unknown filter "blur"'),
+            ("{{colors.primary.default | lighten}}", 'line 1: Filter "lighten" needs a numeric argument'),
+            ("{{colors.primary.default | alpha: 2}}", "line 1: Alpha must be between 0 and 1"),
+            ("{{colors.primary.default | rgb: 1}}", 'line 1: Filter "rgb" takes no argument'),
+            ("{{mode | lighten: 5}}", 'line 1: Filter "lighten" needs a color'),
+            ("{{image}}", "line 1: No image"),
+            ("{{wallpaper}}", 'line 1: Unknown placeholder "wallpaper"'),
+            ("ok\n\n{{colors.primary", "line 3: Unclosed {{"),
+        ]
+        for template, message in cases:
+            with self.subTest(template), self.assertRaises(ValueError) as raised:
+                self.render(template)
+            self.assertTrue(str(raised.exception).startswith(message), raised.exception)
+
+    def test_unknown_mode(self):
+        with self.assertRaises(ValueError):
+            self.render("{{mode}}", mode="dim")
+
+
+class TestRenderTemplates(TemplateTest):
+    """Tests for render_templates and templates.json."""
+
+    def setUp(self):
+        super().setUp()
+        self.templates = self.home / ".config" / "matuwrap" / "templates"
+        self.templates.mkdir(parents=True)
+        (self.templates / "colors.txt").write_text("{{colors.primary.default.hex}}\n")
+
+    def write_manifest(self, **templates):
+        (self.templates / "templates.json").write_text(json.dumps(templates))
+
+    def test_renders_only_changes(self):
+        self.write_manifest(colors={"input": "colors.txt", "output": "~/out/colors.txt"})
+        (self.home / "out").mkdir()
+        [first] = render_templates(self.wall, mode="dark")
+        self.assertEqual(first.output, str(self.home / "out" / "colors.txt"))
+        self.assertEqual((first.changed, first.error), (True, None))
+        self.assertEqual((self.home / "out" / "colors.txt").read_text(), f"{self.palette.dark.primary}\n")
+        [second] = render_templates(self.wall, mode="dark")
+        self.assertFalse(second.changed)
+
+    def test_dry_run(self):
+        self.write_manifest(colors={"input": "colors.txt", "output": "out.txt"})
+        [result] = render_templates(self.wall, dry_run=True)
+        self.assertTrue(result.changed)
+        self.assertFalse((self.templates / "out.txt").exists())
+
+    def test_symlinked_output_is_written_through(self):
+        self.write_manifest(colors={"input": "colors.txt", "output": "~/link.txt"})
+        (self.home / "link.txt").symlink_to(self.home / "real.txt")
+        render_templates(self.wall, mode="light")
+        self.assertTrue((self.home / "link.txt").is_symlink())
+        self.assertEqual((self.home / "real.txt").read_text(), f"{self.palette.light.primary}\n")
+
+    def test_failing_template_does_not_stop_others(self):
+        (self.templates / "bad.txt").write_text("{{colors.nope.default}}")
+        self.write_manifest(
+            bad={"input": "bad.txt", "output": "bad.out"},
+            good={"input": "colors.txt", "output": "good.out"},
+        )
+        bad, good = render_templates(self.wall)
+        self.assertIn("line 1: Unknown color role", bad.error)
+        self.assertTrue(good.changed)
+
+    def test_no_manifest(self):
+        self.assertEqual(render_templates(self.wall), [])
+
+    def test_invalid_manifest(self):
+        self.write_manifest(colors={"input": "colors.txt", "outptu": "x"})
+        with self.assertRaisesRegex(ValueError, "Invalid template manifest"):
+            render_templates(self.wall)
+
+
+if __name__ == "__main__":
+    unittest.main()
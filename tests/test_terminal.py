@@ -0,0 +1,65 @@
+"""Tests for terminal color depth detection and quantization."""
+
+import os
+import unittest
+from unittest import mock
+
+from matuwrap.wrp_native import ansi_escape, detect_color_depth, render_prompt
+
+
+class TestQuantization(unittest.TestCase):
+    """Tests for the nearest xterm colors."""
+
+    def test_exact_colors(self):
+        """Colors in the xterm tables map to themselves."""
+        self.assertEqual(ansi_escape("#5f87af", "256"), "\x1b[38;5;67m")
+        self.assertEqual(ansi_escape("#808080", "256"), "\x1b[38;5;244m")
+        self.assertEqual(ansi_escape("#ff0000", "16"), "\x1b[91m")
+
+    def test_perceptual_nearest(self):
+        """A light pink is nearer to light gray than to red on 16 colors."""
+        self.assertEqual(ansi_escape("#ffb2b7", "256"), "\x1b[38;5;217m")
+        self.assertEqual(ansi_escape("#ffb2b7", "16"), "\x1b[37m")
+
+    def test_truecolor_and_none(self):
+        self.assertEqual(ansi_escape("#ffb2b7", "truecolor"), "\x1b[38;2;255;178;183m")
+        self.assertEqual(ansi_escape("#ffb2b7", "none"), "")
+
+    def test_invalid(self):
+        for color, depth in [("#12345", "256"), ("#ffffff", "88")]:
+            with self.subTest(color=color, depth=depth), self.assertRaises(ValueError):
+                ansi_escape(color, depth)
+
+
+class TestDetection(unittest.TestCase):
+    """Tests for detect_color_depth."""
+
+    def detect(self, **env):
+        clean = {k: v for k, v in os.environ.items() if k not in ("NO_COLOR", "COLORTERM", "TERM")}
+        with mock.patch.dict(os.environ, {**clean, **env}, clear=True):
+            return detect_color_depth()
+
+    def test_terms(self):
+        cases = [
+            ({"COLORTERM": "truecolor", "TERM": "tmux-256color"}, "truecolor"),
+            ({"TERM": "foot"}, "truecolor"),
+            ({"TERM": "tmux-256color"}, "256"),
+            ({"TERM": "linux"}, "16"),
+            ({}, "16"),
+            ({"TERM": "dumb"}, "none"),
+        ]
+        for env, depth in cases:
+            with self.subTest(env=env):
+                self.assertEqual(self.detect(**env), depth)
+
+    def test_no_color(self):
+        """NO_COLOR wins when set and not empty."""
+        self.assertEqual(self.detect(NO_COLOR="1", COLORTERM="truecolor"), "none")
+        self.assertEqual(self.detect(NO_COLOR="", TERM="xterm-256color"), "256")
+
+    def test_prompt_without_color_keeps_bold(self):
+        self.assertEqual(render_prompt(None, "zsh", "[bold][#ff0000]x[/][/]", depth="none"), "%Bx%f%b")
+
+
+if __name__ == "__main__":
+    unittest.main()
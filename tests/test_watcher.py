@@ -0,0 +1,111 @@
+"""Tests for wrp_native.WallpaperWatcher."""
+
+import threading
+import time
+import unittest
+
+from matuwrap.wrp_native import WallpaperWatcher
+
+from tests.support import isolated_home, write_png
+
+
+class WatcherTest(unittest.TestCase):
+    """Sets up a home with a wallpaper link to one of two images."""
+
+    def setUp(self):
+        self.home_context = isolated_home()
+        self.home = self.home_context.__enter__()
+        self.addCleanup(self.home_context.__exit__, None, None, None)
+        self.red = write_png(self.home / "red.png", (0xC0, 0x30, 0x30))
+        self.green = write_png(self.home / "green.png", (0x30, 0x90, 0x40))
+        self.link = self.home / ".current.wall"
+        self.link.symlink_to(self.red)
+
+    def watcher(self, **kwargs):
+        kwargs.setdefault("debounce", 0.05)
+        watcher = WallpaperWatcher(self.link, **kwargs)
+        self.addCleanup(watcher.close)
+        return watcher
+
+    def relink(self, target, delay=0.1):
+        """Re-point the link to `target` from a thread after `delay` seconds."""
+
+        def swap():
+            time.sleep(delay)
+            tmp = self.home / ".current.wall.tmp"
+            tmp.symlink_to(target)
+            tmp.replace(self.link)
+
+        thread = threading.Thread(target=swap)
+        thread.start()
+        self.addCleanup(thread.join)
+
+
+class TestChanges(WatcherTest):
+    """Tests for the changes a watcher reports."""
+
+    def test_applies_on_start(self):
+        change = next(self.watcher())
+        self.assertEqual(change.wallpaper, str(self.red))
+        self.assertIsNone(change.error)
+        self.assertIsNotNone(change.palette)
+
+    def test_shell_cache(self):
+        change = next(self.watcher(render=False))
+        cache = self.home / ".cache" / "matuwrap"
+        self.assertIn(str(cache / "ps1"), change.shell_cache)
+        self.assertEqual((cache / "color").read_text().strip(), change.palette.dark.primary)
+
+    def test_relink(self):
+        watcher = self.watcher(apply_on_start=False)
+        self.relink(self.green)
+        change = next(watcher)
+        self.assertEqual(change.wallpaper, str(self.green))
+
+    def test_invalid_options(self):
+        with self.assertRaises(ValueError):
+            WallpaperWatcher(self.link, scheme_type="scheme-nope")
+        with self.assertRaises(ValueError):
+            WallpaperWatcher(self.link, debounce=-1)
+
+
+class TestClose(WatcherTest):
+    """Tests for closing a watcher, including while it waits."""
+
+    def test_close_stops_iteration(self):
+        watcher = self.watcher()
+        watcher.close()
+        self.assertEqual(list(watcher), [])
+
+    def test_close_from_callback(self):
+        watcher = self.watcher()
+        changes = []
+
+        def callback(change):
+            changes.append(change)
+            watcher.close()
+
+        watcher.run(callback)
+        self.assertEqual([c.wallpaper for c in changes], [str(self.red)])
+
+    def test_close_from_another_thread(self):
+        watcher = self.watcher(apply_on_start=False)
+        threading.Timer(0.1, watcher.close).start()
+        start = time.monotonic()
+        watcher.run(self.fail)
+        self.assertLess(time.monotonic() - start, 2)
+
+    def test_concurrent_wait(self):
+        watcher = self.watcher(apply_on_start=False)
+        thread = threading.Thread(target=watcher.run, args=(self.fail,))
+        thread.start()
+        time.sleep(0.1)
+        with self.assertRaises(RuntimeError):
+            next(watcher)
+        watcher.close()
+        thread.join(2)
+        self.assertFalse(thread.is_alive())
+
+
+if __name__ == "__main__":
+    unittest.main()